@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
 
 /// An ability score (1-30).
 pub type Score = i32;
@@ -18,7 +19,7 @@ pub fn score_to_modifier(score: Score) -> Modifier {
 ///
 /// It can represent the ability scores themselves, the ability score modifiers, or any other
 /// numerical values related to abilities, depending on the parameter chosen for the type `T`.
-#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Ability<T> {
     pub strength: T,
     pub dexterity: T,
@@ -42,8 +43,93 @@ impl Ability<Score> {
     }
 }
 
+impl<T> Ability<T> {
+    /// Returns the value for the given ability.
+    pub fn get(&self, kind: AbilityKind) -> &T {
+        match kind {
+            AbilityKind::Strength => &self.strength,
+            AbilityKind::Dexterity => &self.dexterity,
+            AbilityKind::Constitution => &self.constitution,
+            AbilityKind::Intelligence => &self.intelligence,
+            AbilityKind::Wisdom => &self.wisdom,
+            AbilityKind::Charisma => &self.charisma,
+        }
+    }
+}
+
+/// One of the six abilities.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AbilityKind {
+    Strength,
+    Dexterity,
+    Constitution,
+    Intelligence,
+    Wisdom,
+    Charisma,
+}
+
+impl AbilityKind {
+    /// All six abilities, in the order they appear in a stat block.
+    pub const ALL: [AbilityKind; 6] = [
+        AbilityKind::Strength,
+        AbilityKind::Dexterity,
+        AbilityKind::Constitution,
+        AbilityKind::Intelligence,
+        AbilityKind::Wisdom,
+        AbilityKind::Charisma,
+    ];
+
+    /// Returns the three-letter abbreviation of the ability, such as `"DEX"`.
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            AbilityKind::Strength => "STR",
+            AbilityKind::Dexterity => "DEX",
+            AbilityKind::Constitution => "CON",
+            AbilityKind::Intelligence => "INT",
+            AbilityKind::Wisdom => "WIS",
+            AbilityKind::Charisma => "CHA",
+        }
+    }
+}
+
+impl fmt::Display for AbilityKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.abbreviation())
+    }
+}
+
+/// An error returned when parsing an unknown ability or skill name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseAbilityError(pub String);
+
+impl fmt::Display for ParseAbilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown ability or skill: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseAbilityError {}
+
+/// Parses an ability from its full name or its abbreviation, ignoring case, such as `"dex"` or
+/// `"Dexterity"`.
+impl FromStr for AbilityKind {
+    type Err = ParseAbilityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        AbilityKind::ALL
+            .into_iter()
+            .find(|kind| {
+                kind.abbreviation().eq_ignore_ascii_case(name)
+                    || format!("{:?}", kind).eq_ignore_ascii_case(name)
+            })
+            .ok_or_else(|| ParseAbilityError(s.to_string()))
+    }
+}
+
 /// A type that packs together all skills.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Skill<T> {
     /// Acrobatics (Dexterity).
     pub acrobatics: T,
@@ -99,3 +185,84 @@ pub struct Skill<T> {
     /// Survival (Wisdom).
     pub survival: T,
 }
+
+macro_rules! skills {
+    ($($kind:ident, $field:ident, $name:expr => $ability:ident),* $(,)?) => {
+        /// One of the eighteen skills.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum SkillKind {
+            $(
+                #[doc = concat!($name, " (", stringify!($ability), ").")]
+                $kind,
+            )*
+        }
+
+        impl SkillKind {
+            /// All eighteen skills, in alphabetical order.
+            pub const ALL: [SkillKind; 18] = [$(SkillKind::$kind,)*];
+
+            /// Returns the name of the skill, such as `"Sleight of Hand"`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(SkillKind::$kind => $name,)*
+                }
+            }
+
+            /// Returns the ability the skill is based on.
+            pub fn ability(&self) -> AbilityKind {
+                match self {
+                    $(SkillKind::$kind => AbilityKind::$ability,)*
+                }
+            }
+        }
+
+        impl<T> Skill<T> {
+            /// Returns the value for the given skill.
+            pub fn get(&self, kind: SkillKind) -> &T {
+                match kind {
+                    $(SkillKind::$kind => &self.$field,)*
+                }
+            }
+        }
+    };
+}
+
+skills! {
+    Acrobatics, acrobatics, "Acrobatics" => Dexterity,
+    AnimalHandling, animal_handling, "Animal Handling" => Wisdom,
+    Arcana, arcana, "Arcana" => Intelligence,
+    Athletics, athletics, "Athletics" => Strength,
+    Deception, deception, "Deception" => Charisma,
+    History, history, "History" => Intelligence,
+    Insight, insight, "Insight" => Wisdom,
+    Intimidation, intimidation, "Intimidation" => Charisma,
+    Investigation, investigation, "Investigation" => Intelligence,
+    Medicine, medicine, "Medicine" => Wisdom,
+    Nature, nature, "Nature" => Intelligence,
+    Perception, perception, "Perception" => Wisdom,
+    Performance, performance, "Performance" => Charisma,
+    Persuasion, persuasion, "Persuasion" => Charisma,
+    Religion, religion, "Religion" => Intelligence,
+    SleightOfHand, sleight_of_hand, "Sleight of Hand" => Dexterity,
+    Stealth, stealth, "Stealth" => Dexterity,
+    Survival, survival, "Survival" => Wisdom,
+}
+
+impl fmt::Display for SkillKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Parses a skill from its name, ignoring case, such as `"stealth"` or `"sleight-of-hand"`.
+impl FromStr for SkillKind {
+    type Err = ParseAbilityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().replace(['-', '_'], " ");
+        SkillKind::ALL
+            .into_iter()
+            .find(|kind| kind.name().eq_ignore_ascii_case(&name))
+            .ok_or_else(|| ParseAbilityError(s.to_string()))
+    }
+}
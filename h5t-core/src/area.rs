@@ -0,0 +1,146 @@
+//! Area effects, such as Fireball, that deal damage to each target based on their saving throw.
+
+use crate::{dice, AbilityKind, Check, Combatant, Resistance, Tracker};
+use rand::Rng;
+
+/// A damaging effect that each target makes a saving throw against, taking full damage on a
+/// failure and half as much on a success.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AreaEffect {
+    /// The damage rolled for the effect, shared by every target.
+    pub damage: i32,
+
+    /// The type of damage, such as `"fire"`, used to apply resistances. Empty for none.
+    pub damage_type: String,
+
+    /// The ability used for the saving throw.
+    pub ability: Option<AbilityKind>,
+
+    /// The saving throw's difficulty class.
+    pub dc: i32,
+}
+
+/// The result of one target's saving throw against an [`AreaEffect`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TargetSave {
+    /// The index of the target in the tracker.
+    pub combatant: usize,
+
+    /// The total of the target's saving throw.
+    pub total: i32,
+
+    /// Whether the saving throw succeeded.
+    pub success: bool,
+
+    /// Whether the target has Evasion, taking no damage on a success and half on a failure.
+    pub evasion: bool,
+
+    /// How the target responds to the effect's damage type.
+    pub resistance: Resistance,
+
+    /// The damage the target takes.
+    pub damage: i32,
+}
+
+impl AreaEffect {
+    /// Resolve a target's saving throw with the given total.
+    ///
+    /// Monsters with the Evasion trait use it against Dexterity saving throws.
+    pub fn resolve(&self, tracker: &Tracker, combatant: usize, total: i32) -> TargetSave {
+        let target = &tracker.combatants[combatant];
+        let evasion = self.ability == Some(AbilityKind::Dexterity) && target.has_trait("Evasion");
+        let mut save = TargetSave {
+            combatant,
+            total,
+            success: total >= self.dc,
+            evasion,
+            resistance: target.resistance(&self.damage_type),
+            damage: 0,
+        };
+        save.damage = self.damage_for(&save);
+        save
+    }
+
+    /// Roll a target's saving throw and resolve it.
+    ///
+    /// The target's conditions can give them disadvantage, or make them fail automatically.
+    pub fn roll(&self, tracker: &Tracker, combatant: usize, rng: &mut impl Rng) -> TargetSave {
+        let Some(ability) = self.ability else {
+            return self.resolve(tracker, combatant, dice::d20(rng) as i32);
+        };
+        let roll = tracker.combatants[combatant].roll_check(Check::Save(ability), rng);
+        let mut save = self.resolve(tracker, combatant, roll.total);
+        if roll.auto_fail {
+            save.success = false;
+            save.damage = self.damage_for(&save);
+        }
+        save
+    }
+
+    /// Returns the target's modifier for the effect's saving throw.
+    pub fn modifier(&self, combatant: &Combatant) -> i32 {
+        self.ability
+            .map(|ability| combatant.saving_throw(ability))
+            .unwrap_or_default()
+    }
+
+    /// Returns the damage a target takes, given the outcome of their saving throw.
+    pub fn damage_for(&self, save: &TargetSave) -> i32 {
+        let damage = match (save.success, save.evasion) {
+            (false, false) => self.damage,
+            (true, false) | (false, true) => self.damage / 2,
+            (true, true) => 0,
+        };
+        save.resistance.apply(damage)
+    }
+}
+
+impl Tracker {
+    /// Apply the damage of each resolved saving throw to its target, with [`Tracker::deal_damage`].
+    pub fn apply_saves(&mut self, saves: &[TargetSave]) {
+        for save in saves {
+            self.deal_damage(save.combatant, save.damage);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{monster::Trait, Monster};
+
+    /// Ensure saves, Evasion, and resistances each change the damage taken.
+    #[test]
+    fn fireball() {
+        let monster = |resistance: &str, trait_name: &str| Combatant::from(Monster {
+            hit_points: 50,
+            damage_resistances: vec![resistance.to_string()],
+            traits: vec![Trait { name: trait_name.to_string(), ..Default::default() }],
+            ..Default::default()
+        });
+        let mut tracker = Tracker::new(vec![
+            monster("", ""),
+            monster("fire", ""),
+            monster("", "Evasion"),
+        ]);
+        let fireball = AreaEffect {
+            damage: 28,
+            damage_type: "Fire".to_string(),
+            ability: Some(AbilityKind::Dexterity),
+            dc: 15,
+        };
+
+        let saves = vec![
+            fireball.resolve(&tracker, 0, 10),
+            fireball.resolve(&tracker, 1, 10),
+            fireball.resolve(&tracker, 2, 15),
+        ];
+        assert_eq!(saves.iter().map(|save| save.damage).collect::<Vec<_>>(), [28, 14, 0]);
+        assert_eq!(fireball.resolve(&tracker, 0, 15).damage, 14);
+        assert_eq!(fireball.resolve(&tracker, 2, 10).damage, 14);
+
+        tracker.apply_saves(&saves);
+        assert_eq!(tracker.combatants[0].hit_points, 22);
+        assert_eq!(tracker.combatants[1].hit_points, 36);
+    }
+}
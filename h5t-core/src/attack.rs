@@ -0,0 +1,276 @@
+//! Attack rolls made with a monster's actions.
+
+use crate::{
+    dice::{Advantage, Expr, Term},
+    monster::Action,
+    Combatant,
+    ConditionKind,
+    Resistance,
+    Tracker,
+};
+use rand::Rng;
+
+/// The damage of one damage type dealt by an attack.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DamageRoll {
+    /// The type of damage, such as `"slashing"`.
+    pub damage_type: String,
+
+    /// The damage rolled, including modifiers.
+    pub amount: i32,
+
+    /// The extra damage rolled if the attack is a critical hit: the damage dice rolled again,
+    /// without modifiers.
+    pub critical: i32,
+
+    /// How the target responds to the damage type.
+    pub resistance: Resistance,
+}
+
+/// The result of one attack roll against one target.
+///
+/// Every part of the roll is public so the DM can overrule it before the attack is applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttackRoll {
+    /// The index of the target in the tracker.
+    pub target: usize,
+
+    /// Whether the roll had advantage or disadvantage.
+    pub advantage: Advantage,
+
+    /// The d20 roll that counts.
+    pub roll: u32,
+
+    /// The d20 roll that was dropped because of advantage or disadvantage, if any.
+    pub dropped: Option<u32>,
+
+    /// The total attack roll.
+    pub total: i32,
+
+    /// The target's armor class.
+    pub armor_class: u32,
+
+    /// Whether the attack hits.
+    pub hit: bool,
+
+    /// Whether the attack is a critical hit.
+    pub critical: bool,
+
+    /// The damage dealt if the attack hits.
+    pub damage: Vec<DamageRoll>,
+
+    /// A manual adjustment to the total damage.
+    pub adjustment: i32,
+
+    /// For an attack by a mob, the number of hits its members landed, whose damage is all
+    /// included in [`AttackRoll::damage`]. This is [`None`] for a single attack roll.
+    pub mob_hits: Option<u32>,
+}
+
+impl AttackRoll {
+    /// Returns the total damage the target takes, after resistances.
+    pub fn total_damage(&self) -> i32 {
+        if !self.hit {
+            return 0;
+        }
+        let damage = self.damage
+            .iter()
+            .map(|damage| {
+                let amount = damage.amount + if self.critical { damage.critical } else { 0 };
+                damage.resistance.apply(amount.max(0))
+            })
+            .sum::<i32>();
+        (damage + self.adjustment).max(0)
+    }
+}
+
+/// Returns whether an attack by the attacker against the target has advantage or disadvantage,
+/// based on their conditions.
+pub fn attack_advantage(attacker: &Combatant, target: &Combatant, melee: bool) -> Advantage {
+    let attacker_conditions = attacker.condition_kinds().into_iter().map(|kind| match kind {
+        ConditionKind::Blinded
+            | ConditionKind::Frightened
+            | ConditionKind::Poisoned
+            | ConditionKind::Prone
+            | ConditionKind::Restrained => Advantage::Disadvantage,
+        ConditionKind::Invisible => Advantage::Advantage,
+        _ => Advantage::Normal,
+    });
+    let target_conditions = target.condition_kinds().into_iter().map(|kind| match kind {
+        ConditionKind::Blinded
+            | ConditionKind::Paralyzed
+            | ConditionKind::Petrified
+            | ConditionKind::Restrained
+            | ConditionKind::Stunned
+            | ConditionKind::Unconscious => Advantage::Advantage,
+        ConditionKind::Prone if melee => Advantage::Advantage,
+        ConditionKind::Prone | ConditionKind::Invisible => Advantage::Disadvantage,
+        _ => Advantage::Normal,
+    });
+    // exhaustion level 3 gives disadvantage on attack rolls
+    let exhaustion = (attacker.exhaustion >= 3).then_some(Advantage::Disadvantage);
+
+    Advantage::combine(attacker_conditions.chain(target_conditions).chain(exhaustion))
+}
+
+/// Roll only the dice of the expression, ignoring constants, as is done for critical hits.
+fn roll_dice_only(expr: &Expr, rng: &mut impl Rng) -> i32 {
+    let dice = Expr {
+        terms: expr.terms
+            .iter()
+            .filter(|term| matches!(term, Term::Dice { .. }))
+            .cloned()
+            .collect(),
+    };
+    dice.roll(rng).total
+}
+
+impl Tracker {
+    /// Roll an attack with the given action from one combatant against another.
+    ///
+    /// Advantage and disadvantage come from both combatants' conditions. A natural 20 is a critical
+    /// hit and a natural 1 always misses; melee hits against paralyzed or unconscious targets are
+    /// also critical hits.
+    pub fn roll_attack(
+        &self,
+        attacker: usize,
+        action: &Action,
+        target: usize,
+        rng: &mut impl Rng,
+    ) -> AttackRoll {
+        let melee = action.is_melee();
+        let target_combatant = &self.combatants[target];
+        let advantage = attack_advantage(&self.combatants[attacker], target_combatant, melee);
+        let (roll, dropped) = advantage.roll(rng);
+        let total = roll as i32 + action.attack_bonus.unwrap_or_default();
+        let armor_class = target_combatant.armor_class();
+        let hit = roll == 20 || (roll != 1 && total >= armor_class as i32);
+        let critical = hit && (roll == 20 || melee && target_combatant.condition_kinds()
+            .into_iter()
+            .any(|kind| matches!(kind, ConditionKind::Paralyzed | ConditionKind::Unconscious)));
+
+        let damage = action.damage
+            .iter()
+            .filter_map(|damage| {
+                let expr = damage.dice.parse::<Expr>().ok()?;
+                Some(DamageRoll {
+                    damage_type: damage.damage_type.clone(),
+                    amount: expr.roll(rng).total,
+                    critical: roll_dice_only(&expr, rng),
+                    resistance: target_combatant.resistance(&damage.damage_type),
+                })
+            })
+            .collect();
+
+        AttackRoll {
+            target,
+            advantage,
+            roll,
+            dropped,
+            total,
+            armor_class,
+            hit,
+            critical,
+            damage,
+            adjustment: 0,
+            mob_hits: None,
+        }
+    }
+
+    /// Apply the damage of each attack roll to its target, and write the attacks to the log. The
+    /// damage and any critical hits are credited to the attacker.
+    pub fn apply_attacks(&mut self, attacker: usize, action: &Action, rolls: &[AttackRoll]) {
+        for attack in rolls {
+            let damage = attack.total_damage();
+            self.deal_damage_from(Some(attacker), attack.target, damage);
+            if attack.hit && attack.critical {
+                self.combatants[attacker].stats.crits += 1;
+            }
+
+            let verb = match (attack.hit, attack.critical) {
+                (false, _) => "misses",
+                (true, false) => "hits",
+                (true, true) => "critically hits",
+            };
+            let mut message = match attack.mob_hits {
+                Some(hits) => format!(
+                    "{} hit {} {} time{} with {} (vs AC {})",
+                    self.combatants[attacker].name(),
+                    self.combatants[attack.target].name(),
+                    hits,
+                    if hits == 1 { "" } else { "s" },
+                    action.name,
+                    attack.armor_class,
+                ),
+                None => format!(
+                    "{} {} {} with {} ({} vs AC {})",
+                    self.combatants[attacker].name(),
+                    verb,
+                    self.combatants[attack.target].name(),
+                    action.name,
+                    attack.total,
+                    attack.armor_class,
+                ),
+            };
+            if attack.hit {
+                message.push_str(&format!(": {}", damage));
+                let types = attack.damage
+                    .iter()
+                    .map(|damage| damage.damage_type.as_str())
+                    .filter(|damage_type| !damage_type.is_empty())
+                    .collect::<Vec<_>>();
+                if !types.is_empty() {
+                    message.push_str(&format!(" {}", types.join(" and ")));
+                }
+                message.push_str(" damage");
+            }
+            self.log(message);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{monster::Damage, Condition, ConditionDuration, Monster, Player};
+
+    /// Ensure conditions give advantage, and hits deal damage and are logged.
+    #[test]
+    fn attack() {
+        let mut tracker = Tracker::new(vec![
+            Combatant::from(Monster { name: "Goblin".to_string(), ..Default::default() }),
+            Combatant::from(Player {
+                name: "Thorin".to_string(),
+                armor_class: 1,
+                max_hit_points: 30,
+                ..Default::default()
+            }),
+        ]);
+        tracker.combatants[1].apply_condition(Condition {
+            kind: ConditionKind::Prone,
+            duration: ConditionDuration::Forever,
+        });
+        let scimitar = Action {
+            name: "Scimitar".to_string(),
+            desc: "Melee Weapon Attack: +4 to hit".to_string(),
+            attack_bonus: Some(4),
+            damage: vec![Damage { dice: "1d6+2".to_string(), damage_type: "slashing".to_string() }],
+        };
+
+        let attack = tracker.roll_attack(0, &scimitar, 1, &mut rand::thread_rng());
+        assert_eq!(attack.advantage, Advantage::Advantage);
+        assert_eq!(attack.hit, attack.roll != 1);
+        assert!(attack.damage[0].amount >= 3 && attack.damage[0].critical >= 1);
+
+        let mut attack = attack;
+        attack.hit = true;
+        attack.critical = false;
+        attack.adjustment = 1;
+        let damage = attack.total_damage();
+        tracker.apply_attacks(0, &scimitar, &[attack]);
+        assert_eq!(tracker.combatants[1].hit_points, 30 - damage);
+        let message = &tracker.log[0].message;
+        assert!(message.starts_with("Goblin hits Thorin with Scimitar ("));
+        assert!(message.ends_with(&format!("vs AC 1): {} slashing damage", damage)));
+    }
+}
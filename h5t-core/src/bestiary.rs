@@ -0,0 +1,590 @@
+//! A collection of monsters, indexed by their [`Monster::index`], that can be searched and
+//! filtered.
+//!
+//! Monsters can be loaded in layers from several files and directories using [`Bestiary::load`].
+//! Later layers override earlier ones by index, which allows homebrew monsters to replace or
+//! extend the SRD.
+
+use crate::{monster::{Monster, Size, Type}, ChallengeRating};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::Read,
+    ops::{Bound, RangeBounds},
+    path::{Path, PathBuf},
+};
+
+/// An error that occurred while loading monsters into a [`Bestiary`].
+#[derive(Debug)]
+pub enum LoadError {
+    /// A file or directory could not be read.
+    Io {
+        /// The path that could not be read.
+        path: PathBuf,
+
+        /// The underlying I/O error.
+        error: std::io::Error,
+    },
+
+    /// A file does not contain valid JSON.
+    Json {
+        /// The file containing the invalid JSON.
+        path: PathBuf,
+
+        /// The underlying JSON error.
+        error: serde_json::Error,
+    },
+
+    /// A monster in a file could not be parsed.
+    Monster {
+        /// The file containing the monster.
+        path: PathBuf,
+
+        /// The JSON path to the invalid value, such as `[3].armor_class`.
+        json_path: String,
+
+        /// The monster's name, if one could be found.
+        name: Option<String>,
+
+        /// The underlying JSON error.
+        error: serde_json::Error,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            LoadError::Json { path, error } => write!(f, "{}: {}", path.display(), error),
+            LoadError::Monster { path, json_path, name, error } => {
+                write!(f, "{}: at `{}`", path.display(), json_path)?;
+                if let Some(name) = name {
+                    write!(f, " (monster \"{}\")", name)?;
+                }
+                write!(f, ": {}", error)
+            },
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io { error, .. } => Some(error),
+            LoadError::Json { error, .. } | LoadError::Monster { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Parses monsters from JSON text, which can contain either a single monster object or an array of
+/// monsters. Errors are attributed to the given path.
+fn parse_monsters(path: &Path, text: &str) -> Result<Vec<Monster>, LoadError> {
+    let value = serde_json::from_str::<Value>(text)
+        .map_err(|error| LoadError::Json { path: path.to_owned(), error })?;
+
+    let (values, is_array) = match value {
+        Value::Array(values) => (values, true),
+        value => (vec![value], false),
+    };
+
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let name = value.get("name").and_then(Value::as_str).map(str::to_owned);
+            serde_path_to_error::deserialize(value).map_err(|error| {
+                let inner_path = error.path().to_string();
+                let json_path = match (is_array, inner_path.as_str()) {
+                    (true, ".") => format!("[{}]", i),
+                    (true, _) => format!("[{}].{}", i, inner_path),
+                    (false, _) => inner_path,
+                };
+                LoadError::Monster {
+                    path: path.to_owned(),
+                    json_path,
+                    name,
+                    error: error.into_inner(),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Reads the monsters in a JSON file, which can contain either a single monster object or an array
+/// of monsters, in either the SRD API format or the native format.
+pub fn read_monsters(path: impl AsRef<Path>) -> Result<Vec<Monster>, LoadError> {
+    let path = path.as_ref();
+    let text = std::fs::read_to_string(path)
+        .map_err(|error| LoadError::Io { path: path.to_owned(), error })?;
+    parse_monsters(path, &text)
+}
+
+/// A collection of monsters, indexed by their [`Monster::index`].
+#[derive(Clone, Debug, Default)]
+pub struct Bestiary {
+    /// The monsters, keyed by their index.
+    monsters: BTreeMap<String, Monster>,
+}
+
+impl FromIterator<Monster> for Bestiary {
+    fn from_iter<I: IntoIterator<Item = Monster>>(iter: I) -> Self {
+        let mut bestiary = Self::new();
+        bestiary.extend(iter);
+        bestiary
+    }
+}
+
+impl Extend<Monster> for Bestiary {
+    fn extend<I: IntoIterator<Item = Monster>>(&mut self, iter: I) {
+        for monster in iter {
+            self.insert(monster);
+        }
+    }
+}
+
+impl Bestiary {
+    /// Create an empty bestiary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a bestiary from a reader containing a JSON array of monsters.
+    pub fn from_reader(reader: impl Read) -> serde_json::Result<Self> {
+        let monsters = serde_json::from_reader::<_, Vec<Monster>>(reader)?;
+        Ok(monsters.into_iter().collect())
+    }
+
+    /// Create a bestiary by loading each of the given files or directories in order, as if by
+    /// [`Bestiary::load`].
+    pub fn load_layers<P: AsRef<Path>>(
+        layers: impl IntoIterator<Item = P>,
+    ) -> Result<Self, LoadError> {
+        let mut bestiary = Self::new();
+        for layer in layers {
+            bestiary.load(layer)?;
+        }
+        Ok(bestiary)
+    }
+
+    /// Load monsters from a JSON file or a directory of JSON files, returning the number of
+    /// monsters loaded.
+    ///
+    /// Each file may contain either a single monster or an array of monsters. Directories are
+    /// searched recursively for files with the `.json` extension, which are loaded in order of
+    /// their paths. Loaded monsters replace any existing monster with the same index.
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<usize, LoadError> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            self.load_dir(path)
+        } else {
+            self.load_file(path)
+        }
+    }
+
+    /// Load monsters from a single JSON file.
+    fn load_file(&mut self, path: &Path) -> Result<usize, LoadError> {
+        let monsters = read_monsters(path)?;
+        let count = monsters.len();
+        self.extend(monsters);
+        Ok(count)
+    }
+
+    /// Load monsters from every JSON file in a directory, recursively.
+    fn load_dir(&mut self, path: &Path) -> Result<usize, LoadError> {
+        let io_error = |error| LoadError::Io { path: path.to_owned(), error };
+        let mut entries = std::fs::read_dir(path)
+            .map_err(io_error)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io_error)?;
+        entries.sort();
+
+        let mut count = 0;
+        for entry in entries {
+            if entry.is_dir() {
+                count += self.load_dir(&entry)?;
+            } else if entry.extension().is_some_and(|ext| ext == "json") {
+                count += self.load_file(&entry)?;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Add a monster to the bestiary. If a monster with the same index already exists, it is
+    /// replaced and returned.
+    pub fn insert(&mut self, monster: Monster) -> Option<Monster> {
+        self.monsters.insert(monster.index.clone(), monster)
+    }
+
+    /// Returns the monster with the given index.
+    pub fn get(&self, index: &str) -> Option<&Monster> {
+        self.monsters.get(index)
+    }
+
+    /// Returns the number of monsters in the bestiary.
+    pub fn len(&self) -> usize {
+        self.monsters.len()
+    }
+
+    /// Returns `true` if the bestiary contains no monsters.
+    pub fn is_empty(&self) -> bool {
+        self.monsters.is_empty()
+    }
+
+    /// Returns an iterator over all monsters, ordered by index.
+    pub fn iter(&self) -> impl Iterator<Item = &Monster> {
+        self.monsters.values()
+    }
+
+    /// Start a new query over the monsters in the bestiary.
+    pub fn query(&self) -> Query<'_> {
+        Query::new(self)
+    }
+}
+
+/// A query over the monsters in a [`Bestiary`].
+///
+/// Each method adds a filter; a monster must pass every filter to be included in the results.
+/// Filters that accept multiple values (such as [`Query::r#type`]) match if the monster matches
+/// any of the values given.
+///
+/// # Example
+///
+/// ```
+/// # use h5t_core::{bestiary::Bestiary, monster::Type, ChallengeRating};
+/// # let bestiary = Bestiary::new();
+/// let results = bestiary.query()
+///     .name("gob")
+///     .challenge_rating(..=ChallengeRating::whole(1).unwrap())
+///     .r#type(Type::Humanoid)
+///     .run();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Query<'a> {
+    /// The bestiary to search.
+    bestiary: &'a Bestiary,
+
+    /// Fuzzy pattern to match against monster names.
+    name: Option<String>,
+
+    /// Range of challenge ratings to include.
+    challenge_rating: (Bound<ChallengeRating>, Bound<ChallengeRating>),
+
+    /// Creature types to include.
+    types: Vec<Type>,
+
+    /// Creature sizes to include.
+    sizes: Vec<Size>,
+
+    /// Case-insensitive substring to find in the monster's alignment.
+    alignment: Option<String>,
+
+    /// Environment tags to include.
+    environments: Vec<String>,
+
+    /// Damage types the monster must be immune to.
+    damage_immunities: Vec<String>,
+
+    /// Whether the monster must (or must not) have legendary actions.
+    legendary: Option<bool>,
+}
+
+impl<'a> Query<'a> {
+    /// Create a query that matches every monster in the bestiary.
+    pub fn new(bestiary: &'a Bestiary) -> Self {
+        Self {
+            bestiary,
+            name: None,
+            challenge_rating: (Bound::Unbounded, Bound::Unbounded),
+            types: Vec::new(),
+            sizes: Vec::new(),
+            alignment: None,
+            environments: Vec::new(),
+            damage_immunities: Vec::new(),
+            legendary: None,
+        }
+    }
+
+    /// Only include monsters whose name fuzzy-matches the given pattern. Results are ranked by
+    /// how well they match.
+    pub fn name(mut self, pattern: impl Into<String>) -> Self {
+        self.name = Some(pattern.into());
+        self
+    }
+
+    /// Only include monsters whose challenge rating lies in the given range.
+    pub fn challenge_rating(mut self, range: impl RangeBounds<ChallengeRating>) -> Self {
+        self.challenge_rating = (range.start_bound().cloned(), range.end_bound().cloned());
+        self
+    }
+
+    /// Include monsters of the given type. Can be called multiple times to include several types.
+    pub fn r#type(mut self, r#type: Type) -> Self {
+        self.types.push(r#type);
+        self
+    }
+
+    /// Include monsters of the given size. Can be called multiple times to include several sizes.
+    pub fn size(mut self, size: Size) -> Self {
+        self.sizes.push(size);
+        self
+    }
+
+    /// Only include monsters whose alignment contains the given text, such as "evil" or "lawful".
+    pub fn alignment(mut self, alignment: impl Into<String>) -> Self {
+        self.alignment = Some(alignment.into().to_lowercase());
+        self
+    }
+
+    /// Include monsters found in the given environment. Can be called multiple times to include
+    /// several environments.
+    pub fn environment(mut self, environment: impl Into<String>) -> Self {
+        self.environments.push(environment.into().to_lowercase());
+        self
+    }
+
+    /// Only include monsters immune to the given damage type. Can be called multiple times to
+    /// require immunity to several damage types.
+    pub fn immune_to(mut self, damage_type: impl Into<String>) -> Self {
+        self.damage_immunities.push(damage_type.into().to_lowercase());
+        self
+    }
+
+    /// Only include monsters that have legendary actions (`true`), or that don't (`false`).
+    pub fn legendary(mut self, legendary: bool) -> Self {
+        self.legendary = Some(legendary);
+        self
+    }
+
+    /// Returns `true` if the monster passes every filter except the name filter.
+    fn matches(&self, monster: &Monster) -> bool {
+        if !self.challenge_rating.contains(&monster.challenge_rating) {
+            return false;
+        }
+
+        if !self.types.is_empty() && !self.types.contains(&monster.r#type) {
+            return false;
+        }
+
+        if !self.sizes.is_empty() && !self.sizes.contains(&monster.size) {
+            return false;
+        }
+
+        if let Some(alignment) = &self.alignment {
+            if !monster.alignment.to_lowercase().contains(alignment) {
+                return false;
+            }
+        }
+
+        if !self.environments.is_empty() && !monster.environments
+            .iter()
+            .any(|env| self.environments.contains(&env.to_lowercase()))
+        {
+            return false;
+        }
+
+        if !self.damage_immunities.iter().all(|damage_type| {
+            monster.damage_immunities
+                .iter()
+                .any(|immunity| immunity.to_lowercase().contains(damage_type))
+        }) {
+            return false;
+        }
+
+        if let Some(legendary) = self.legendary {
+            if monster.has_legendary_actions() != legendary {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Run the query, returning the matching monsters.
+    ///
+    /// If a name pattern was given, the results are ordered from best to worst match. Otherwise,
+    /// they are ordered by index.
+    pub fn run(self) -> Vec<&'a Monster> {
+        let filtered = self.bestiary
+            .iter()
+            .filter(|monster| self.matches(monster));
+
+        let Some(pattern) = &self.name else {
+            return filtered.collect();
+        };
+
+        let mut ranked = filtered
+            .filter_map(|monster| Some((fuzzy_score(pattern, &monster.name)?, monster)))
+            .collect::<Vec<_>>();
+
+        // stable sort keeps ties in index order
+        ranked.sort_by(|(a, _), (b, _)| b.cmp(a));
+        ranked.into_iter().map(|(_, monster)| monster).collect()
+    }
+}
+
+/// Scores how well `text` matches the fuzzy `pattern`. Returns [`None`] if the characters of the
+/// pattern do not appear in order in the text. Matching is case-insensitive, and whitespace in the
+/// pattern is ignored.
+///
+/// Higher scores are better matches. Exact matches score highest, followed by prefixes, then
+/// substrings. Characters that match consecutively or at the start of a word score higher than
+/// scattered characters.
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<u32> {
+    let pattern = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect::<Vec<_>>();
+    let text = text.chars().flat_map(char::to_lowercase).collect::<Vec<_>>();
+
+    if pattern.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut pattern_idx = 0;
+    let mut last_match = None;
+    for (i, c) in text.iter().enumerate() {
+        if pattern_idx == pattern.len() {
+            break;
+        }
+        if *c != pattern[pattern_idx] {
+            continue;
+        }
+
+        score += 1;
+        if last_match.is_some_and(|last| last + 1 == i) {
+            score += 5;
+        }
+        if i == 0 || !text[i - 1].is_alphanumeric() {
+            score += 8;
+        }
+        last_match = Some(i);
+        pattern_idx += 1;
+    }
+
+    if pattern_idx < pattern.len() {
+        return None;
+    }
+
+    // bonuses for matching the pattern literally
+    let text = text.iter().collect::<String>();
+    let pattern = pattern.iter().collect::<String>();
+    if text == pattern {
+        score += 100;
+    } else if text.starts_with(&pattern) {
+        score += 50;
+    } else if text.contains(&pattern) {
+        score += 25;
+    }
+
+    // prefer shorter names when the match is otherwise equal
+    Some(score * 100 + 100u32.saturating_sub(text.len() as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monster(index: &str, name: &str, challenge_rating: f32, r#type: Type) -> Monster {
+        Monster {
+            index: index.to_string(),
+            name: name.to_string(),
+            challenge_rating: ChallengeRating::from_f32(challenge_rating).unwrap(),
+            r#type,
+            ..Default::default()
+        }
+    }
+
+    /// Ensure fuzzy name matching ranks exact and prefix matches first.
+    #[test]
+    fn fuzzy_ranking() {
+        let bestiary = Bestiary::from_iter([
+            monster("hobgoblin", "Hobgoblin", 0.5, Type::Humanoid),
+            monster("goblin", "Goblin", 0.25, Type::Humanoid),
+            monster("gibbering-mouther", "Gibbering Mouther", 2.0, Type::Aberration),
+            monster("ogre", "Ogre", 2.0, Type::Giant),
+        ]);
+
+        let names = bestiary.query()
+            .name("goblin")
+            .run()
+            .into_iter()
+            .map(|m| m.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Goblin", "Hobgoblin"]);
+
+        let names = bestiary.query()
+            .name("gm")
+            .run()
+            .into_iter()
+            .map(|m| m.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Gibbering Mouther"]);
+    }
+
+    /// Ensure filters are combined.
+    #[test]
+    fn filters() {
+        let bestiary = Bestiary::from_iter([
+            monster("hobgoblin", "Hobgoblin", 0.5, Type::Humanoid),
+            monster("goblin", "Goblin", 0.25, Type::Humanoid),
+            monster("ogre", "Ogre", 2.0, Type::Giant),
+        ]);
+
+        let results = bestiary.query()
+            .challenge_rating(ChallengeRating::HALF..)
+            .run();
+        assert_eq!(results.len(), 2);
+
+        let results = bestiary.query()
+            .challenge_rating(ChallengeRating::HALF..)
+            .r#type(Type::Humanoid)
+            .run();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].index, "hobgoblin");
+
+        assert!(bestiary.query().legendary(true).run().is_empty());
+    }
+
+    /// Ensure parse errors identify the offending monster and value.
+    #[test]
+    fn load_error() {
+        let srd = serde_json::from_str::<Vec<Value>>(include_str!("../../data/monsters.json"))
+            .unwrap();
+        let goblin = srd.into_iter().find(|m| m["index"] == "goblin").unwrap();
+        let mut goblin_boss = goblin.clone();
+        goblin_boss["name"] = "Goblin Boss".into();
+        goblin_boss["armor_class"][0]["value"] = "high".into();
+
+        let text = serde_json::to_string(&[goblin, goblin_boss]).unwrap();
+        let Err(LoadError::Monster { json_path, name, .. }) = parse_monsters(Path::new("homebrew.json"), &text) else {
+            panic!("expected a monster error");
+        };
+        assert_eq!(json_path, "[1].armor_class[0].value");
+        assert_eq!(name.as_deref(), Some("Goblin Boss"));
+    }
+
+    /// Ensure the SRD monsters load and can be queried.
+    #[test]
+    fn srd() {
+        let bestiary = Bestiary::from_reader(include_str!("../../data/monsters.json").as_bytes())
+            .unwrap();
+        assert_eq!(bestiary.len(), 334);
+
+        let results = bestiary.query()
+            .name("adult red")
+            .run();
+        assert_eq!(results[0].index, "adult-red-dragon");
+
+        let results = bestiary.query()
+            .r#type(Type::Dragon)
+            .immune_to("fire")
+            .legendary(true)
+            .run();
+        assert!(results.iter().any(|m| m.index == "ancient-red-dragon"));
+        assert!(results.iter().all(|m| m.has_legendary_actions()));
+    }
+}
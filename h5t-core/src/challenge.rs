@@ -0,0 +1,191 @@
+//! Challenge ratings, which measure how dangerous a monster is.
+
+use crate::ability::Modifier;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// The XP for each challenge rating, in the order of [`ChallengeRating`]'s internal index.
+const XP: [u32; 34] = [
+    10, 25, 50, 100, // 0, 1/8, 1/4, 1/2
+    200, 450, 700, 1_100, 1_800, 2_300, 2_900, 3_900, 5_000, 5_900, // 1-10
+    7_200, 8_400, 10_000, 11_500, 13_000, 15_000, 18_000, 20_000, 22_000, 25_000, // 11-20
+    33_000, 41_000, 50_000, 62_000, 75_000, 90_000, 105_000, 120_000, 135_000, 155_000, // 21-30
+];
+
+/// A monster's challenge rating: 0, 1/8, 1/4, 1/2, or a whole number from 1 to 30.
+///
+/// Challenge ratings parse from the numbers used by the SRD (such as `0.25`) and from fractions
+/// (such as `"1/4"`), and display as fractions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChallengeRating(
+    // 0 through 3 are 0, 1/8, 1/4, and 1/2; 4 through 33 are 1 through 30
+    u8,
+);
+
+impl ChallengeRating {
+    /// Challenge rating 0.
+    pub const ZERO: Self = Self(0);
+
+    /// Challenge rating 1/8.
+    pub const EIGHTH: Self = Self(1);
+
+    /// Challenge rating 1/4.
+    pub const QUARTER: Self = Self(2);
+
+    /// Challenge rating 1/2.
+    pub const HALF: Self = Self(3);
+
+    /// Returns the whole challenge rating `n`, or [`None`] if it is not between 1 and 30.
+    pub fn whole(n: u32) -> Option<Self> {
+        (1..=30).contains(&n).then(|| Self(n as u8 + 3))
+    }
+
+    /// Returns the challenge rating equal to the given number, or [`None`] if there is none.
+    pub fn from_f32(value: f32) -> Option<Self> {
+        match value {
+            0.0 => Some(Self::ZERO),
+            0.125 => Some(Self::EIGHTH),
+            0.25 => Some(Self::QUARTER),
+            0.5 => Some(Self::HALF),
+            _ if value.fract() == 0.0 && value > 0.0 => Self::whole(value as u32),
+            _ => None,
+        }
+    }
+
+    /// Returns the challenge rating as a number, such as `0.25` for 1/4.
+    pub fn as_f32(&self) -> f32 {
+        match self.0 {
+            0 => 0.0,
+            1 => 0.125,
+            2 => 0.25,
+            3 => 0.5,
+            n => (n - 3) as f32,
+        }
+    }
+
+    /// Returns the XP for defeating a monster of this challenge rating.
+    pub fn xp(&self) -> u32 {
+        XP[self.0 as usize]
+    }
+
+    /// Returns the proficiency bonus of a monster of this challenge rating.
+    pub fn proficiency_bonus(&self) -> Modifier {
+        // +2 up to CR 4, then +1 for every 4 challenge ratings
+        let whole = self.0.saturating_sub(3).max(1) as Modifier;
+        2 + (whole - 1) / 4
+    }
+}
+
+impl fmt::Display for ChallengeRating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            0 => write!(f, "0"),
+            1 => write!(f, "1/8"),
+            2 => write!(f, "1/4"),
+            3 => write!(f, "1/2"),
+            n => write!(f, "{}", n - 3),
+        }
+    }
+}
+
+/// An invalid challenge rating.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidChallengeRating(pub String);
+
+impl fmt::Display for InvalidChallengeRating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid challenge rating `{}`; expected 0, 1/8, 1/4, 1/2, or a whole number from 1 to 30",
+            self.0,
+        )
+    }
+}
+
+impl std::error::Error for InvalidChallengeRating {}
+
+impl FromStr for ChallengeRating {
+    type Err = InvalidChallengeRating;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let challenge_rating = match s.trim() {
+            "1/8" => Some(Self::EIGHTH),
+            "1/4" => Some(Self::QUARTER),
+            "1/2" => Some(Self::HALF),
+            other => other.parse().ok().and_then(Self::from_f32),
+        };
+        challenge_rating.ok_or_else(|| InvalidChallengeRating(s.to_string()))
+    }
+}
+
+/// Serializes as a number, like the SRD.
+impl Serialize for ChallengeRating {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0 <= 3 {
+            serializer.serialize_f32(self.as_f32())
+        } else {
+            serializer.serialize_u32(self.0 as u32 - 3)
+        }
+    }
+}
+
+/// Deserializes from a number or a string.
+impl<'de> Deserialize<'de> for ChallengeRating {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl de::Visitor<'_> for Visitor {
+            type Value = ChallengeRating;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a challenge rating, such as 2, 0.25, or \"1/4\"")
+            }
+
+            fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+                ChallengeRating::from_f32(value as f32)
+                    .ok_or_else(|| E::custom(InvalidChallengeRating(value.to_string())))
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                self.visit_f64(value as f64)
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                self.visit_f64(value as f64)
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        d.deserialize_any(Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ensure challenge ratings parse, order, and map to XP and proficiency bonus.
+    #[test]
+    fn challenge_rating() {
+        let quarter = "1/4".parse::<ChallengeRating>().unwrap();
+        assert_eq!(quarter, ChallengeRating::from_f32(0.25).unwrap());
+        assert_eq!(quarter.to_string(), "1/4");
+        assert_eq!(quarter.xp(), 50);
+        assert!(ChallengeRating::ZERO < quarter && quarter < ChallengeRating::whole(1).unwrap());
+
+        let cr = ChallengeRating::whole(17).unwrap();
+        assert_eq!((cr.xp(), cr.proficiency_bonus()), (18_000, 6));
+        assert_eq!(ChallengeRating::HALF.proficiency_bonus(), 2);
+        assert_eq!(ChallengeRating::whole(30).unwrap().proficiency_bonus(), 9);
+
+        assert!("1/3".parse::<ChallengeRating>().is_err());
+        assert!(ChallengeRating::from_f32(31.0).is_none());
+        let err = serde_json::from_str::<ChallengeRating>("0.3").unwrap_err();
+        assert!(err.to_string().starts_with("invalid challenge rating `0.3"));
+        assert_eq!(serde_json::from_str::<ChallengeRating>("\"1/8\"").unwrap(), ChallengeRating::EIGHTH);
+        assert_eq!(serde_json::to_string(&cr).unwrap(), "17");
+    }
+}
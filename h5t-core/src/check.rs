@@ -0,0 +1,244 @@
+//! Saving throws and ability checks, with the modifiers, advantage, and automatic failures that
+//! come from a combatant's stat block and conditions.
+
+use crate::{
+    ability::{Modifier, ParseAbilityError, SkillKind},
+    dice::Advantage,
+    score_to_modifier,
+    AbilityKind,
+    ActiveKind,
+    Combatant,
+    ConditionKind,
+    Tracker,
+};
+use rand::Rng;
+use std::{fmt, str::FromStr};
+
+/// A kind of d20 roll a combatant can make, other than an attack roll.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Check {
+    /// A saving throw.
+    Save(AbilityKind),
+
+    /// An ability check without a skill.
+    Ability(AbilityKind),
+
+    /// An ability check with a skill.
+    Skill(SkillKind),
+}
+
+impl Check {
+    /// Returns the ability the roll is based on.
+    pub fn ability(&self) -> AbilityKind {
+        match self {
+            Check::Save(ability) | Check::Ability(ability) => *ability,
+            Check::Skill(skill) => skill.ability(),
+        }
+    }
+
+    /// Returns `true` if the roll is a saving throw.
+    pub fn is_save(&self) -> bool {
+        matches!(self, Check::Save(_))
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Check::Save(ability) => write!(f, "{} save", ability),
+            Check::Ability(ability) => write!(f, "{} check", ability),
+            Check::Skill(skill) => write!(f, "{}", skill),
+        }
+    }
+}
+
+/// Parses a check such as `"wis save"`, `"dexterity saving throw"`, `"str"` or `"str check"`, or
+/// a skill name such as `"stealth"`, ignoring case.
+impl FromStr for Check {
+    type Err = ParseAbilityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        if let Some(ability) = lower
+            .strip_suffix("saving throw")
+            .or_else(|| lower.strip_suffix("save"))
+        {
+            return ability.parse().map(Check::Save);
+        }
+        if let Ok(skill) = lower.parse() {
+            return Ok(Check::Skill(skill));
+        }
+        lower
+            .strip_suffix("check")
+            .unwrap_or(&lower)
+            .parse()
+            .map(Check::Ability)
+            .map_err(|_| ParseAbilityError(s.to_string()))
+    }
+}
+
+/// The result of a combatant rolling a [`Check`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CheckRoll {
+    /// Whether the roll had advantage or disadvantage.
+    pub advantage: Advantage,
+
+    /// The d20 roll that counts.
+    pub roll: u32,
+
+    /// The d20 roll that was dropped because of advantage or disadvantage, if any.
+    pub dropped: Option<u32>,
+
+    /// The total of the roll.
+    pub total: i32,
+
+    /// Whether the combatant automatically fails, such as a stunned creature's Dexterity save.
+    pub auto_fail: bool,
+}
+
+impl CheckRoll {
+    /// Returns `true` if the roll meets the given DC.
+    pub fn passes(&self, dc: i32) -> bool {
+        !self.auto_fail && self.total >= dc
+    }
+}
+
+impl Combatant {
+    /// Returns the combatant's skill modifier: their proficiency modifier if they have one, or their
+    /// ability modifier otherwise.
+    pub fn skill_modifier(&self, skill: SkillKind) -> Modifier {
+        let modifier = score_to_modifier(*self.scores().get(skill.ability()));
+        match self.active_kind() {
+            ActiveKind::Monster(monster) => monster.proficiencies.skills
+                .get(skill)
+                .unwrap_or(modifier),
+            ActiveKind::Player(_) => modifier,
+        }
+    }
+
+    /// Returns the combatant's modifier for the given check.
+    pub fn check_modifier(&self, check: Check) -> Modifier {
+        match check {
+            Check::Save(ability) => self.saving_throw(ability),
+            Check::Ability(ability) => score_to_modifier(*self.scores().get(ability)),
+            Check::Skill(skill) => self.skill_modifier(skill),
+        }
+    }
+
+    /// Returns `true` if the combatant's conditions make them automatically fail the check.
+    ///
+    /// Paralyzed, petrified, stunned, and unconscious creatures fail Strength and Dexterity saving
+    /// throws.
+    pub fn auto_fails(&self, check: Check) -> bool {
+        let Check::Save(AbilityKind::Strength | AbilityKind::Dexterity) = check else {
+            return false;
+        };
+        self.condition_kinds().into_iter().any(|kind| matches!(
+            kind,
+            ConditionKind::Paralyzed
+                | ConditionKind::Petrified
+                | ConditionKind::Stunned
+                | ConditionKind::Unconscious
+        ))
+    }
+
+    /// Returns whether the combatant's conditions give them advantage or disadvantage on the check.
+    pub fn check_advantage(&self, check: Check) -> Advantage {
+        let conditions = self.condition_kinds().into_iter().map(|kind| match kind {
+            ConditionKind::Restrained if check == Check::Save(AbilityKind::Dexterity) => {
+                Advantage::Disadvantage
+            },
+            ConditionKind::Frightened | ConditionKind::Poisoned if !check.is_save() => {
+                Advantage::Disadvantage
+            },
+            _ => Advantage::Normal,
+        });
+        // exhaustion gives disadvantage on ability checks from level 1, and on saves from level 3
+        let exhaustion_level = if check.is_save() { 3 } else { 1 };
+        let exhaustion = (self.exhaustion >= exhaustion_level).then_some(Advantage::Disadvantage);
+
+        Advantage::combine(conditions.chain(exhaustion))
+    }
+
+    /// Roll the check for the combatant.
+    pub fn roll_check(&self, check: Check, rng: &mut impl Rng) -> CheckRoll {
+        let advantage = self.check_advantage(check);
+        let (roll, dropped) = advantage.roll(rng);
+        CheckRoll {
+            advantage,
+            roll,
+            dropped,
+            total: roll as i32 + self.check_modifier(check),
+            auto_fail: self.auto_fails(check),
+        }
+    }
+}
+
+impl Tracker {
+    /// Roll the check for each of the combatants at the given indices, and log how many passed.
+    pub fn group_check(
+        &mut self,
+        indices: &[usize],
+        check: Check,
+        dc: i32,
+        rng: &mut impl Rng,
+    ) -> Vec<CheckRoll> {
+        let rolls = indices
+            .iter()
+            .map(|&index| self.combatants[index].roll_check(check, rng))
+            .collect::<Vec<_>>();
+        let passed = rolls.iter().filter(|roll| roll.passes(dc)).count();
+        self.log(format!("{} of {} pass a DC {} {}", passed, rolls.len(), dc, check));
+        rolls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{monster::Proficiencies, Ability, Condition, ConditionDuration, Monster};
+
+    /// Ensure checks parse, and modifiers fall back to ability modifiers.
+    #[test]
+    fn modifiers() {
+        assert_eq!("wis save".parse(), Ok(Check::Save(AbilityKind::Wisdom)));
+        assert_eq!("Dexterity saving throw".parse(), Ok(Check::Save(AbilityKind::Dexterity)));
+        assert_eq!("sleight of hand".parse(), Ok(Check::Skill(SkillKind::SleightOfHand)));
+        assert_eq!("str check".parse(), Ok(Check::Ability(AbilityKind::Strength)));
+        assert!("cooking".parse::<Check>().is_err());
+
+        let mut proficiencies = Proficiencies::default();
+        proficiencies.skills.stealth = Some(6);
+        let combatant = Combatant::from(Monster {
+            scores: Ability { dexterity: 14, wisdom: 8, ..Default::default() },
+            proficiencies,
+            ..Default::default()
+        });
+        assert_eq!(combatant.check_modifier(Check::Skill(SkillKind::Stealth)), 6);
+        assert_eq!(combatant.check_modifier(Check::Skill(SkillKind::Acrobatics)), 2);
+        assert_eq!(combatant.check_modifier(Check::Save(AbilityKind::Wisdom)), -1);
+    }
+
+    /// Ensure conditions cause automatic failures and disadvantage.
+    #[test]
+    fn conditions() {
+        let mut combatant = Combatant::from(Monster::default());
+        combatant.apply_condition(Condition {
+            kind: ConditionKind::Stunned,
+            duration: ConditionDuration::Forever,
+        });
+        combatant.apply_condition(Condition {
+            kind: ConditionKind::Poisoned,
+            duration: ConditionDuration::Forever,
+        });
+
+        let dexterity_save = Check::Save(AbilityKind::Dexterity);
+        let wisdom_save = Check::Save(AbilityKind::Wisdom);
+        let stealth = Check::Skill(SkillKind::Stealth);
+        let save = combatant.roll_check(dexterity_save, &mut rand::thread_rng());
+        assert!(save.auto_fail && !save.passes(-100));
+        assert!(!combatant.auto_fails(wisdom_save));
+        assert_eq!(combatant.check_advantage(stealth), Advantage::Disadvantage);
+        assert_eq!(combatant.check_advantage(wisdom_save), Advantage::Normal);
+    }
+}
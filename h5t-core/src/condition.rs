@@ -1,8 +1,9 @@
 use enumset::EnumSetType;
+use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, num::NonZeroU32};
 
 /// A condition and how long it lasts.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Condition {
     /// The condition to apply.
     pub kind: ConditionKind,
@@ -12,7 +13,8 @@ pub struct Condition {
 }
 
 /// All possible conditions that can be applied to a combatant.
-#[derive(EnumSetType, Debug, Hash)]
+#[derive(EnumSetType, Debug, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ConditionKind {
     Blinded,
     Charmed,
@@ -83,7 +85,8 @@ make_abbreviations! {
 }
 
 /// Duration of a condition.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ConditionDuration {
     /// The condition lasts until the end of the combatant's next turn.
     #[default]
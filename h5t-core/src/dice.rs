@@ -0,0 +1,348 @@
+//! Dice expressions, such as `2d6+5`, and rolling them.
+
+use rand::Rng;
+use std::{fmt, str::FromStr};
+
+/// The most dice a single term may roll.
+pub const MAX_DICE: u32 = 1000;
+
+/// The most sides a die may have.
+pub const MAX_SIDES: u32 = 1000;
+
+/// A single term in a dice expression.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Term {
+    /// Roll `count` dice with `sides` sides each and add (or subtract, if `negative`) the sum.
+    Dice {
+        count: u32,
+        sides: u32,
+        negative: bool,
+    },
+
+    /// A constant modifier.
+    Constant(i32),
+}
+
+/// A dice expression, such as `1d20+5` or `2d6 + 1d4 - 1`.
+///
+/// Expressions are sums of dice terms (`NdS`, or `dS` for a single die) and constants.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Expr {
+    /// The terms in the expression, in the order they were written.
+    pub terms: Vec<Term>,
+}
+
+/// An error that occurred while parsing a dice expression.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The expression that could not be parsed.
+    pub expr: String,
+
+    /// A description of the problem.
+    pub reason: &'static str,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid dice expression `{}`: {}", self.expr, self.reason)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl FromStr for Expr {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = |reason| ParseError { expr: s.to_string(), reason };
+
+        // whitespace is only allowed around operators, so "2d6 5" is not read as "2d65"
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        if words.windows(2).any(|pair| {
+            pair[0].ends_with(|c: char| c.is_ascii_alphanumeric())
+                && pair[1].starts_with(|c: char| c.is_ascii_alphanumeric())
+        }) {
+            return Err(err("expected `+` or `-` between terms"));
+        }
+
+        let compact = s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+        if compact.is_empty() {
+            return Err(err("expression is empty"));
+        }
+
+        // split into signed terms, e.g. "2d6+5-1d4" -> ["2d6", "+5", "-1d4"]
+        let mut terms = Vec::new();
+        let mut rest = compact.as_str();
+        while !rest.is_empty() {
+            let (negative, body) = match rest.as_bytes()[0] {
+                b'+' => (false, &rest[1..]),
+                b'-' => (true, &rest[1..]),
+                _ if terms.is_empty() => (false, rest),
+                _ => return Err(err("expected `+` or `-` between terms")),
+            };
+            let end = body.find(['+', '-']).unwrap_or(body.len());
+            let (term, next) = body.split_at(end);
+            rest = next;
+
+            let term = term.to_lowercase();
+            if let Some((count, sides)) = term.split_once('d') {
+                let count = if count.is_empty() {
+                    1
+                } else {
+                    count.parse().map_err(|_| err("invalid dice count"))?
+                };
+                let sides = sides.parse().map_err(|_| err("invalid number of sides"))?;
+                if sides == 0 {
+                    return Err(err("dice must have at least one side"));
+                }
+                if count > MAX_DICE {
+                    return Err(err("too many dice"));
+                }
+                if sides > MAX_SIDES {
+                    return Err(err("too many sides"));
+                }
+                terms.push(Term::Dice { count, sides, negative });
+            } else {
+                let value = term.parse::<i32>().map_err(|_| err("invalid constant"))?;
+                terms.push(Term::Constant(if negative { -value } else { value }));
+            }
+        }
+
+        Ok(Self { terms })
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, term) in self.terms.iter().enumerate() {
+            match *term {
+                Term::Dice { count, sides, negative } => {
+                    if negative {
+                        write!(f, "-")?;
+                    } else if i > 0 {
+                        write!(f, "+")?;
+                    }
+                    write!(f, "{}d{}", count, sides)?;
+                },
+                Term::Constant(value) if i > 0 => write!(f, "{:+}", value)?,
+                Term::Constant(value) => write!(f, "{}", value)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Expr {
+    /// Create an expression that rolls `count` dice with `sides` sides.
+    ///
+    /// Returns [`None`] if the dice have no sides, or if there are more than [`MAX_DICE`] dice or
+    /// [`MAX_SIDES`] sides, the same as when parsing.
+    pub fn dice(count: u32, sides: u32) -> Option<Self> {
+        if sides == 0 || count > MAX_DICE || sides > MAX_SIDES {
+            return None;
+        }
+        Some(Self { terms: vec![Term::Dice { count, sides, negative: false }] })
+    }
+
+    /// Add a constant modifier to the expression.
+    pub fn plus(mut self, modifier: i32) -> Self {
+        if modifier != 0 {
+            self.terms.push(Term::Constant(modifier));
+        }
+        self
+    }
+
+    /// Returns the average result of the expression, rounded down, as printed in stat blocks.
+    pub fn average(&self) -> i32 {
+        let (dice, constant) = self.terms.iter().fold((0i32, 0i32), |(dice, constant), term| {
+            match *term {
+                // average of one die is (sides + 1) / 2; keep it doubled to avoid fractions
+                Term::Dice { count, sides, negative } => {
+                    let doubled = dice_total(count, sides.saturating_add(1));
+                    (dice.saturating_add(if negative { -doubled } else { doubled }), constant)
+                },
+                Term::Constant(value) => (dice, constant.saturating_add(value)),
+            }
+        });
+        dice.div_euclid(2).saturating_add(constant)
+    }
+
+    /// Returns the maximum possible result of the expression.
+    pub fn max(&self) -> i32 {
+        self.terms.iter().map(|term| match *term {
+            Term::Dice { count, negative: true, .. } => -dice_total(count, 1),
+            Term::Dice { count, sides, .. } => dice_total(count, sides),
+            Term::Constant(value) => value,
+        }).fold(0, i32::saturating_add)
+    }
+
+    /// Returns the minimum possible result of the expression.
+    pub fn min(&self) -> i32 {
+        self.terms.iter().map(|term| match *term {
+            Term::Dice { count, sides, negative: true } => -dice_total(count, sides),
+            Term::Dice { count, .. } => dice_total(count, 1),
+            Term::Constant(value) => value,
+        }).fold(0, i32::saturating_add)
+    }
+
+    /// Roll the expression.
+    pub fn roll(&self, rng: &mut impl Rng) -> Outcome {
+        let mut rolls = Vec::new();
+        let mut total = 0i32;
+        for term in &self.terms {
+            match *term {
+                // a die with no sides can't be rolled, so it adds nothing
+                Term::Dice { sides: 0, .. } => (),
+                Term::Dice { count, sides, negative } => {
+                    for _ in 0..count {
+                        let roll = rng.gen_range(1..=sides);
+                        rolls.push(roll);
+                        let value = dice_total(1, roll);
+                        total = total.saturating_add(if negative { -value } else { value });
+                    }
+                },
+                Term::Constant(value) => total = total.saturating_add(value),
+            }
+        }
+        Outcome { rolls, total }
+    }
+}
+
+/// Returns `count` times `sides`, saturating at [`i32::MAX`] instead of overflowing.
+fn dice_total(count: u32, sides: u32) -> i32 {
+    count.saturating_mul(sides).try_into().unwrap_or(i32::MAX)
+}
+
+/// The result of rolling a dice [`Expr`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    /// The value of each die rolled, in order.
+    pub rolls: Vec<u32>,
+
+    /// The total result, including constant modifiers.
+    pub total: i32,
+}
+
+/// Roll a single twenty-sided die.
+pub fn d20(rng: &mut impl Rng) -> u32 {
+    rng.gen_range(1..=20)
+}
+
+/// Whether a d20 roll has advantage or disadvantage.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Advantage {
+    /// Roll one d20.
+    #[default]
+    Normal,
+
+    /// Roll two d20s and use the higher roll.
+    Advantage,
+
+    /// Roll two d20s and use the lower roll.
+    Disadvantage,
+}
+
+impl Advantage {
+    /// Combine the advantage and disadvantage from several sources. Any advantage and any
+    /// disadvantage cancel out, no matter how many sources each has.
+    pub fn combine(sources: impl IntoIterator<Item = Advantage>) -> Self {
+        let (mut advantage, mut disadvantage) = (false, false);
+        for source in sources {
+            match source {
+                Advantage::Normal => (),
+                Advantage::Advantage => advantage = true,
+                Advantage::Disadvantage => disadvantage = true,
+            }
+        }
+        match (advantage, disadvantage) {
+            (true, false) => Advantage::Advantage,
+            (false, true) => Advantage::Disadvantage,
+            _ => Advantage::Normal,
+        }
+    }
+
+    /// Roll a d20 with this advantage, returning the roll that counts and, if two dice were rolled,
+    /// the roll that was dropped.
+    pub fn roll(&self, rng: &mut impl Rng) -> (u32, Option<u32>) {
+        let first = d20(rng);
+        let (kept, dropped) = match self {
+            Advantage::Normal => return (first, None),
+            Advantage::Advantage => {
+                let second = d20(rng);
+                (first.max(second), first.min(second))
+            },
+            Advantage::Disadvantage => {
+                let second = d20(rng);
+                (first.min(second), first.max(second))
+            },
+        };
+        (kept, Some(dropped))
+    }
+}
+
+impl fmt::Display for Advantage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Advantage::Normal => Ok(()),
+            Advantage::Advantage => write!(f, "advantage"),
+            Advantage::Disadvantage => write!(f, "disadvantage"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ensure expressions parse and print back out.
+    #[test]
+    fn parse() {
+        let expr = "2d6 + 5".parse::<Expr>().unwrap();
+        assert_eq!(expr.terms, [
+            Term::Dice { count: 2, sides: 6, negative: false },
+            Term::Constant(5),
+        ]);
+        assert_eq!(expr.to_string(), "2d6+5");
+
+        let expr = "d20-1d4-1".parse::<Expr>().unwrap();
+        assert_eq!(expr.to_string(), "1d20-1d4-1");
+
+        assert!("".parse::<Expr>().is_err());
+        assert!("2d".parse::<Expr>().is_err());
+        assert!("2d6 5".parse::<Expr>().is_err());
+        assert!("1d0".parse::<Expr>().is_err());
+    }
+
+    /// Ensure huge or sideless dice are rejected, and terms built directly don't overflow or panic.
+    #[test]
+    fn limits() {
+        assert!("1000d1000".parse::<Expr>().is_ok());
+        assert!("1001d6".parse::<Expr>().is_err());
+        assert!("1d1001".parse::<Expr>().is_err());
+        assert!("100000000d100000000".parse::<Expr>().is_err());
+        assert!("99999999999d6".parse::<Expr>().is_err());
+
+        assert!(Expr::dice(1000, 1000).is_some());
+        assert!(Expr::dice(1001, 6).is_none());
+        assert!(Expr::dice(1, 1001).is_none());
+        assert!(Expr::dice(1, 0).is_none());
+
+        let dice = |count, sides| Expr {
+            terms: vec![Term::Dice { count, sides, negative: false }],
+        };
+        let expr = dice(u32::MAX, u32::MAX).plus(i32::MAX);
+        assert_eq!(expr.max(), i32::MAX);
+        assert_eq!(expr.average(), i32::MAX);
+        assert_eq!(dice(u32::MAX, 2).plus(-5).min(), i32::MAX - 5);
+        assert_eq!(dice(3, 0).plus(2).roll(&mut rand::thread_rng()).total, 2);
+    }
+
+    /// Ensure averages match the values printed in the SRD.
+    #[test]
+    fn average() {
+        assert_eq!("18d10+36".parse::<Expr>().unwrap().average(), 135);
+        assert_eq!("2d6".parse::<Expr>().unwrap().average(), 7);
+        assert_eq!("1d6+2".parse::<Expr>().unwrap().average(), 5);
+        assert_eq!("1d4".parse::<Expr>().unwrap().max(), 4);
+    }
+}
@@ -0,0 +1,351 @@
+//! Encounter difficulty calculation, using either the XP thresholds from the 2014 Dungeon Master's
+//! Guide, or the XP budgets from the 2024 Dungeon Master's Guide.
+
+use serde::{Deserialize, Serialize};
+
+/// The method used to rate an encounter's difficulty.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+    /// The 2014 Dungeon Master's Guide method. Monster XP is summed and multiplied by an encounter
+    /// multiplier based on the number of monsters, then compared against the party's XP
+    /// thresholds.
+    #[default]
+    Dmg2014,
+
+    /// The 2024 Dungeon Master's Guide method. Monster XP is summed (without any multiplier) and
+    /// compared against the party's XP budget.
+    Dmg2024,
+}
+
+/// The difficulty rating of an encounter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    /// The encounter does not reach the easy threshold.
+    Trivial,
+
+    /// Easy (2014), or Low (2024).
+    Easy,
+
+    /// Medium (2014), or Moderate (2024).
+    Medium,
+
+    /// Hard (2014), or High (2024).
+    Hard,
+
+    /// Deadly (2014), or beyond the High budget (2024).
+    Deadly,
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// XP thresholds for each difficulty, as given by the 2014 Dungeon Master's Guide.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Thresholds {
+    pub easy: u32,
+    pub medium: u32,
+    pub hard: u32,
+    pub deadly: u32,
+}
+
+/// XP budgets for each difficulty, as given by the 2024 Dungeon Master's Guide.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Budget {
+    pub low: u32,
+    pub moderate: u32,
+    pub high: u32,
+}
+
+/// XP thresholds per character level (2014 Dungeon Master's Guide, chapter 3), in the order: easy,
+/// medium, hard, deadly.
+const THRESHOLDS: [[u32; 4]; 20] = [
+    [25, 50, 75, 100],
+    [50, 100, 150, 200],
+    [75, 150, 225, 400],
+    [125, 250, 375, 500],
+    [250, 500, 750, 1100],
+    [300, 600, 900, 1400],
+    [350, 750, 1100, 1700],
+    [450, 900, 1400, 2100],
+    [550, 1100, 1600, 2400],
+    [600, 1200, 1900, 2800],
+    [800, 1600, 2400, 3600],
+    [1000, 2000, 3000, 4500],
+    [1100, 2200, 3400, 5100],
+    [1250, 2500, 3800, 5700],
+    [1400, 2800, 4300, 6400],
+    [1600, 3200, 4800, 7200],
+    [2000, 3900, 5900, 8800],
+    [2100, 4200, 6300, 9500],
+    [2400, 4900, 7300, 10900],
+    [2800, 5700, 8500, 12700],
+];
+
+/// XP budget per character level (2024 Dungeon Master's Guide, chapter 4), in the order: low,
+/// moderate, high.
+const BUDGETS: [[u32; 3]; 20] = [
+    [50, 75, 100],
+    [100, 150, 200],
+    [150, 225, 400],
+    [250, 375, 500],
+    [500, 750, 1100],
+    [600, 1000, 1400],
+    [750, 1300, 1700],
+    [1000, 1700, 2100],
+    [1300, 2000, 2600],
+    [1600, 2300, 3100],
+    [1900, 2900, 4100],
+    [2200, 3700, 4700],
+    [2600, 4200, 5400],
+    [2900, 4900, 6200],
+    [3300, 5400, 7800],
+    [3800, 6100, 9800],
+    [4500, 7200, 11700],
+    [5000, 8700, 14200],
+    [5500, 10700, 17200],
+    [6400, 13200, 22000],
+];
+
+/// Encounter multipliers (2014 Dungeon Master's Guide), including the extra steps used to adjust
+/// for very small and very large parties.
+const MULTIPLIERS: [f32; 8] = [0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 4.0, 5.0];
+
+/// Returns the index into [`THRESHOLDS`] / [`BUDGETS`] for the given level, clamping it to 1-20.
+fn level_index(level: u32) -> usize {
+    level.clamp(1, 20) as usize - 1
+}
+
+/// Returns the XP thresholds for a single character of the given level.
+pub fn character_thresholds(level: u32) -> Thresholds {
+    let [easy, medium, hard, deadly] = THRESHOLDS[level_index(level)];
+    Thresholds { easy, medium, hard, deadly }
+}
+
+/// Returns the XP budget for a single character of the given level.
+pub fn character_budget(level: u32) -> Budget {
+    let [low, moderate, high] = BUDGETS[level_index(level)];
+    Budget { low, moderate, high }
+}
+
+/// Returns the encounter multiplier for the given number of monsters, adjusted for the size of the
+/// party.
+///
+/// Parties with fewer than three characters use the next highest multiplier, and parties with six
+/// or more characters use the next lowest multiplier.
+pub fn encounter_multiplier(monster_count: usize, party_size: usize) -> f32 {
+    if monster_count == 0 {
+        return 0.0;
+    }
+
+    let step: usize = match monster_count {
+        1 => 1,
+        2 => 2,
+        3..=6 => 3,
+        7..=10 => 4,
+        11..=14 => 5,
+        _ => 6,
+    };
+    let step = match party_size {
+        0..=2 => step + 1,
+        3..=5 => step,
+        _ => step - 1,
+    };
+    MULTIPLIERS[step]
+}
+
+/// The characters in the party, described by their levels.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Party {
+    /// The level of each character in the party.
+    pub levels: Vec<u32>,
+}
+
+impl Party {
+    /// Create a party with characters of the given levels.
+    pub fn new(levels: impl Into<Vec<u32>>) -> Self {
+        Self { levels: levels.into() }
+    }
+
+    /// Create a party of `count` characters, all of the same level.
+    pub fn uniform(count: usize, level: u32) -> Self {
+        Self { levels: vec![level; count] }
+    }
+
+    /// Returns the number of characters in the party.
+    pub fn size(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Returns `true` if there are no characters in the party.
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    /// Returns the party's combined XP thresholds.
+    pub fn thresholds(&self) -> Thresholds {
+        self.levels
+            .iter()
+            .map(|level| character_thresholds(*level))
+            .fold(Thresholds::default(), |acc, t| Thresholds {
+                easy: acc.easy + t.easy,
+                medium: acc.medium + t.medium,
+                hard: acc.hard + t.hard,
+                deadly: acc.deadly + t.deadly,
+            })
+    }
+
+    /// Returns the party's combined XP budget.
+    pub fn budget(&self) -> Budget {
+        self.levels
+            .iter()
+            .map(|level| character_budget(*level))
+            .fold(Budget::default(), |acc, b| Budget {
+                low: acc.low + b.low,
+                moderate: acc.moderate + b.moderate,
+                high: acc.high + b.high,
+            })
+    }
+
+    /// Rate an encounter against this party, given the XP value of each monster in it.
+    pub fn evaluate(
+        &self,
+        monster_xp: impl IntoIterator<Item = u32>,
+        method: Method,
+    ) -> EncounterDifficulty {
+        let (count, total_xp) = monster_xp
+            .into_iter()
+            .fold((0, 0), |(count, total), xp| (count + 1, total + xp));
+
+        match method {
+            Method::Dmg2014 => {
+                let multiplier = encounter_multiplier(count, self.size());
+                let adjusted_xp = (total_xp as f32 * multiplier) as u32;
+                let thresholds = self.thresholds();
+                let difficulty = if adjusted_xp >= thresholds.deadly {
+                    Difficulty::Deadly
+                } else if adjusted_xp >= thresholds.hard {
+                    Difficulty::Hard
+                } else if adjusted_xp >= thresholds.medium {
+                    Difficulty::Medium
+                } else if adjusted_xp >= thresholds.easy {
+                    Difficulty::Easy
+                } else {
+                    Difficulty::Trivial
+                };
+                EncounterDifficulty { method, total_xp, multiplier, adjusted_xp, difficulty }
+            },
+            Method::Dmg2024 => {
+                // budgets are upper bounds: an encounter is rated by the smallest budget it fits in
+                let budget = self.budget();
+                let difficulty = if total_xp == 0 {
+                    Difficulty::Trivial
+                } else if total_xp <= budget.low {
+                    Difficulty::Easy
+                } else if total_xp <= budget.moderate {
+                    Difficulty::Medium
+                } else if total_xp <= budget.high {
+                    Difficulty::Hard
+                } else {
+                    Difficulty::Deadly
+                };
+                EncounterDifficulty {
+                    method,
+                    total_xp,
+                    multiplier: 1.0,
+                    adjusted_xp: total_xp,
+                    difficulty,
+                }
+            },
+        }
+    }
+}
+
+/// The result of rating an encounter's difficulty.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EncounterDifficulty {
+    /// The method used to rate the encounter.
+    pub method: Method,
+
+    /// The sum of the XP of every monster in the encounter.
+    pub total_xp: u32,
+
+    /// The encounter multiplier applied to the total XP. This is always `1.0` for
+    /// [`Method::Dmg2024`].
+    pub multiplier: f32,
+
+    /// The total XP after applying the encounter multiplier, which is compared against the party's
+    /// thresholds.
+    pub adjusted_xp: u32,
+
+    /// The difficulty rating of the encounter.
+    pub difficulty: Difficulty,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Combatant, Monster, Side, Tracker};
+
+    /// Ensure the encounter multiplier accounts for monster count and party size.
+    #[test]
+    fn multiplier() {
+        assert_eq!(encounter_multiplier(0, 4), 0.0);
+        assert_eq!(encounter_multiplier(1, 4), 1.0);
+        assert_eq!(encounter_multiplier(2, 4), 1.5);
+        assert_eq!(encounter_multiplier(6, 4), 2.0);
+        assert_eq!(encounter_multiplier(10, 4), 2.5);
+        assert_eq!(encounter_multiplier(14, 4), 3.0);
+        assert_eq!(encounter_multiplier(15, 4), 4.0);
+
+        // small and large parties
+        assert_eq!(encounter_multiplier(1, 2), 1.5);
+        assert_eq!(encounter_multiplier(15, 1), 5.0);
+        assert_eq!(encounter_multiplier(1, 6), 0.5);
+    }
+
+    /// Ensure encounters are rated per the DMG examples.
+    #[test]
+    fn evaluate() {
+        // 2014 DMG example: party of four 3rd-level characters (thresholds 300 / 600 / 900 / 1600)
+        // against a bugbear (200 XP) and three hobgoblins (100 XP each)
+        let party = Party::uniform(4, 3);
+        let result = party.evaluate([200, 100, 100, 100], Method::Dmg2014);
+        assert_eq!(result.total_xp, 500);
+        assert_eq!(result.adjusted_xp, 1000);
+        assert_eq!(result.difficulty, Difficulty::Hard);
+
+        // same encounter with the 2024 budget (low 600, moderate 900, high 1600)
+        let result = party.evaluate([200, 100, 100, 100], Method::Dmg2024);
+        assert_eq!(result.adjusted_xp, 500);
+        assert_eq!(result.difficulty, Difficulty::Easy);
+
+        let result = party.evaluate([], Method::Dmg2014);
+        assert_eq!(result.difficulty, Difficulty::Trivial);
+    }
+
+    /// Ensure only monsters hostile to the party count towards the tracker's rating.
+    #[test]
+    fn hostile_monsters() {
+        let ogre = |side| {
+            let mut ogre = Combatant::from(Monster {
+                xp: 450,
+                hit_points: 59,
+                ..Default::default()
+            });
+            ogre.side = side;
+            ogre
+        };
+        let mut tracker = Tracker::new(vec![
+            ogre(Side::Enemy),
+            ogre(Side::Party),
+            ogre(Side::Neutral),
+            ogre(Side::Custom("Cultists".to_string())),
+        ]);
+        tracker.party = Party::uniform(4, 3);
+        assert_eq!(tracker.difficulty().unwrap().total_xp, 900);
+    }
+}
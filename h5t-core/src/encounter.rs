@@ -0,0 +1,749 @@
+//! Encounter definition files.
+//!
+//! An encounter lists the monsters (by their [`Monster::index`] in a [`Bestiary`]) and player
+//! characters that take part in a fight, along with how they start. Encounters are written in
+//! TOML:
+//!
+//! ```toml
+//! name = "Goblin ambush"
+//! difficulty_method = "dmg2014"
+//!
+//! [[player]]
+//! name = "Thorin"
+//! level = 3
+//! armor_class = 18
+//! max_hit_points = 28
+//! initiative = 15
+//!
+//! [[monster]]
+//! index = "goblin"
+//! count = 4
+//! names = ["Snik", "Grub"]
+//! hit_points = "roll"
+//!
+//! [[monster]]
+//! index = "bugbear"
+//! hit_points = 30
+//! initiative = 18
+//! conditions = ["invisible", { kind = "poisoned", duration = { rounds = 3 } }]
+//! ```
+//!
+//! [`Monster::index`]: crate::Monster::index
+
+use crate::{
+    difficulty::Method,
+    dice::Expr,
+    mob::{Mob, MobMember},
+    naming,
+    Bestiary,
+    Combatant,
+    CombatantKind,
+    Condition,
+    ConditionDuration,
+    ConditionKind,
+    InitiativeMode,
+    NamingScheme,
+    Party,
+    Player,
+    Side,
+    Tracker,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{fmt, path::Path};
+
+/// How a monster's starting hit points are determined from its stat block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HitPointPolicy {
+    /// Use the average hit points printed in the stat block.
+    #[default]
+    Average,
+
+    /// Roll the stat block's hit dice.
+    Roll,
+
+    /// Use the maximum possible result of the stat block's hit dice.
+    Max,
+}
+
+/// A monster's starting hit points: either a policy, or a fixed amount.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum HitPoints {
+    /// Start with exactly this many hit points.
+    Fixed(i32),
+
+    /// Determine hit points from the stat block.
+    Policy(HitPointPolicy),
+}
+
+impl Default for HitPoints {
+    fn default() -> Self {
+        HitPoints::Policy(HitPointPolicy::default())
+    }
+}
+
+/// A condition a combatant starts the encounter with.
+///
+/// This can be written as just the condition's name, in which case it lasts until it is removed,
+/// or as a full [`Condition`] with a duration.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum StartingCondition {
+    /// The condition lasts until it is removed.
+    Kind(ConditionKind),
+
+    /// The condition lasts for the given duration.
+    Condition(Condition),
+}
+
+impl From<StartingCondition> for Condition {
+    fn from(condition: StartingCondition) -> Self {
+        match condition {
+            StartingCondition::Kind(kind) => Condition { kind, duration: ConditionDuration::Forever },
+            StartingCondition::Condition(condition) => condition,
+        }
+    }
+}
+
+impl From<Condition> for StartingCondition {
+    fn from(condition: Condition) -> Self {
+        match condition.duration {
+            ConditionDuration::Forever => StartingCondition::Kind(condition.kind),
+            _ => StartingCondition::Condition(condition),
+        }
+    }
+}
+
+/// An alternate form a combatant starts in, such as a druid's Wild Shape.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StartingForm {
+    /// The form's monster index in the bestiary.
+    pub index: String,
+
+    /// The form's current hit points. If not given, the form starts at full hit points.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hit_points: Option<i32>,
+}
+
+impl StartingForm {
+    /// Describe the form a combatant is in, if it is transformed.
+    fn from_combatant(combatant: &Combatant) -> Option<Self> {
+        combatant.form.as_ref().map(|form| StartingForm {
+            index: form.monster.index.clone(),
+            hit_points: Some(combatant.hit_points),
+        })
+    }
+}
+
+fn one() -> usize {
+    1
+}
+
+fn is_one(count: &usize) -> bool {
+    *count == 1
+}
+
+/// One or more copies of a monster from the bestiary.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MonsterEntry {
+    /// The monster's index in the bestiary.
+    pub index: String,
+
+    /// The number of copies of the monster.
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    pub count: usize,
+
+    /// Display names for the copies, in order. Copies without a name use the stat block's name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub names: Vec<String>,
+
+    /// How the copies' starting hit points are determined.
+    #[serde(default)]
+    pub hit_points: HitPoints,
+
+    /// The copies' hit point maximum. If not given, it is the same as their starting hit points.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_hit_points: Option<i32>,
+
+    /// The initiative of the copies. If not given, initiative is rolled for each copy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initiative: Option<i32>,
+
+    /// Conditions the copies start with.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<StartingCondition>,
+
+    /// Whether the combatants start surprised, losing their turn in the first round.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub surprised: bool,
+
+    /// Whether the copies form a single mob, sharing one place in the order of play, instead of
+    /// each acting on its own. The mob's display name is the first of [`MonsterEntry::names`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mob: bool,
+
+    /// The mob's members, each with its own hit points and conditions, such as for a mob saved
+    /// partway through a fight. If given, these replace `count` and `hit_points`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<MobMember>,
+
+    /// The side the copies fight on, if not [`Side::Enemy`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub side: Option<Side>,
+
+    /// The name the players know the copies by until they are identified, such as "Hooded figure".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+
+    /// Whether the copies are minions, which drop from any damage. Minions of the same monster
+    /// form one group, which shares overflow damage and attacks as a unit.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub minion: bool,
+
+    /// The alternate form the copies start in, if any. The hit points above are those of the
+    /// copies' original form. Mobs can't start transformed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub form: Option<StartingForm>,
+}
+
+/// A player character.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PlayerEntry {
+    /// The player character.
+    #[serde(flatten)]
+    pub player: Player,
+
+    /// The character's current hit points. If not given, the character starts at their maximum.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hit_points: Option<i32>,
+
+    /// The character's initiative. If not given, initiative is rolled for the character.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initiative: Option<i32>,
+
+    /// Conditions the character starts with.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<StartingCondition>,
+
+    /// Whether the combatants start surprised, losing their turn in the first round.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub surprised: bool,
+
+    /// The side the character fights on, if not [`Side::Party`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub side: Option<Side>,
+
+    /// The alternate form the character starts in, if any. The hit points above are those of the
+    /// character's original form.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub form: Option<StartingForm>,
+}
+
+/// An encounter definition, which can be built into a [`Tracker`].
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Encounter {
+    /// A name for the encounter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The method used to rate the encounter's difficulty.
+    #[serde(default)]
+    pub difficulty_method: Method,
+
+    /// How to name monsters that share a stat block and have no name given in `names`.
+    #[serde(default)]
+    pub naming: NamingScheme,
+
+    /// How initiative is rolled, and how the turn passes from one combatant to the next. With side
+    /// or group initiative, the initiative given for a player or monster is ignored, as the side or
+    /// group rolls together.
+    #[serde(default)]
+    pub initiative_mode: InitiativeMode,
+
+    /// Whether the monsters are ambushing the party. If so, each monster's Stealth is rolled
+    /// against each player's passive Perception to decide which players are surprised.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ambush: bool,
+
+    /// The player characters taking part in the encounter.
+    #[serde(default, rename = "player")]
+    pub players: Vec<PlayerEntry>,
+
+    /// The monsters taking part in the encounter.
+    #[serde(default, rename = "monster")]
+    pub monsters: Vec<MonsterEntry>,
+}
+
+/// An error that occurred while loading, saving, or building an [`Encounter`].
+#[derive(Debug)]
+pub enum EncounterError {
+    /// The encounter file could not be read or written.
+    Io(std::io::Error),
+
+    /// The encounter file is not a valid encounter.
+    Parse(toml::de::Error),
+
+    /// The encounter could not be written as TOML.
+    Serialize(toml::ser::Error),
+
+    /// The encounter refers to a monster index that is not in the bestiary.
+    UnknownMonster(String),
+
+    /// A saved combatant is neither a monster nor a player, or the saved turn is out of range.
+    InvalidCombatant,
+}
+
+impl fmt::Display for EncounterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncounterError::Io(error) => write!(f, "{}", error),
+            EncounterError::Parse(error) => write!(f, "{}", error),
+            EncounterError::Serialize(error) => write!(f, "{}", error),
+            EncounterError::UnknownMonster(index) => write!(f, "unknown monster `{}`", index),
+            EncounterError::InvalidCombatant => write!(f, "invalid saved combatant"),
+        }
+    }
+}
+
+impl std::error::Error for EncounterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EncounterError::Io(error) => Some(error),
+            EncounterError::Parse(error) => Some(error),
+            EncounterError::Serialize(error) => Some(error),
+            EncounterError::UnknownMonster(_) | EncounterError::InvalidCombatant => None,
+        }
+    }
+}
+
+impl Encounter {
+    /// Parse an encounter from TOML.
+    pub fn from_toml(text: &str) -> Result<Self, EncounterError> {
+        toml::from_str(text).map_err(EncounterError::Parse)
+    }
+
+    /// Write the encounter as TOML.
+    pub fn to_toml(&self) -> Result<String, EncounterError> {
+        toml::to_string(self).map_err(EncounterError::Serialize)
+    }
+
+    /// Load an encounter from a TOML file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, EncounterError> {
+        let text = std::fs::read_to_string(path).map_err(EncounterError::Io)?;
+        Self::from_toml(&text)
+    }
+
+    /// Save the encounter to a TOML file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), EncounterError> {
+        std::fs::write(path, self.to_toml()?).map_err(EncounterError::Io)
+    }
+
+    /// Describe the current state of a tracker as an encounter, such that building the encounter
+    /// recreates the tracker's combatants with their current hit points, initiative, conditions,
+    /// and forms.
+    pub fn from_tracker(tracker: &Tracker) -> Self {
+        let mut encounter = Encounter {
+            name: None,
+            difficulty_method: tracker.difficulty_method,
+            naming: tracker.naming.clone(),
+            initiative_mode: tracker.initiative_mode,
+            ambush: false,
+            players: Vec::new(),
+            monsters: Vec::new(),
+        };
+
+        for combatant in &tracker.combatants {
+            let conditions = combatant.conditions
+                .iter()
+                .cloned()
+                .map(StartingCondition::from)
+                .collect();
+            let surprised = combatant.surprised && tracker.round == 0;
+            let side = (combatant.side != Side::default_for(&combatant.kind))
+                .then(|| combatant.side.clone());
+            // a transformed combatant is saved with its original hit points, and its form's
+            // hit points are saved with the form
+            let (hit_points, max_hit_points) = match &combatant.form {
+                Some(form) => (form.original_hit_points, form.original_max_hit_points),
+                None => (combatant.hit_points, combatant.max_hit_points),
+            };
+            let form = StartingForm::from_combatant(combatant);
+            match (&combatant.kind, &combatant.mob) {
+                // every member is saved with its own hit points and conditions, dead or alive
+                (CombatantKind::Monster(monster), Some(mob)) => {
+                    encounter.monsters.push(MonsterEntry {
+                        index: monster.index.clone(),
+                        count: mob.members.len(),
+                        names: combatant.display_name.iter().cloned().collect(),
+                        hit_points: HitPoints::Fixed(mob.member_max_hit_points),
+                        max_hit_points: Some(mob.member_max_hit_points),
+                        initiative: Some(combatant.initiative),
+                        conditions,
+                        surprised,
+                        side,
+                        alias: combatant.alias.clone(),
+                        mob: true,
+                        members: mob.members.clone(),
+                        minion: combatant.is_minion(),
+                        form: None,
+                    });
+                },
+                (CombatantKind::Monster(monster), None) => encounter.monsters.push(MonsterEntry {
+                    index: monster.index.clone(),
+                    count: 1,
+                    names: combatant.display_name.iter().cloned().collect(),
+                    hit_points: HitPoints::Fixed(hit_points),
+                    max_hit_points: Some(max_hit_points),
+                    initiative: Some(combatant.initiative),
+                    conditions,
+                    surprised,
+                    side,
+                    alias: combatant.alias.clone(),
+                    mob: false,
+                    members: Vec::new(),
+                    minion: combatant.is_minion(),
+                    form,
+                }),
+                (CombatantKind::Player(player), _) => encounter.players.push(PlayerEntry {
+                    player: player.clone(),
+                    hit_points: Some(hit_points),
+                    initiative: Some(combatant.initiative),
+                    conditions,
+                    surprised,
+                    side,
+                    form,
+                }),
+            }
+        }
+
+        encounter
+    }
+
+    /// Build a [`Tracker`] for the encounter, looking up monsters in the given bestiary.
+    ///
+    /// Initiative is rolled for every combatant without an initiative override, and the tracker's
+    /// party is made up of the encounter's player characters.
+    pub fn build(&self, bestiary: &Bestiary, rng: &mut impl Rng) -> Result<Tracker, EncounterError> {
+        let mut combatants = Vec::new();
+
+        for entry in &self.players {
+            let mut combatant = Combatant::from(entry.player.clone());
+            if let Some(hit_points) = entry.hit_points {
+                combatant.hit_points = hit_points;
+            }
+            if let Some(side) = &entry.side {
+                combatant.side = side.clone();
+            }
+            transform(&mut combatant, entry.form.as_ref(), bestiary)?;
+            start(&mut combatant, entry.initiative, &entry.conditions, entry.surprised, rng);
+            combatants.push(combatant);
+        }
+
+        for entry in &self.monsters {
+            let monster = bestiary
+                .get(&entry.index)
+                .ok_or_else(|| EncounterError::UnknownMonster(entry.index.clone()))?;
+
+            let roll_hit_points = |rng: &mut _| match entry.hit_points {
+                HitPoints::Fixed(hit_points) => hit_points,
+                HitPoints::Policy(policy) => {
+                    // fall back to the printed average if the hit dice can't be parsed
+                    let expr = monster.hit_points_roll.parse::<Expr>();
+                    match (policy, expr) {
+                        (HitPointPolicy::Roll, Ok(expr)) => expr.roll(rng).total.max(1),
+                        (HitPointPolicy::Max, Ok(expr)) => expr.max(),
+                        _ => monster.hit_points,
+                    }
+                },
+            };
+
+            if entry.mob {
+                let members = if entry.members.is_empty() {
+                    (0..entry.count)
+                        .map(|_| MobMember {
+                            hit_points: roll_hit_points(rng),
+                            conditions: Vec::new(),
+                        })
+                        .collect()
+                } else {
+                    entry.members.clone()
+                };
+                let max = entry.max_hit_points.unwrap_or_else(|| members
+                    .iter()
+                    .map(|member| member.hit_points)
+                    .max()
+                    .unwrap_or_default());
+                let mut combatant = Combatant::from(monster.clone());
+                combatant.display_name = entry.names
+                    .first()
+                    .cloned()
+                    .or_else(|| Some(naming::plural(&monster.name)));
+                combatant.mob = Some(Mob { member_max_hit_points: max, members });
+                combatant.minion = entry.minion.then(|| entry.index.clone());
+                if let Some(side) = &entry.side {
+                    combatant.side = side.clone();
+                }
+                combatant.alias = entry.alias.clone();
+                combatant.update_mob_hit_points();
+                start(&mut combatant, entry.initiative, &entry.conditions, entry.surprised, rng);
+                combatants.push(combatant);
+                continue;
+            }
+
+            for i in 0..entry.count {
+                let mut combatant = Combatant::from(monster.clone());
+                combatant.display_name = entry.names.get(i).cloned();
+                combatant.hit_points = roll_hit_points(rng);
+                combatant.max_hit_points = entry.max_hit_points.unwrap_or(combatant.hit_points);
+                combatant.minion = entry.minion.then(|| entry.index.clone());
+                if let Some(side) = &entry.side {
+                    combatant.side = side.clone();
+                }
+                combatant.alias = entry.alias.clone();
+                transform(&mut combatant, entry.form.as_ref(), bestiary)?;
+                start(&mut combatant, entry.initiative, &entry.conditions, entry.surprised, rng);
+                combatants.push(combatant);
+            }
+        }
+
+        let mut tracker = Tracker::new(combatants);
+        tracker.party = Party::new(self.players
+            .iter()
+            .map(|entry| entry.player.level)
+            .collect::<Vec<_>>());
+        tracker.difficulty_method = self.difficulty_method;
+        tracker.naming = self.naming.clone();
+        tracker.initiative_mode = self.initiative_mode;
+        tracker.disambiguate_names();
+        match self.initiative_mode {
+            InitiativeMode::Side | InitiativeMode::Group => tracker.roll_initiative(rng),
+            _ => tracker.sort_by_initiative(),
+        }
+        if self.ambush {
+            tracker.roll_surprise(rng);
+        }
+        tracker.start();
+        Ok(tracker)
+    }
+}
+
+/// Put a combatant in its starting form, if it has one, looking the form up in the given bestiary.
+fn transform(
+    combatant: &mut Combatant,
+    form: Option<&StartingForm>,
+    bestiary: &Bestiary,
+) -> Result<(), EncounterError> {
+    let Some(form) = form else {
+        return Ok(());
+    };
+    let monster = bestiary
+        .get(&form.index)
+        .cloned()
+        .ok_or_else(|| EncounterError::UnknownMonster(form.index.clone()))?;
+    combatant.assume_form(monster);
+    if let Some(hit_points) = form.hit_points {
+        combatant.hit_points = hit_points;
+    }
+    Ok(())
+}
+
+/// Set a combatant's initiative (rolling it if no override is given), starting conditions, and
+/// whether they are surprised.
+fn start(
+    combatant: &mut Combatant,
+    initiative: Option<i32>,
+    conditions: &[StartingCondition],
+    surprised: bool,
+    rng: &mut impl Rng,
+) {
+    match initiative {
+        Some(initiative) => combatant.initiative = initiative,
+        None => {
+            combatant.roll_initiative(rng);
+        },
+    }
+    for condition in conditions {
+        combatant.apply_condition(condition.clone().into());
+    }
+    if surprised {
+        combatant.surprise();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Monster;
+
+    /// Ensure an encounter file builds a tracker, and that the tracker can be saved back out.
+    #[test]
+    fn build_and_save() {
+        let bestiary = Bestiary::from_iter([Monster {
+            index: "goblin".to_string(),
+            name: "Goblin".to_string(),
+            hit_points: 7,
+            hit_points_roll: "2d6".to_string(),
+            ..Default::default()
+        }]);
+        let encounter = Encounter::from_toml(r#"
+            naming = "colors"
+
+            [[player]]
+            name = "Thorin"
+            level = 3
+            armor_class = 18
+            max_hit_points = 28
+            initiative = 15
+
+            [[monster]]
+            index = "goblin"
+            count = 3
+            names = ["Snik"]
+            hit_points = "max"
+            initiative = 12
+            conditions = ["prone", { kind = "poisoned", duration = { rounds = 3 } }]
+
+            [[monster]]
+            index = "goblin"
+            count = 5
+            hit_points = 5
+            initiative = 1
+            mob = true
+        "#).unwrap();
+
+        let tracker = encounter.build(&bestiary, &mut rand::thread_rng()).unwrap();
+        assert_eq!(tracker.combatants.len(), 5);
+        assert_eq!(tracker.party.levels, [3]);
+        assert_eq!(tracker.combatants[0].name(), "Thorin");
+        assert_eq!(tracker.combatants[1].name(), "Snik");
+        assert_eq!(tracker.combatants[2].name(), "Red Goblin");
+        assert_eq!(tracker.combatants[3].name(), "Blue Goblin");
+        assert_eq!(tracker.combatants[1].hit_points, 12);
+        assert_eq!(tracker.combatants[1].conditions.len(), 2);
+        assert_eq!(tracker.combatants[4].name(), "Goblins");
+        assert_eq!(tracker.combatants[4].hit_points, 25);
+
+        // saving and rebuilding gives the same combatants
+        let saved = Encounter::from_tracker(&tracker).to_toml().unwrap();
+        let rebuilt = Encounter::from_toml(&saved)
+            .unwrap()
+            .build(&bestiary, &mut rand::thread_rng())
+            .unwrap();
+        let names = |tracker: &Tracker| tracker.combatants
+            .iter()
+            .map(|c| (c.name().to_string(), c.hit_points, c.max_hit_points, c.initiative, c.conditions.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(names(&tracker), names(&rebuilt));
+
+        let missing = Encounter::from_toml("[[monster]]\nindex = \"ogre\"").unwrap();
+        assert!(matches!(
+            missing.build(&bestiary, &mut rand::thread_rng()),
+            Err(EncounterError::UnknownMonster(_)),
+        ));
+    }
+
+    /// Ensure a mob is saved member by member, keeping dead members, wounds, and member
+    /// conditions.
+    #[test]
+    fn mob_members() {
+        let bestiary = Bestiary::from_iter([Monster {
+            index: "goblin".to_string(),
+            name: "Goblin".to_string(),
+            hit_points: 7,
+            ..Default::default()
+        }]);
+        let encounter = Encounter::from_toml(r#"
+            [[monster]]
+            index = "goblin"
+            count = 4
+            initiative = 10
+            mob = true
+        "#).unwrap();
+        let mut tracker = encounter.build(&bestiary, &mut rand::thread_rng()).unwrap();
+        tracker.damage_members(0, 7, 1);
+        tracker.damage_members(0, 3, 1);
+        tracker.inflict_member_condition(0, 3, Condition {
+            kind: ConditionKind::Prone,
+            duration: ConditionDuration::Forever,
+        });
+
+        let rebuild = |tracker: &Tracker| Encounter::from_toml(&Encounter::from_tracker(tracker)
+            .to_toml()
+            .unwrap())
+            .unwrap()
+            .build(&bestiary, &mut rand::thread_rng())
+            .unwrap();
+        let members = |tracker: &Tracker| tracker.combatants[0].mob
+            .as_ref()
+            .unwrap()
+            .members
+            .iter()
+            .map(|member| (member.hit_points, member.conditions.len()))
+            .collect::<Vec<_>>();
+        let rebuilt = rebuild(&tracker);
+        assert_eq!(members(&rebuilt), [(0, 0), (4, 0), (7, 0), (7, 1)]);
+        assert_eq!(rebuilt.combatants[0].hit_points, 18);
+        assert_eq!(rebuilt.combatants[0].max_hit_points, 28);
+
+        // a mob that has been wiped out stays that way
+        tracker.damage_members(0, 7, 3);
+        let rebuilt = rebuild(&tracker);
+        assert_eq!(rebuilt.combatants[0].mob.as_ref().unwrap().alive(), 0);
+        assert_eq!(members(&rebuilt).len(), 4);
+        assert_eq!(rebuilt.combatants[0].hit_points, 0);
+    }
+
+    /// Ensure transformed combatants are saved in their form, with their original hit points kept
+    /// for when they revert.
+    #[test]
+    fn forms() {
+        let bestiary = Bestiary::from_iter([
+            Monster {
+                index: "brown-bear".to_string(),
+                name: "Brown Bear".to_string(),
+                hit_points: 34,
+                ..Default::default()
+            },
+            Monster {
+                index: "doppelganger".to_string(),
+                name: "Doppelganger".to_string(),
+                hit_points: 52,
+                ..Default::default()
+            },
+        ]);
+        let encounter = Encounter::from_toml(r#"
+            [[player]]
+            name = "Keyleth"
+            level = 3
+            armor_class = 14
+            max_hit_points = 30
+            hit_points = 25
+            initiative = 15
+
+            [[monster]]
+            index = "doppelganger"
+            initiative = 10
+        "#).unwrap();
+        let mut tracker = encounter.build(&bestiary, &mut rand::thread_rng()).unwrap();
+        tracker.combatants[0].assume_form(bestiary.get("brown-bear").unwrap().clone());
+        tracker.combatants[0].damage(10);
+        tracker.combatants[1].damage(2);
+        tracker.combatants[1].assume_form(bestiary.get("brown-bear").unwrap().clone());
+
+        let saved = Encounter::from_tracker(&tracker).to_toml().unwrap();
+        let mut rebuilt = Encounter::from_toml(&saved)
+            .unwrap()
+            .build(&bestiary, &mut rand::thread_rng())
+            .unwrap();
+        let druid = &mut rebuilt.combatants[0];
+        assert_eq!((druid.hit_points, druid.max_hit_points), (24, 34));
+        assert_eq!(druid.revert_form().unwrap().monster.name, "Brown Bear");
+        assert_eq!((druid.hit_points, druid.max_hit_points), (25, 30));
+        let doppelganger = &mut rebuilt.combatants[1];
+        assert_eq!((doppelganger.hit_points, doppelganger.max_hit_points), (34, 34));
+        doppelganger.revert_form();
+        assert_eq!((doppelganger.hit_points, doppelganger.max_hit_points), (50, 52));
+    }
+}
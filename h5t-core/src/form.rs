@@ -0,0 +1,99 @@
+//! Alternate forms a combatant can assume, such as a druid's Wild Shape, a polymorph spell, or one
+//! of a shapechanger's listed forms.
+//!
+//! While transformed, a combatant uses the statistics of its new form and has a separate pool of
+//! hit points. Its original statistics stay in [`Combatant::kind`].
+
+use crate::{Combatant, CombatantKind, Monster, Player};
+
+/// A stat block a combatant has assumed in place of its own.
+#[derive(Clone, Debug)]
+pub struct Form {
+    /// The stat block of the form.
+    pub monster: Monster,
+
+    /// The combatant's hit points in its original form, restored when it reverts.
+    pub original_hit_points: i32,
+
+    /// The combatant's hit point maximum in its original form.
+    pub original_max_hit_points: i32,
+}
+
+/// The statistics a combatant is currently using, from [`Combatant::active_kind`].
+#[derive(Clone, Copy, Debug)]
+pub enum ActiveKind<'a> {
+    /// A monster's stat block: the combatant's own, or the form it has assumed.
+    Monster(&'a Monster),
+
+    /// A player character's own statistics.
+    Player(&'a Player),
+}
+
+impl Combatant {
+    /// Returns the statistics the combatant is currently using: its form's stat block if it is
+    /// transformed, and its own otherwise.
+    pub fn active_kind(&self) -> ActiveKind<'_> {
+        match (&self.form, &self.kind) {
+            (Some(form), _) => ActiveKind::Monster(&form.monster),
+            (None, CombatantKind::Monster(monster)) => ActiveKind::Monster(monster),
+            (None, CombatantKind::Player(player)) => ActiveKind::Player(player),
+        }
+    }
+
+    /// Assume the given stat block, with its hit points as a separate pool. If the combatant is
+    /// already transformed, it changes directly to the new form, and keeps its original hit points.
+    pub fn assume_form(&mut self, monster: Monster) {
+        let (original_hit_points, original_max_hit_points) = match self.form.take() {
+            Some(form) => (form.original_hit_points, form.original_max_hit_points),
+            None => (self.hit_points, self.max_hit_points),
+        };
+        self.hit_points = monster.hit_points;
+        self.max_hit_points = monster.hit_points;
+        self.form = Some(Form {
+            monster,
+            original_hit_points,
+            original_max_hit_points,
+        });
+    }
+
+    /// Revert to the combatant's original form, restoring its original hit points. Returns the
+    /// form it left, or [`None`] if it wasn't transformed.
+    pub fn revert_form(&mut self) -> Option<Form> {
+        let form = self.form.take()?;
+        self.hit_points = form.original_hit_points;
+        self.max_hit_points = form.original_max_hit_points;
+        Some(form)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{monster::ArmorClass, Player};
+
+    /// Ensure a form has its own hit points, and excess damage carries over when it drops to 0.
+    #[test]
+    fn wild_shape() {
+        let mut druid = Combatant::from(Player {
+            name: "Keyleth".to_string(),
+            armor_class: 14,
+            max_hit_points: 30,
+            ..Default::default()
+        });
+        druid.damage(5);
+        druid.assume_form(Monster {
+            name: "Brown Bear".to_string(),
+            armor_class: ArmorClass { value: 11, ..Default::default() },
+            hit_points: 34,
+            ..Default::default()
+        });
+        assert_eq!((druid.hit_points, druid.max_hit_points), (34, 34));
+        assert_eq!(druid.armor_class(), 11);
+        assert_eq!(druid.name(), "Keyleth");
+
+        druid.damage(40);
+        assert!(druid.form.is_none());
+        assert_eq!((druid.hit_points, druid.max_hit_points), (19, 30));
+        assert_eq!(druid.armor_class(), 14);
+    }
+}
@@ -0,0 +1,237 @@
+//! Initiative modes, which decide how the order of play is rolled and who goes next.
+
+use crate::{dice, score_to_modifier, CombatantKind, Side, Tracker};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How initiative is rolled, and how the turn passes from one combatant to the next.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InitiativeMode {
+    /// Each combatant rolls initiative, and combatants act from highest to lowest.
+    #[default]
+    Individual,
+
+    /// Each [`Side`] rolls a single d20. Every combatant on the side with the highest roll acts,
+    /// then every combatant on the next side, and so on.
+    Side,
+
+    /// Monsters that share a stat block roll initiative once and act together, while each player
+    /// rolls on their own.
+    Group,
+
+    /// The first combatant is decided by initiative. After that, whoever just acted picks who goes
+    /// next with [`Tracker::pass_turn`], from those who haven't acted yet this round.
+    Popcorn,
+
+    /// Each combatant rolls initiative again at the start of every round.
+    SpeedFactor,
+}
+
+impl std::fmt::Display for InitiativeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Individual => write!(f, "individual"),
+            Self::Side => write!(f, "side"),
+            Self::Group => write!(f, "group"),
+            Self::Popcorn => write!(f, "popcorn"),
+            Self::SpeedFactor => write!(f, "speed factor"),
+        }
+    }
+}
+
+impl Tracker {
+    /// Roll initiative for every combatant following the tracker's [`InitiativeMode`], and sort
+    /// the combatants by it.
+    pub fn roll_initiative(&mut self, rng: &mut impl Rng) {
+        match self.initiative_mode {
+            InitiativeMode::Side => {
+                // each side rolls once; sides that tie are kept apart when sorting
+                let mut rolls: HashMap<Side, i32> = HashMap::new();
+                for combatant in &mut self.combatants {
+                    combatant.initiative = *rolls
+                        .entry(combatant.side.clone())
+                        .or_insert_with(|| dice::d20(rng) as i32);
+                }
+            },
+            InitiativeMode::Group => {
+                let mut groups = HashMap::new();
+                for combatant in &mut self.combatants {
+                    let CombatantKind::Monster(monster) = &combatant.kind else {
+                        combatant.roll_initiative(rng);
+                        continue;
+                    };
+                    let modifier = score_to_modifier(combatant.scores().dexterity);
+                    combatant.initiative = *groups
+                        .entry(monster.index.clone())
+                        .or_insert_with(|| dice::d20(rng) as i32 + modifier);
+                }
+            },
+            InitiativeMode::Individual | InitiativeMode::Popcorn | InitiativeMode::SpeedFactor => {
+                for combatant in &mut self.combatants {
+                    combatant.roll_initiative(rng);
+                }
+            },
+        }
+        self.sort_by_initiative();
+    }
+
+    /// End the current combatant's turn and pass it to the combatant at the given index, as in
+    /// popcorn initiative.
+    ///
+    /// Returns `false` without changing the turn if that combatant has already acted this round.
+    /// Once everyone has acted, a new round starts, and the turn can be passed to anyone.
+    pub fn pass_turn(&mut self, to: usize) -> bool {
+        let round_over = self.combatants
+            .iter()
+            .all(|combatant| combatant.acted || combatant.delayed);
+        if to >= self.combatants.len() || !round_over && self.combatants[to].acted {
+            return false;
+        }
+
+        self.end_turn();
+        if round_over {
+            self.new_round();
+        }
+        self.turn = to;
+        if self.skips_turn() {
+            self.advance();
+        } else {
+            self.start_turn();
+        }
+        true
+    }
+
+    /// Returns the index of the combatant who goes after the current one by default, starting a
+    /// new round if everyone has acted.
+    pub(crate) fn next_in_order(&mut self) -> usize {
+        let len = self.combatants.len();
+        if self.initiative_mode != InitiativeMode::Popcorn {
+            // go down the order, passing over anyone moved there after acting, then back to anyone
+            // moved above the turn before acting, before starting a new round
+            let next = (self.turn + 1..len)
+                .chain(0..self.turn)
+                .find(|&i| !self.combatants[i].acted && !self.combatants[i].delayed);
+            return next.unwrap_or_else(|| {
+                self.new_round();
+                0
+            });
+        }
+
+        // in popcorn initiative, default to the next combatant down who hasn't acted yet
+        let waiting = |tracker: &Self| (1..=len)
+            .map(|offset| (tracker.turn + offset) % len)
+            .find(|&i| !tracker.combatants[i].acted && !tracker.combatants[i].delayed);
+        if let Some(next) = waiting(self) {
+            return next;
+        }
+        self.new_round();
+        waiting(self).unwrap_or(self.turn)
+    }
+
+    /// Start a new round. Everyone can act again, and with speed factor initiative, initiative is
+    /// rolled again and the order starts from the top.
+    pub(crate) fn new_round(&mut self) {
+        self.round += 1;
+        for combatant in &mut self.combatants {
+            combatant.acted = false;
+        }
+        if self.initiative_mode == InitiativeMode::SpeedFactor {
+            for combatant in &mut self.combatants {
+                combatant.roll_initiative(&mut self.rng);
+            }
+            self.sort_by_initiative();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Combatant, Condition, ConditionDuration, ConditionKind, Monster, Player};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// Ensure side initiative puts each side together, and popcorn initiative lets each combatant
+    /// act once per round, with conditions still counting down.
+    #[test]
+    fn modes() {
+        let goblin = || Combatant::from(Monster {
+            index: "goblin".to_string(),
+            name: "Goblin".to_string(),
+            ..Default::default()
+        });
+        let player = |name: &str| Combatant::from(Player {
+            name: name.to_string(),
+            ..Default::default()
+        });
+
+        let mut tracker = Tracker::new(vec![goblin(), player("Thorin"), goblin(), player("Kira")]);
+        tracker.initiative_mode = InitiativeMode::Side;
+        tracker.roll_initiative(&mut rand::thread_rng());
+        let sides = tracker.combatants
+            .iter()
+            .map(|combatant| matches!(combatant.kind, CombatantKind::Player(_)))
+            .collect::<Vec<_>>();
+        assert!(sides == [true, true, false, false] || sides == [false, false, true, true]);
+
+        let mut tracker = Tracker::new(vec![player("Thorin"), goblin(), player("Kira")]);
+        tracker.initiative_mode = InitiativeMode::Popcorn;
+        tracker.start();
+        tracker.current_combatant_mut().apply_condition(Condition {
+            kind: ConditionKind::Blinded,
+            duration: ConditionDuration::Rounds(std::num::NonZeroU32::MIN),
+        });
+
+        // Thorin picks Kira, who can't pass back to Thorin, so the goblin goes last
+        assert!(tracker.pass_turn(2));
+        assert!(tracker.combatants[0].conditions.is_empty());
+        assert!(!tracker.pass_turn(0));
+        tracker.next_turn();
+        assert_eq!((tracker.turn, tracker.round), (1, 0));
+
+        // with everyone done, the goblin can pick anyone to start the next round
+        assert!(tracker.pass_turn(1));
+        assert_eq!((tracker.turn, tracker.round), (1, 1));
+    }
+
+    /// Ensure speed factor initiative rerolls each round with the tracker's rng, so a seeded
+    /// tracker rerolls the same way every time.
+    #[test]
+    fn speed_factor() {
+        let reroll = || {
+            let goblins = (0..6).map(|_| Combatant::from(Monster::default()));
+            let mut tracker = Tracker::new(goblins.collect::<Vec<_>>());
+            tracker.initiative_mode = InitiativeMode::SpeedFactor;
+            tracker.rng = StdRng::seed_from_u64(7);
+            for _ in 0..6 {
+                tracker.next_turn();
+            }
+            assert_eq!((tracker.turn, tracker.round), (0, 1));
+            tracker.combatants.iter().map(|combatant| combatant.initiative).collect::<Vec<_>>()
+        };
+        let initiatives = reroll();
+        assert!(initiatives.iter().any(|&initiative| initiative != 0));
+        assert_eq!(initiatives, reroll());
+    }
+
+    /// Ensure side initiative works with more sides than a d20 has faces, keeping each side
+    /// together even when sides tie.
+    #[test]
+    fn many_sides() {
+        let mut combatants = Vec::new();
+        for _ in 0..2 {
+            for side in 0..25 {
+                let mut goblin = Combatant::from(Monster::default());
+                goblin.side = Side::Custom(format!("Warband {}", side));
+                combatants.push(goblin);
+            }
+        }
+        let mut tracker = Tracker::new(combatants);
+        tracker.initiative_mode = InitiativeMode::Side;
+        tracker.roll_initiative(&mut rand::thread_rng());
+        for pair in tracker.combatants.chunks(2) {
+            assert_eq!(pair[0].side, pair[1].side);
+        }
+    }
+}
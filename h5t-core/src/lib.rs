@@ -1,17 +1,57 @@
 pub mod ability;
+pub mod area;
+pub mod attack;
+pub mod bestiary;
+pub mod challenge;
+pub mod check;
 pub mod condition;
+pub mod dice;
+pub mod difficulty;
+pub mod encounter;
+pub mod form;
+pub mod initiative;
+pub mod log;
+pub mod minion;
+pub mod mob;
 pub mod monster;
+pub mod naming;
+pub mod player;
+pub mod player_view;
+pub mod rest;
+pub mod save;
+pub mod side;
+pub mod stats;
+pub mod summary;
 
-use ability::Modifier;
-pub use ability::{Ability, score_to_modifier};
+use ability::{Modifier, Score};
+pub use ability::{Ability, AbilityKind, SkillKind, score_to_modifier};
+pub use area::AreaEffect;
+pub use bestiary::Bestiary;
+pub use challenge::ChallengeRating;
+pub use check::Check;
 pub use condition::{Condition, ConditionKind, ConditionDuration};
+pub use difficulty::{Difficulty, EncounterDifficulty, Party};
+pub use encounter::Encounter;
+pub use form::ActiveKind;
+pub use initiative::InitiativeMode;
+pub use log::LogEntry;
 pub use monster::Monster;
+pub use naming::NamingScheme;
 use monster::Speed;
+pub use player::Player;
+pub use player_view::PlayerView;
+pub use rest::Rest;
+pub use save::Save;
+pub use side::Side;
+pub use stats::CombatStats;
+pub use summary::EncounterSummary;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 
 /// The number of resources available to the combatant, including action count, bonus action
 /// count, reaction count, and resources granted by classes (e.g. Superiority dice) and spells
 /// (e.g. Haste action).
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Resources {
     pub actions: u32,
     pub bonus_actions: u32,
@@ -32,80 +72,335 @@ impl Default for Resources {
 /// A combatant in the initiative tracker.
 ///
 /// Combatants can include player characters, monsters, NPCs, etc.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Combatant {
     /// The kind of combatant.
     pub kind: CombatantKind,
 
+    /// The name to display for the combatant, overriding the name of its stat block.
+    pub display_name: Option<String>,
+
+    /// The name shown to players instead of the combatant's name, such as "Hooded figure" for a
+    /// monster they haven't identified yet.
+    pub alias: Option<String>,
+
+    /// The side the combatant fights on.
+    pub side: Side,
+
+    /// The combatant's initiative. Combatants with higher initiative act first.
+    pub initiative: i32,
+
     /// The combatant's conditions.
     pub conditions: Vec<Condition>,
 
     /// The combatant's current hit points.
     pub hit_points: i32,
 
+    /// The combatant's hit point maximum. This starts at the stat block's hit points, but can
+    /// differ if the combatant's hit points were rolled.
+    pub max_hit_points: i32,
+
     /// The number of resources available to the combatant, including action count, bonus action
     /// count, reaction count, and resources granted by classes (e.g. Superiority dice) and spells
     /// (e.g. Haste action).
     pub resources: Resources,
+
+    /// Whether the combatant is delaying their turn. Delaying combatants are skipped in the order
+    /// of play until they re-enter it with [`Tracker::end_delay`].
+    pub delayed: bool,
+
+    /// The trigger of the action the combatant has readied, if any. The readied action is lost at
+    /// the start of the combatant's next turn.
+    pub readied: Option<String>,
+
+    /// Whether the combatant is surprised. A surprised combatant loses its turn in the first round
+    /// and can't take reactions until that turn ends. Use [`Combatant::surprise`] to set it.
+    pub surprised: bool,
+
+    /// The combatant's traits that can only be used a limited number of times between rests.
+    pub limited_uses: Vec<rest::LimitedUse>,
+
+    /// The combatant's spell slots, by spell level.
+    pub spell_slots: Vec<rest::SpellSlots>,
+
+    /// The number of hit dice the combatant has spent on short rests.
+    pub hit_dice_spent: u32,
+
+    /// The combatant's level of exhaustion, from 0 to 6.
+    pub exhaustion: u32,
+
+    /// The alternate form the combatant has assumed, if any. While transformed, the combatant uses
+    /// the form's statistics and hit points.
+    pub form: Option<form::Form>,
+
+    /// The members of the combatant, if it is a mob of identical monsters sharing one place in
+    /// the order of play. A mob's hit points are the total of its members'.
+    pub mob: Option<mob::Mob>,
+
+    /// The group the combatant belongs to, if it is a minion. Minions drop from any damage, and
+    /// attack together with the rest of their group.
+    pub minion: Option<String>,
+
+    /// Whether the combatant has taken (or lost) their turn this round. This decides who can still
+    /// be picked with [`InitiativeMode::Popcorn`], and who still acts this round when the order of
+    /// play changes.
+    pub acted: bool,
+
+    /// What the combatant has done and suffered so far in the fight.
+    pub stats: CombatStats,
+
+    /// The conditions the players can see on the combatant, if they apply. See
+    /// [`Combatant::player_conditions`].
+    pub visible_conditions: Vec<ConditionKind>,
 }
 
 impl From<CombatantKind> for Combatant {
     fn from(kind: CombatantKind) -> Self {
         match kind {
             CombatantKind::Monster(monster) => monster.into(),
+            CombatantKind::Player(player) => player.into(),
         }
     }
 }
 
 impl Combatant {
     /// Returns the combatant's name.
+    ///
+    /// This is the combatant's display name if it has one, otherwise the name of its stat block.
     pub fn name(&self) -> &str {
+        self.display_name.as_deref().unwrap_or_else(|| self.base_name())
+    }
+
+    /// Returns the name of the combatant's stat block, ignoring any display name.
+    pub fn base_name(&self) -> &str {
         match &self.kind {
             CombatantKind::Monster(monster) => &monster.name,
+            CombatantKind::Player(player) => &player.name,
+        }
+    }
+
+    /// Mark the combatant as surprised, taking away their reaction until their first turn ends.
+    pub fn surprise(&mut self) {
+        self.surprised = true;
+        self.resources.reactions = 0;
+    }
+
+    /// Returns the combatant's saving throw modifier for the given ability.
+    ///
+    /// Monsters use their saving throw proficiency if they have one, and the ability modifier
+    /// otherwise. Players always use the ability modifier.
+    pub fn saving_throw(&self, ability: AbilityKind) -> Modifier {
+        let modifier = score_to_modifier(*self.scores().get(ability));
+        match self.active_kind() {
+            ActiveKind::Monster(monster) => monster.proficiencies.saving_throws
+                .get(ability)
+                .unwrap_or(modifier),
+            ActiveKind::Player(_) => modifier,
+        }
+    }
+
+    /// Returns how the combatant responds to damage of the given type, such as `"fire"`.
+    ///
+    /// Monster resistances are free-form text, so any immunity, resistance, or vulnerability that
+    /// mentions the damage type counts.
+    pub fn resistance(&self, damage_type: &str) -> Resistance {
+        let ActiveKind::Monster(monster) = self.active_kind() else {
+            return Resistance::Normal;
+        };
+        let damage_type = damage_type.trim().to_lowercase();
+        if damage_type.is_empty() {
+            return Resistance::Normal;
+        }
+        let mentions = |list: &[String]| list
+            .iter()
+            .any(|entry| entry.to_lowercase().contains(&damage_type));
+
+        if mentions(&monster.damage_immunities) {
+            Resistance::Immune
+        } else if mentions(&monster.damage_resistances) {
+            Resistance::Resistant
+        } else if mentions(&monster.damage_vulnerabilities) {
+            Resistance::Vulnerable
+        } else {
+            Resistance::Normal
+        }
+    }
+
+    /// Returns true if the combatant has a trait with the given name, ignoring case.
+    pub fn has_trait(&self, name: &str) -> bool {
+        match self.active_kind() {
+            ActiveKind::Monster(monster) => monster.traits
+                .iter()
+                .any(|ability| ability.name.eq_ignore_ascii_case(name)),
+            ActiveKind::Player(_) => false,
+        }
+    }
+
+    /// Returns the combatant's passive Wisdom (Perception) score.
+    pub fn passive_perception(&self) -> i32 {
+        match self.active_kind() {
+            ActiveKind::Monster(monster) => monster.senses.passive_perception
+                .unwrap_or_else(|| 10 + self.skill_modifier(SkillKind::Perception)),
+            ActiveKind::Player(player) => player.passive_perception(),
         }
     }
 
     /// Returns the combatant's main armor class.
     pub fn armor_class(&self) -> u32 {
-        match &self.kind {
-            CombatantKind::Monster(monster) => monster.armor_class.value,
+        match self.active_kind() {
+            ActiveKind::Monster(monster) => monster.armor_class.value,
+            ActiveKind::Player(player) => player.armor_class,
         }
     }
 
     /// Returns the combatant's speed.
     pub fn speed(&self) -> &Speed {
-        match &self.kind {
-            CombatantKind::Monster(monster) => &monster.speed,
+        match self.active_kind() {
+            ActiveKind::Monster(monster) => &monster.speed,
+            ActiveKind::Player(player) => &player.speed,
         }
     }
 
-    /// Returns the combatant's maximum hit points.
-    pub fn max_hit_points(&self) -> i32 {
-        match &self.kind {
-            CombatantKind::Monster(monster) => monster.hit_points,
+    /// Returns the combatant's proficiency bonus.
+    pub fn proficiency_bonus(&self) -> Modifier {
+        match self.active_kind() {
+            ActiveKind::Monster(monster) => monster.proficiency_bonus,
+            ActiveKind::Player(player) => player.proficiency_bonus(),
         }
     }
 
-    /// Returns the combatant's proficiency bonus.
-    pub fn proficiency_bonus(&self) -> Modifier {
-        match &self.kind {
-            CombatantKind::Monster(monster) => monster.proficiency_bonus,
+    /// Returns the combatant's ability scores.
+    pub fn scores(&self) -> &Ability<Score> {
+        match self.active_kind() {
+            ActiveKind::Monster(monster) => &monster.scores,
+            ActiveKind::Player(player) => &player.scores,
         }
     }
 
+    /// Roll initiative for the combatant (a d20 plus its Dexterity modifier), and set its
+    /// initiative to the result.
+    pub fn roll_initiative(&mut self, rng: &mut impl Rng) -> i32 {
+        let modifier = score_to_modifier(self.scores().dexterity);
+        self.initiative = dice::d20(rng) as i32 + modifier;
+        self.initiative
+    }
+
+    /// Apply a condition to the combatant.
+    ///
+    /// If the combatant already has the condition, its duration is extended to the new duration if
+    /// the new duration is longer. Each application of [`ConditionKind::Exhaustion`] adds a level
+    /// of exhaustion.
+    pub fn apply_condition(&mut self, condition: Condition) {
+        if condition.kind == ConditionKind::Exhaustion {
+            self.exhaustion = (self.exhaustion + 1).min(6);
+        }
+
+        add_condition(&mut self.conditions, condition);
+    }
+
     /// Damage the combatant by the given amount.
     ///
-    /// The amount will not saturate to 0, meaning the combatant can have negative hit points.
+    /// The amount will not saturate to 0, meaning the combatant can have negative hit points. If
+    /// the combatant is in an alternate form that drops to 0 hit points, it reverts to its original
+    /// form, and any excess damage carries over.
+    ///
+    /// A minion drops to 0 hit points from any damage. Use [`Tracker::deal_damage`] to carry the
+    /// excess over to the rest of its group.
+    ///
+    /// A mob takes the damage on its first living member, and healing goes to its most wounded
+    /// member. Use [`Combatant::damage_members`] to damage several members at once.
     pub fn damage(&mut self, amount: i32) {
-        self.hit_points -= amount;
+        self.record_damage_taken(amount);
+        if let Some(mob) = &mut self.mob {
+            if amount < 0 {
+                mob.heal(-amount);
+            } else {
+                mob.damage(amount, 1);
+            }
+            return self.update_mob_hit_points();
+        }
+
+        // any damage drops a minion
+        if self.minion.is_some() && amount > 0 {
+            self.hit_points = self.hit_points.min(0);
+            return;
+        }
+
+        self.hit_points = self.hit_points.saturating_sub(amount);
+        if self.hit_points <= 0 && self.form.is_some() {
+            let excess = self.hit_points.saturating_neg();
+            self.revert_form();
+            self.hit_points = self.hit_points.saturating_sub(excess);
+        }
+    }
+}
+
+/// Add a condition to the list, or extend the duration of the same condition if it is already in
+/// the list and the new duration is longer.
+pub(crate) fn add_condition(conditions: &mut Vec<Condition>, condition: Condition) {
+    let existing_condition = conditions
+        .iter_mut()
+        .find(|c| c.kind == condition.kind);
+
+    if let Some(existing_condition) = existing_condition {
+        if condition.duration > existing_condition.duration {
+            existing_condition.duration = condition.duration;
+        }
+    } else {
+        conditions.push(condition);
+    }
+}
+
+/// How a combatant responds to a type of damage.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Resistance {
+    /// The combatant takes normal damage.
+    #[default]
+    Normal,
+
+    /// The combatant takes half damage.
+    Resistant,
+
+    /// The combatant takes no damage.
+    Immune,
+
+    /// The combatant takes double damage.
+    Vulnerable,
+}
+
+impl Resistance {
+    /// Apply the resistance to the given amount of damage. Halved damage is rounded down.
+    pub fn apply(&self, amount: i32) -> i32 {
+        match self {
+            Resistance::Normal => amount,
+            Resistance::Resistant => amount / 2,
+            Resistance::Immune => 0,
+            Resistance::Vulnerable => amount * 2,
+        }
+    }
+}
+
+impl std::fmt::Display for Resistance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Resistance::Normal => Ok(()),
+            Resistance::Resistant => write!(f, "resistant"),
+            Resistance::Immune => write!(f, "immune"),
+            Resistance::Vulnerable => write!(f, "vulnerable"),
+        }
     }
 }
 
 /// A kind of combatant.
-#[derive(Debug)]
+// there are only ever a handful of combatants, so boxing the monster isn't worth the indirection
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
 pub enum CombatantKind {
     /// Pre-made monster.
     Monster(Monster),
+
+    /// Player character.
+    Player(Player),
 }
 
 impl From<Monster> for CombatantKind {
@@ -118,9 +413,66 @@ impl From<Monster> for Combatant {
     fn from(monster: Monster) -> Self {
         Self {
             hit_points: monster.hit_points,
+            max_hit_points: monster.hit_points,
+            display_name: None,
+            alias: None,
+            side: Side::Enemy,
+            initiative: 0,
             conditions: Vec::new(),
+            limited_uses: rest::limited_uses(&monster),
+            spell_slots: rest::spell_slots(&monster),
             kind: monster.into(),
             resources: Resources::default(),
+            delayed: false,
+            readied: None,
+            surprised: false,
+            hit_dice_spent: 0,
+            exhaustion: 0,
+            form: None,
+            mob: None,
+            minion: None,
+            acted: false,
+            stats: CombatStats::default(),
+            visible_conditions: Vec::new(),
+        }
+    }
+}
+
+impl From<Player> for CombatantKind {
+    fn from(player: Player) -> Self {
+        Self::Player(player)
+    }
+}
+
+impl From<Player> for Combatant {
+    fn from(player: Player) -> Self {
+        Self {
+            hit_points: player.max_hit_points,
+            max_hit_points: player.max_hit_points,
+            display_name: None,
+            alias: None,
+            side: Side::Party,
+            initiative: 0,
+            conditions: Vec::new(),
+            limited_uses: Vec::new(),
+            spell_slots: player.spell_slots
+                .iter()
+                .enumerate()
+                .map(|(i, &max)| rest::SpellSlots { level: i as u32 + 1, max, used: 0 })
+                .collect(),
+            kind: player.into(),
+            resources: Resources::default(),
+            delayed: false,
+            readied: None,
+            surprised: false,
+            hit_dice_spent: 0,
+            exhaustion: 0,
+            form: None,
+            mob: None,
+            minion: None,
+            acted: false,
+            stats: CombatStats::default(),
+            visible_conditions: Vec::new(),
         }
     }
 }
@@ -139,6 +491,26 @@ pub struct Tracker {
 
     /// The list of combatants.
     pub combatants: Vec<Combatant>,
+
+    /// The party of player characters the encounter is balanced against. If the party is empty,
+    /// the encounter's difficulty is not calculated.
+    pub party: Party,
+
+    /// The method used to rate the encounter's difficulty.
+    pub difficulty_method: difficulty::Method,
+
+    /// How to name combatants that share a stat block.
+    pub naming: NamingScheme,
+
+    /// How initiative is rolled, and how the turn passes from one combatant to the next.
+    pub initiative_mode: InitiativeMode,
+
+    /// The combat log.
+    pub log: Vec<LogEntry>,
+
+    /// The random number generator for rolls the tracker makes on its own, such as rerolling
+    /// initiative each round with [`InitiativeMode::SpeedFactor`]. Seed it for repeatable rolls.
+    pub rng: StdRng,
 }
 
 impl Tracker {
@@ -148,33 +520,220 @@ impl Tracker {
             turn: 0,
             round: 0,
             combatants: combatants.into(),
+            party: Party::default(),
+            difficulty_method: difficulty::Method::default(),
+            naming: NamingScheme::default(),
+            initiative_mode: InitiativeMode::default(),
+            log: Vec::new(),
+            rng: StdRng::from_entropy(),
         }
     }
 
+    /// Give a display name to each combatant that shares its stat block with another combatant,
+    /// following the tracker's [`NamingScheme`].
+    ///
+    /// Combatants that already have a display name keep it, and new names never collide with
+    /// existing ones, so calling this again after adding or removing combatants leaves the other
+    /// names untouched.
+    pub fn disambiguate_names(&mut self) {
+        for i in 0..self.combatants.len() {
+            if self.combatants[i].display_name.is_some() {
+                continue;
+            }
+
+            let base = self.combatants[i].base_name().to_string();
+            let shared = self.combatants
+                .iter()
+                .enumerate()
+                .any(|(j, other)| j != i && other.base_name() == base);
+            if !shared {
+                continue;
+            }
+
+            let name = (0..)
+                .map(|n| self.naming.name(&base, n))
+                .find(|name| self.combatants.iter().all(|other| other.name() != name))
+                .unwrap();
+            self.combatants[i].display_name = Some(name);
+        }
+    }
+
+    /// Rate the difficulty of the encounter against the tracker's [`Party`], counting only the
+    /// monsters that are still standing and hostile to the party. Returns [`None`] if the party
+    /// is empty.
+    pub fn difficulty(&self) -> Option<EncounterDifficulty> {
+        if self.party.is_empty() {
+            return None;
+        }
+
+        let monster_xp = self.combatants
+            .iter()
+            .filter(|combatant| combatant.hit_points > 0)
+            .filter(|combatant| combatant.side.is_hostile_to(&Side::Party))
+            .filter_map(|combatant| match &combatant.kind {
+                CombatantKind::Monster(monster) => {
+                    let count = combatant.mob.as_ref().map_or(1, mob::Mob::alive);
+                    Some(std::iter::repeat_n(monster.xp.max(0) as u32, count))
+                },
+                CombatantKind::Player(_) => None,
+            })
+            .flatten();
+        Some(self.party.evaluate(monster_xp, self.difficulty_method))
+    }
+
+    /// Sort the combatants by initiative, from highest to lowest, and start from the top of the
+    /// order. Ties are broken by Dexterity score, then by the existing order.
+    ///
+    /// With [`InitiativeMode::Side`], sides that rolled the same result stay together: the side
+    /// that comes first in the existing order goes first.
+    pub fn sort_by_initiative(&mut self) {
+        let mut side_order = std::collections::HashMap::new();
+        if self.initiative_mode == InitiativeMode::Side {
+            for combatant in &self.combatants {
+                let next = side_order.len();
+                side_order.entry(combatant.side.clone()).or_insert(next);
+            }
+        }
+        self.combatants.sort_by(|a, b| {
+            b.initiative
+                .cmp(&a.initiative)
+                .then(side_order.get(&a.side).cmp(&side_order.get(&b.side)))
+                .then(b.scores().dexterity.cmp(&a.scores().dexterity))
+        });
+        self.turn = 0;
+    }
+
     /// Advance the tracker to the next combatant's turn.
+    ///
+    /// With [`InitiativeMode::Popcorn`], this is the next combatant down the order who hasn't
+    /// acted yet this round. Use [`Tracker::pass_turn`] to pick someone else.
     pub fn next_turn(&mut self) {
-        // advance condition durations
-        self.current_combatant_mut()
-            .conditions
-            .retain_mut(|c| {
-                let new_duration = c.duration.decrement();
-                if let Some(new) = new_duration {
-                    c.duration = new;
-                    true
-                } else {
-                    // condition expired
-                    false
-                }
-            });
+        self.end_turn();
+        self.advance();
+    }
+
+    /// End the current combatant's turn.
+    fn end_turn(&mut self) {
+        self.current_combatant_mut().acted = true;
+
+        // advance condition durations, including those of each mob member
+        let expire = |c: &mut Condition| {
+            let new_duration = c.duration.decrement();
+            if let Some(new) = new_duration {
+                c.duration = new;
+                true
+            } else {
+                // condition expired
+                false
+            }
+        };
+        let current = self.current_combatant_mut();
+        current.conditions.retain_mut(expire);
+        if let Some(mob) = &mut current.mob {
+            for member in &mut mob.members {
+                member.conditions.retain_mut(expire);
+            }
+        }
+    }
+
+    /// Pass the turn to the next combatant that can act, and start their turn.
+    fn advance(&mut self) {
+        // if no one can act, the turn comes back around to the current combatant
+        for _ in 0..self.combatants.len() {
+            self.turn = self.next_in_order();
+            if !self.skips_turn() {
+                break;
+            }
+        }
+        self.start_turn();
+    }
+
+    /// Returns `true` if the current combatant doesn't take their turn, because they are delaying
+    /// or lose their turn to surprise.
+    ///
+    /// A surprised combatant's lost turn ends right away, so they stop being surprised.
+    fn skips_turn(&mut self) -> bool {
+        let first_round = self.round == 0;
+        let combatant = self.current_combatant_mut();
+        if combatant.surprised && first_round {
+            combatant.surprised = false;
+            combatant.resources = Resources::default();
+            combatant.acted = true;
+            return true;
+        }
+        if combatant.delayed {
+            combatant.acted = true;
+        }
+        combatant.delayed
+    }
+
+    /// Start the first round of combat with the combatants in their current order, skipping the
+    /// turns of any surprised combatants at the top of the order.
+    pub fn start(&mut self) {
+        self.turn = 0;
+        self.round = 0;
+        if self.combatants.is_empty() {
+            return;
+        }
+        for combatant in &mut self.combatants {
+            combatant.acted = false;
+        }
+        if self.skips_turn() {
+            self.advance();
+        } else {
+            self.start_turn();
+        }
+    }
+
+    /// Roll each monster's Dexterity (Stealth) check against each player's passive Wisdom
+    /// (Perception), as when monsters ambush the party. A player that doesn't notice any of the
+    /// monsters, because their passive Perception is lower than every monster's check, is
+    /// surprised.
+    ///
+    /// Returns the indices of the players that were surprised.
+    pub fn roll_surprise(&mut self, rng: &mut impl Rng) -> Vec<usize> {
+        let lowest_stealth = self.combatants
+            .iter()
+            .filter(|combatant| matches!(combatant.kind, CombatantKind::Monster(_)))
+            .map(|combatant| dice::d20(rng) as i32 + combatant.skill_modifier(SkillKind::Stealth))
+            .min();
+        let Some(lowest_stealth) = lowest_stealth else {
+            return Vec::new();
+        };
 
-        self.turn = (self.turn + 1) % self.combatants.len();
-        if self.turn == 0 {
-            self.round += 1;
+        let surprised = self.combatants
+            .iter()
+            .enumerate()
+            .filter(|(_, combatant)| matches!(combatant.kind, CombatantKind::Player(_)))
+            .filter(|(_, combatant)| combatant.passive_perception() < lowest_stealth)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        for &i in &surprised {
+            self.combatants[i].surprise();
         }
+        surprised
+    }
+
+    /// Start the current combatant's turn.
+    fn start_turn(&mut self) {
+        let combatant = self.current_combatant_mut();
 
         // restore current combatant's actions at the start of their turn
         // TODO: will reset class and spell things when they shouldn't be reset
-        self.current_combatant_mut().resources = Resources::default();
+        combatant.resources = Resources::default();
+
+        // a readied action is lost if its trigger didn't occur before the combatant's next turn
+        combatant.readied = None;
+
+        // surprise only matters in the first round
+        combatant.surprised = false;
+
+        combatant.acted = true;
+    }
+
+    /// Write a message to the combat log, in the current round.
+    pub fn log(&mut self, message: impl Into<String>) {
+        self.log.push(LogEntry { round: self.round, message: message.into() });
     }
 
     /// Get the combatant that is currently taking their turn.
@@ -187,6 +746,166 @@ impl Tracker {
         &mut self.combatants[self.turn]
     }
 
+    /// Add a combatant in initiative order, after any combatants with the same initiative, and
+    /// return its index.
+    ///
+    /// If the combatant lands above the one whose turn it is, it first acts next round.
+    pub fn add(&mut self, combatant: Combatant) -> usize {
+        let index = self.combatants
+            .iter()
+            .position(|other| other.initiative < combatant.initiative)
+            .unwrap_or(self.combatants.len());
+        self.insert(index, combatant);
+        index
+    }
+
+    /// Insert a combatant at the given position in the order of play, keeping the turn with the
+    /// combatant currently taking it.
+    ///
+    /// The combatant is given a display name if it shares its stat block with another combatant.
+    pub fn insert(&mut self, index: usize, combatant: Combatant) {
+        self.combatants.insert(index, combatant);
+        if index <= self.turn && self.combatants.len() > 1 {
+            self.turn += 1;
+
+            // joining above the current turn, the combatant first acts next round
+            if self.initiative_mode != InitiativeMode::Popcorn {
+                self.combatants[index].acted = true;
+            }
+        }
+        self.disambiguate_names();
+    }
+
+    /// Add a fresh copy of a combatant, with full hit points and no conditions, right after it in
+    /// the order of play. Returns the index of the copy.
+    pub fn duplicate(&mut self, index: usize) -> usize {
+        let original = &self.combatants[index];
+        let mut copy = Combatant::from(original.kind.clone());
+        copy.initiative = original.initiative;
+        self.insert(index + 1, copy);
+        index + 1
+    }
+
+    /// Remove the combatant at the given index and return it. Returns [`None`] if the index is out
+    /// of range or the combatant is the last one left, as the tracker always needs someone whose
+    /// turn it is.
+    ///
+    /// If it was the combatant's turn, the turn passes to the next combatant, as if the removed
+    /// combatant had ended its turn.
+    pub fn remove(&mut self, index: usize) -> Option<Combatant> {
+        if index >= self.combatants.len() || self.combatants.len() == 1 {
+            return None;
+        }
+
+        let combatant = self.combatants.remove(index);
+        if index < self.turn {
+            self.turn -= 1;
+        } else if index == self.turn {
+            // in popcorn initiative, the round only ends once everyone has acted, and otherwise
+            // anyone moved above the turn before acting still acts this round
+            let popcorn = self.initiative_mode == InitiativeMode::Popcorn;
+            let waiting = self.combatants
+                .iter()
+                .any(|combatant| !combatant.acted && !combatant.delayed);
+            if self.turn == self.combatants.len() {
+                self.turn = 0;
+                if !popcorn && !waiting {
+                    self.new_round();
+                }
+            }
+            if self.current_combatant().acted || self.skips_turn() {
+                self.advance();
+            } else {
+                self.start_turn();
+            }
+        }
+        Some(combatant)
+    }
+
+    /// Move the combatant at index `from` to index `to` in the order of play, shifting the
+    /// combatants in between. The turn stays with the combatant currently taking it.
+    ///
+    /// Moving doesn't skip or repeat anyone's turn: a combatant moved above the current turn before
+    /// acting still acts this round, once the rest of the order has, and a combatant moved below
+    /// it after acting doesn't act again until the next round.
+    ///
+    /// Returns `false` without moving anyone if either index is out of bounds.
+    pub fn move_combatant(&mut self, from: usize, to: usize) -> bool {
+        if from >= self.combatants.len() || to >= self.combatants.len() {
+            return false;
+        }
+
+        let combatant = self.combatants.remove(from);
+        self.combatants.insert(to, combatant);
+
+        if from == self.turn {
+            self.turn = to;
+        } else if from < self.turn && to >= self.turn {
+            self.turn -= 1;
+        } else if from > self.turn && to <= self.turn {
+            self.turn += 1;
+        }
+        true
+    }
+
+    /// Delay the current combatant's turn. They leave the order of play until they re-enter it
+    /// with [`Tracker::end_delay`], and the turn passes to the next combatant.
+    ///
+    /// Returns `false` if there is no one else to pass the turn to.
+    pub fn delay(&mut self) -> bool {
+        if self.combatants.iter().filter(|combatant| !combatant.delayed).count() < 2 {
+            return false;
+        }
+        self.current_combatant_mut().delayed = true;
+        self.advance();
+        true
+    }
+
+    /// Bring a delaying combatant back into the order of play right after the current combatant,
+    /// so they take their turn next. Their initiative becomes the current combatant's, so they
+    /// keep their new place in later rounds.
+    pub fn end_delay(&mut self, index: usize) {
+        if !self.combatants[index].delayed {
+            return;
+        }
+        self.combatants[index].delayed = false;
+        self.combatants[index].acted = false;
+        if index == self.turn {
+            // everyone was delaying, so this combatant already holds the turn
+            return;
+        }
+
+        self.combatants[index].initiative = self.current_combatant().initiative;
+        let to = if index < self.turn { self.turn } else { self.turn + 1 };
+        self.move_combatant(index, to);
+    }
+
+    /// Ready an action for the current combatant, with a description of the trigger. This spends
+    /// their action, and their reaction is held to take the readied action with
+    /// [`Tracker::trigger_readied`].
+    ///
+    /// Returns `false` if the combatant had no action left to ready.
+    pub fn ready(&mut self, trigger: impl Into<String>) -> bool {
+        if !self.use_action() {
+            return false;
+        }
+        self.current_combatant_mut().readied = Some(trigger.into());
+        true
+    }
+
+    /// Take the readied action of the combatant at the given index, spending their reaction.
+    ///
+    /// Returns `false` if the combatant has no readied action or no reaction left.
+    pub fn trigger_readied(&mut self, index: usize) -> bool {
+        let combatant = &mut self.combatants[index];
+        if combatant.readied.is_none() || combatant.resources.reactions == 0 {
+            return false;
+        }
+        combatant.resources.reactions -= 1;
+        combatant.readied = None;
+        true
+    }
+
     /// Use an action for the current combatant. Returns `true` if the action was used, or `false`
     /// if the combatant had no actions left to use.
     ///
@@ -270,4 +989,176 @@ mod tests {
         assert_eq!(tracker.turn, 0);
         assert_eq!(tracker.round, 1);
     }
+
+    /// Ensure duplicates are numbered, and numbering stays stable when combatants leave and join.
+    #[test]
+    fn test_disambiguate_names() {
+        let goblin = Monster {
+            index: "goblin".to_string(),
+            name: "Goblin".to_string(),
+            ..Default::default()
+        };
+        let ogre = Monster {
+            index: "ogre".to_string(),
+            name: "Ogre".to_string(),
+            ..Default::default()
+        };
+        let mut tracker = Tracker::new(vec![
+            goblin.clone().into(),
+            ogre.into(),
+            goblin.clone().into(),
+            goblin.clone().into(),
+        ]);
+        tracker.disambiguate_names();
+        let names = tracker.combatants.iter().map(Combatant::name).collect::<Vec<_>>();
+        assert_eq!(names, ["Goblin 1", "Ogre", "Goblin 2", "Goblin 3"]);
+
+        tracker.combatants.remove(2);
+        tracker.combatants.push(goblin.into());
+        tracker.disambiguate_names();
+        let names = tracker.combatants.iter().map(Combatant::name).collect::<Vec<_>>();
+        assert_eq!(names, ["Goblin 1", "Ogre", "Goblin 3", "Goblin 2"]);
+    }
+
+    /// Ensure adding, removing and moving combatants keeps the turn with the same combatant.
+    #[test]
+    fn test_tracker_edit_order() {
+        let monster = |name: &str, initiative| {
+            let mut combatant = Combatant::from(Monster {
+                index: name.to_lowercase(),
+                name: name.to_string(),
+                ..Default::default()
+            });
+            combatant.initiative = initiative;
+            combatant
+        };
+        let names = |tracker: &Tracker| tracker.combatants
+            .iter()
+            .map(|c| c.name().to_string())
+            .collect::<Vec<_>>();
+        let mut tracker = Tracker::new(vec![
+            monster("Ogre", 20),
+            monster("Goblin", 15),
+            monster("Wolf", 10),
+        ]);
+        tracker.next_turn();
+        assert_eq!(tracker.current_combatant().name(), "Goblin");
+
+        // lands above the goblin, so it first acts next round
+        assert_eq!(tracker.add(monster("Bat", 18)), 1);
+        assert_eq!(tracker.current_combatant().name(), "Goblin");
+
+        assert_eq!(tracker.duplicate(3), 4);
+        assert_eq!(names(&tracker), ["Ogre", "Bat", "Goblin", "Wolf 1", "Wolf 2"]);
+
+        assert!(tracker.move_combatant(4, 0));
+        assert_eq!(tracker.current_combatant().name(), "Goblin");
+        assert!(tracker.move_combatant(1, 3));
+        assert!(!tracker.move_combatant(5, 0));
+        assert!(!tracker.move_combatant(0, 5));
+        assert_eq!(tracker.current_combatant().name(), "Goblin");
+        assert_eq!(names(&tracker), ["Wolf 2", "Bat", "Goblin", "Ogre", "Wolf 1"]);
+
+        // the ogre moved below the goblin after acting, so it doesn't act again, while wolf 2 moved
+        // above the goblin before acting, so it still acts this round
+        tracker.next_turn();
+        assert_eq!(tracker.current_combatant().name(), "Wolf 1");
+        tracker.next_turn();
+        assert_eq!((tracker.current_combatant().name(), tracker.round), ("Wolf 2", 0));
+        tracker.next_turn();
+        assert_eq!((tracker.current_combatant().name(), tracker.round), ("Wolf 2", 1));
+
+        // removing the current combatant passes the turn on without skipping anyone
+        tracker.remove(0);
+        assert_eq!(tracker.current_combatant().name(), "Bat");
+        tracker.remove(1);
+        tracker.next_turn();
+        assert_eq!(tracker.current_combatant().name(), "Ogre");
+        tracker.remove(2);
+        tracker.remove(1);
+        assert_eq!(tracker.current_combatant().name(), "Bat");
+        assert_eq!(tracker.round, 2);
+        assert!(tracker.remove(0).is_none());
+    }
+
+    /// Ensure delaying combatants are skipped until they re-enter, and readied actions expire.
+    #[test]
+    fn test_delay_and_ready() {
+        let mut tracker = Tracker::new(["Ogre", "Goblin", "Wolf"].map(|name| Monster {
+            index: name.to_lowercase(),
+            name: name.to_string(),
+            ..Default::default()
+        }.into()));
+
+        assert!(tracker.delay());
+        assert_eq!(tracker.current_combatant().name(), "Goblin");
+        tracker.next_turn();
+        assert_eq!(tracker.current_combatant().name(), "Wolf");
+        tracker.next_turn();
+        assert_eq!(tracker.current_combatant().name(), "Goblin");
+        assert_eq!(tracker.round, 1);
+
+        // the ogre re-enters after the goblin
+        tracker.end_delay(0);
+        tracker.next_turn();
+        assert_eq!(tracker.current_combatant().name(), "Ogre");
+
+        assert!(tracker.ready("an enemy comes within reach"));
+        assert_eq!(tracker.current_combatant().resources.actions, 0);
+        assert!(!tracker.ready("twice"));
+        tracker.next_turn();
+        tracker.next_turn();
+        assert!(tracker.combatants[1].readied.is_some());
+        tracker.next_turn();
+        assert_eq!(tracker.current_combatant().name(), "Ogre");
+        assert!(tracker.current_combatant().readied.is_none());
+    }
+
+    /// Ensure surprised combatants lose their first turn, and only their first turn.
+    #[test]
+    fn test_surprise() {
+        let mut tracker = Tracker::new(["Ogre", "Goblin", "Wolf"].map(|name| Monster {
+            index: name.to_lowercase(),
+            name: name.to_string(),
+            ..Default::default()
+        }.into()));
+        tracker.combatants[0].surprise();
+        tracker.combatants[2].surprise();
+        assert_eq!(tracker.combatants[2].resources.reactions, 0);
+
+        tracker.start();
+        assert_eq!(tracker.current_combatant().name(), "Goblin");
+        assert!(tracker.combatants[2].surprised);
+        tracker.next_turn();
+        assert_eq!(tracker.current_combatant().name(), "Ogre");
+        assert_eq!(tracker.round, 1);
+        assert!(!tracker.combatants[2].surprised);
+        assert_eq!(tracker.combatants[2].resources.reactions, 1);
+        tracker.next_turn();
+        tracker.next_turn();
+        assert_eq!(tracker.current_combatant().name(), "Wolf");
+
+        // a player who notices any of the monsters isn't surprised
+        let player = |name: &str, passive_perception| Combatant::from(Player {
+            name: name.to_string(),
+            passive_perception: Some(passive_perception),
+            ..Default::default()
+        });
+        tracker.combatants.push(player("Alert", 40));
+        // the monsters have a Dexterity of 0, so their Stealth checks are at least -4
+        tracker.combatants.push(player("Oblivious", -5));
+        assert_eq!(tracker.roll_surprise(&mut rand::thread_rng()), [4]);
+
+        // removing the combatant before a surprised one doesn't give them a turn
+        let mut tracker = Tracker::new(["Ogre", "Goblin", "Wolf"].map(|name| Monster {
+            index: name.to_lowercase(),
+            name: name.to_string(),
+            ..Default::default()
+        }.into()));
+        tracker.combatants[1].surprise();
+        tracker.start();
+        tracker.remove(0);
+        assert_eq!(tracker.current_combatant().name(), "Wolf");
+        assert!(!tracker.combatants[0].surprised);
+    }
 }
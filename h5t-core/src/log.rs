@@ -0,0 +1,13 @@
+//! The combat log, a record of what happened in each round.
+
+use serde::{Deserialize, Serialize};
+
+/// An entry in the combat log.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LogEntry {
+    /// The round the entry was logged in, starting at 0.
+    pub round: usize,
+
+    /// What happened.
+    pub message: String,
+}
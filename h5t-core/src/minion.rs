@@ -0,0 +1,205 @@
+//! Minions: weak monsters, in the style of MCDM's homebrew rules, that drop from any damage and
+//! fight as a group.
+//!
+//! Minions belong to a named group. Damage beyond what it takes to drop a minion carries over to
+//! the other minions of its group, and the group attacks together, with a single attack roll
+//! against each target.
+
+use crate::{attack::AttackRoll, mob::split_attackers, monster::Action, Combatant, Tracker};
+use rand::Rng;
+
+impl Combatant {
+    /// Returns `true` if the combatant is a minion.
+    pub fn is_minion(&self) -> bool {
+        self.minion.is_some()
+    }
+}
+
+impl Tracker {
+    /// Returns the indices of the combatants in the same minion group as the given combatant,
+    /// including itself, in the order of play. Returns an empty list if it isn't a minion.
+    pub fn minion_group(&self, index: usize) -> Vec<usize> {
+        let Some(group) = &self.combatants[index].minion else {
+            return Vec::new();
+        };
+        self.combatants
+            .iter()
+            .enumerate()
+            .filter(|(_, combatant)| combatant.minion.as_ref() == Some(group))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Damage the combatant at the given index.
+    ///
+    /// A minion drops from any damage. The damage beyond its hit points carries over to the next
+    /// standing minions of its group in the order of play, dropping each one it reaches, until it
+    /// runs out. Returns the indices of the other minions dropped this way, which are also logged.
+    ///
+    /// The damage is credited to the combatant whose turn it is in their [`CombatStats`].
+    ///
+    /// [`CombatStats`]: crate::stats::CombatStats
+    pub fn deal_damage(&mut self, index: usize, amount: i32) -> Vec<usize> {
+        self.deal_damage_from(Some(self.turn), index, amount)
+    }
+
+    /// Damage the combatant at the given index, carrying any excess damage to a minion over to its
+    /// group, without crediting anyone.
+    pub(crate) fn carry_damage(&mut self, index: usize, amount: i32) -> Vec<usize> {
+        let target = &self.combatants[index];
+        if !target.is_minion() || target.hit_points <= 0 || amount <= 0 {
+            self.combatants[index].damage(amount);
+            return Vec::new();
+        }
+
+        let mut overflow = amount - target.hit_points;
+        self.combatants[index].damage(amount);
+
+        // walk the group starting after the target, wrapping around
+        let group = self.minion_group(index);
+        let start = group.iter().position(|&i| i == index).unwrap_or_default();
+        let mut dropped = Vec::new();
+        for &i in group.iter().cycle().skip(start + 1).take(group.len() - 1) {
+            if overflow <= 0 {
+                break;
+            }
+            let minion = &mut self.combatants[i];
+            if minion.hit_points <= 0 {
+                continue;
+            }
+            let hit_points = minion.hit_points;
+            minion.damage(overflow);
+            overflow -= hit_points;
+            dropped.push(i);
+        }
+
+        if !dropped.is_empty() {
+            let names = dropped
+                .iter()
+                .map(|&i| self.combatants[i].name())
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.log(format!("Overflow damage from {} drops {}", self.combatants[index].name(), names));
+        }
+        dropped
+    }
+
+    /// Roll an attack with the given action for the attacker's whole minion group, which attacks
+    /// as a unit. The minions still standing are split evenly between the targets, as with
+    /// [`Tracker::roll_mob_attack`], and one attack roll is made against each target, with its
+    /// damage multiplied by the number of minions attacking it. Targets left without any minions
+    /// to attack them aren't attacked.
+    ///
+    /// If the attacker isn't a minion, this rolls one [`Tracker::roll_attack`] per target.
+    pub fn roll_minion_attack(
+        &self,
+        attacker: usize,
+        action: &Action,
+        targets: &[usize],
+        rng: &mut impl Rng,
+    ) -> Vec<AttackRoll> {
+        if !self.combatants[attacker].is_minion() {
+            return targets
+                .iter()
+                .map(|&target| self.roll_attack(attacker, action, target, rng))
+                .collect();
+        }
+
+        let minions = self.minion_group(attacker)
+            .into_iter()
+            .filter(|&i| self.combatants[i].hit_points > 0)
+            .count()
+            .max(1) as u32;
+        targets
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &target)| {
+                let share = split_attackers(minions, targets.len(), i) as i32;
+                if share == 0 {
+                    return None;
+                }
+                let mut roll = self.roll_attack(attacker, action, target, rng);
+                for damage in &mut roll.damage {
+                    damage.amount *= share;
+                    damage.critical *= share;
+                }
+                Some(roll)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{monster::Damage, Monster, Player};
+
+    /// Ensure any damage drops a minion, and overflow damage drops the rest of its group in turn.
+    #[test]
+    fn overflow() {
+        let minion = |name: &str, group: &str| {
+            let mut combatant = Combatant::from(Monster {
+                name: name.to_string(),
+                hit_points: 5,
+                ..Default::default()
+            });
+            combatant.minion = Some(group.to_string());
+            combatant
+        };
+        let mut tracker = Tracker::new(vec![
+            minion("Cultist 1", "cultists"),
+            Combatant::from(Player { name: "Thorin".to_string(), max_hit_points: 30, ..Default::default() }),
+            minion("Cultist 2", "cultists"),
+            minion("Imp", "imps"),
+            minion("Cultist 3", "cultists"),
+        ]);
+        assert_eq!(tracker.minion_group(4), [0, 2, 4]);
+
+        // 1 damage still drops a minion, with nothing left over
+        assert!(tracker.deal_damage(2, 1).is_empty());
+        assert_eq!(tracker.combatants[2].hit_points, 0);
+
+        // 6 damage drops Cultist 3 and carries 1 over to Cultist 1, skipping the fallen Cultist 2
+        assert_eq!(tracker.deal_damage(4, 6), [0]);
+        assert_eq!(tracker.combatants[3].hit_points, 5);
+        assert_eq!(tracker.log[0].message, "Overflow damage from Cultist 3 drops Cultist 1");
+
+        tracker.deal_damage(1, 12);
+        assert_eq!(tracker.combatants[1].hit_points, 18);
+    }
+
+    /// Ensure a minion group attacking several targets splits its minions between them, so the
+    /// group deals no more damage in total than against a single target.
+    #[test]
+    fn split_attack() {
+        let mut tracker = Tracker::new(vec![
+            Combatant::from(Monster { name: "Cultist".to_string(), ..Default::default() }),
+            Combatant::from(Monster { name: "Cultist".to_string(), ..Default::default() }),
+            Combatant::from(Monster { name: "Cultist".to_string(), ..Default::default() }),
+            Combatant::from(Player { name: "Thorin".to_string(), ..Default::default() }),
+            Combatant::from(Player { name: "Kira".to_string(), ..Default::default() }),
+        ]);
+        for cultist in &mut tracker.combatants[..3] {
+            cultist.minion = Some("cultists".to_string());
+            cultist.hit_points = 1;
+        }
+        let dagger = Action {
+            name: "Dagger".to_string(),
+            damage: vec![Damage { dice: "4".to_string(), damage_type: "piercing".to_string() }],
+            ..Default::default()
+        };
+        let damage = |rolls: &[AttackRoll]| rolls
+            .iter()
+            .map(|roll| (roll.target, roll.damage[0].amount))
+            .collect::<Vec<_>>();
+
+        let rolls = tracker.roll_minion_attack(0, &dagger, &[3, 4], &mut rand::thread_rng());
+        assert_eq!(damage(&rolls), [(3, 8), (4, 4)]);
+
+        // with one minion left, only the first target is attacked
+        tracker.deal_damage(1, 1);
+        tracker.deal_damage(2, 1);
+        let rolls = tracker.roll_minion_attack(0, &dagger, &[3, 4], &mut rand::thread_rng());
+        assert_eq!(damage(&rolls), [(3, 4)]);
+    }
+}
@@ -0,0 +1,415 @@
+//! Mobs: many identical monsters that share one stat block and one place in the order of play,
+//! while their hit points and conditions are tracked for each member.
+//!
+//! A mob's [`Combatant::hit_points`] is the total of its living members' hit points, so a mob is
+//! only down once every member is.
+
+use crate::{
+    attack::{AttackRoll, DamageRoll},
+    dice::{Advantage, Expr},
+    monster::Action,
+    Combatant,
+    Condition,
+    ConditionKind,
+    Tracker,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// One member of a [`Mob`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MobMember {
+    /// The member's current hit points.
+    pub hit_points: i32,
+
+    /// Conditions that only affect this member. Conditions on the mob's [`Combatant`] affect every
+    /// member, and a condition every living member has affects the whole mob's rolls.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
+}
+
+impl MobMember {
+    /// Returns `true` if the member is still standing.
+    pub fn is_alive(&self) -> bool {
+        self.hit_points > 0
+    }
+
+    /// Apply a condition to the member, extending the duration if it already has the condition.
+    pub fn apply_condition(&mut self, condition: Condition) {
+        crate::add_condition(&mut self.conditions, condition);
+    }
+}
+
+/// The members of a mob.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Mob {
+    /// Each member's hit point maximum.
+    pub member_max_hit_points: i32,
+
+    /// The members, in order.
+    pub members: Vec<MobMember>,
+}
+
+impl Mob {
+    /// Create a mob whose members start with the given hit points, each with the given maximum.
+    pub fn new(member_max_hit_points: i32, hit_points: impl IntoIterator<Item = i32>) -> Self {
+        Self {
+            member_max_hit_points,
+            members: hit_points
+                .into_iter()
+                .map(|hit_points| MobMember { hit_points, conditions: Vec::new() })
+                .collect(),
+        }
+    }
+
+    /// Returns the number of members still standing.
+    pub fn alive(&self) -> usize {
+        self.members.iter().filter(|member| member.is_alive()).count()
+    }
+
+    /// Returns the hit points the first `count` living members would lose to the given damage,
+    /// not counting damage past 0 hit points.
+    pub fn damage_taken(&self, amount: i32, count: usize) -> i32 {
+        self.members
+            .iter()
+            .filter(|member| member.is_alive())
+            .take(count)
+            .map(|member| amount.clamp(0, member.hit_points))
+            .fold(0, i32::saturating_add)
+    }
+
+    /// Deal the given damage to each of the first `count` living members, and return the number
+    /// of members it dropped to 0 hit points.
+    pub fn damage(&mut self, amount: i32, count: usize) -> usize {
+        let mut dropped = 0;
+        for member in self.members.iter_mut().filter(|member| member.is_alive()).take(count) {
+            member.hit_points = member.hit_points.saturating_sub(amount);
+            if !member.is_alive() {
+                dropped += 1;
+            }
+        }
+        dropped
+    }
+
+    /// Heal the most wounded living member by the given amount, up to its maximum.
+    pub fn heal(&mut self, amount: i32) {
+        let max = self.member_max_hit_points;
+        if let Some(member) = self.members
+            .iter_mut()
+            .filter(|member| member.is_alive())
+            .min_by_key(|member| member.hit_points)
+        {
+            member.hit_points = member.hit_points.saturating_add(amount).min(max);
+        }
+    }
+}
+
+/// Returns how many of the attackers attack the `i`th of `targets` targets, when they are split
+/// evenly between the targets. The first targets take any attackers left over.
+pub(crate) fn split_attackers(attackers: u32, targets: usize, i: usize) -> u32 {
+    attackers / targets as u32 + u32::from((i as u32) < attackers % targets as u32)
+}
+
+/// Returns the number of attackers in a mob needed for one of them to hit, following the mob
+/// attack table in the Dungeon Master's Guide.
+///
+/// This is based on the d20 roll the attackers need to hit the target's armor class.
+pub fn attackers_per_hit(attack_bonus: i32, armor_class: u32) -> u32 {
+    match armor_class as i32 - attack_bonus {
+        ..=5 => 1,
+        6..=12 => 2,
+        13..=14 => 3,
+        15..=16 => 4,
+        17..=18 => 5,
+        19 => 10,
+        _ => 20,
+    }
+}
+
+/// Returns the number of hits a group of attackers from a mob lands against a target, without
+/// rolling, following the mob attack rules in the Dungeon Master's Guide.
+pub fn mob_attack_hits(attackers: u32, attack_bonus: i32, armor_class: u32) -> u32 {
+    attackers / attackers_per_hit(attack_bonus, armor_class)
+}
+
+impl Combatant {
+    /// Group the combatant into a mob of `count` members with its current hit points, named after
+    /// its stat block in the plural unless it already has a display name.
+    pub fn into_mob(mut self, count: usize) -> Self {
+        let mob = Mob::new(self.max_hit_points, std::iter::repeat_n(self.hit_points, count));
+        if self.display_name.is_none() {
+            self.display_name = Some(crate::naming::plural(self.base_name()));
+        }
+        self.mob = Some(mob);
+        self.update_mob_hit_points();
+        self
+    }
+
+    /// Deal the given damage to each of the first `count` living members of the combatant's mob,
+    /// and return the number of members it dropped. Combatants that aren't mobs take the damage
+    /// once, and count as one member.
+    pub fn damage_members(&mut self, amount: i32, count: usize) -> usize {
+        let Some(mob) = &mut self.mob else {
+            let was_alive = self.hit_points > 0;
+            self.damage(amount);
+            return usize::from(was_alive && self.hit_points <= 0);
+        };
+        let taken = mob.damage_taken(amount, count);
+        self.stats.damage_taken = self.stats.damage_taken.saturating_add(taken);
+        let dropped = mob.damage(amount, count);
+        self.update_mob_hit_points();
+        dropped
+    }
+
+    /// Returns the kinds of conditions affecting the combatant's rolls. For a mob, this includes
+    /// any condition every living member has, as well as the conditions on the whole mob.
+    pub fn condition_kinds(&self) -> Vec<ConditionKind> {
+        let mut kinds = self.conditions
+            .iter()
+            .map(|condition| condition.kind)
+            .collect::<Vec<_>>();
+        let Some(mob) = &self.mob else {
+            return kinds;
+        };
+        let mut living = mob.members.iter().filter(|member| member.is_alive());
+        let Some(first) = living.next() else {
+            return kinds;
+        };
+        let shared = first.conditions
+            .iter()
+            .map(|condition| condition.kind)
+            .filter(|&kind| living.clone().all(|member| {
+                member.conditions.iter().any(|condition| condition.kind == kind)
+            }))
+            .filter(|kind| !kinds.contains(kind))
+            .collect::<Vec<_>>();
+        kinds.extend(shared);
+        kinds
+    }
+
+    /// Set the combatant's hit points to the total of its mob's living members.
+    pub(crate) fn update_mob_hit_points(&mut self) {
+        let Some(mob) = &self.mob else {
+            return;
+        };
+        self.hit_points = mob.members
+            .iter()
+            .map(|member| member.hit_points.max(0))
+            .sum();
+        self.max_hit_points = mob.member_max_hit_points * mob.members.len() as i32;
+    }
+}
+
+impl Tracker {
+    /// Apply a condition to one member of the mob at the given index, crediting it to the
+    /// combatant whose turn it is, as with [`Tracker::inflict_condition`].
+    ///
+    /// Returns `false` if the combatant isn't a mob or has no such member.
+    pub fn inflict_member_condition(
+        &mut self,
+        index: usize,
+        member: usize,
+        condition: Condition,
+    ) -> bool {
+        let combatant = &mut self.combatants[index];
+        let Some(target) = combatant.mob.as_mut().and_then(|mob| mob.members.get_mut(member)) else {
+            return false;
+        };
+        let kind = condition.kind;
+        target.apply_condition(condition);
+        if !combatant.stats.conditions_inflicted.contains(&kind) {
+            combatant.stats.conditions_inflicted.push(kind);
+        }
+        if self.turn != index {
+            self.current_combatant_mut().stats.conditions_applied += 1;
+        }
+        true
+    }
+
+    /// Group the combatants at the given indices into one mob, in place of the first of them.
+    /// Each combatant becomes a member with its current hit points and conditions. Returns the
+    /// index of the mob.
+    ///
+    /// Returns [`None`] if fewer than two combatants are given, or if they don't all share the
+    /// same monster stat block.
+    pub fn form_mob(&mut self, indices: &[usize]) -> Option<usize> {
+        let mut indices = indices.to_vec();
+        indices.sort_unstable();
+        indices.dedup();
+        let index = *indices.first()?;
+        let index_of = |combatant: &Combatant| match &combatant.kind {
+            crate::CombatantKind::Monster(monster) if combatant.mob.is_none() => {
+                Some(monster.index.clone())
+            },
+            _ => None,
+        };
+        let stat_block = index_of(&self.combatants[index])?;
+        if indices.len() < 2
+            || indices.iter().any(|&i| index_of(&self.combatants[i]).as_ref() != Some(&stat_block))
+        {
+            return None;
+        }
+
+        // remove from the bottom up, so the remaining indices stay valid
+        let mut members = indices[1..]
+            .iter()
+            .rev()
+            .filter_map(|&i| self.remove(i))
+            .collect::<Vec<_>>();
+        members.reverse();
+
+        let first = &mut self.combatants[index];
+        let mut mob = Mob::new(first.max_hit_points, []);
+        for member in std::iter::once(&*first).chain(&members) {
+            mob.members.push(MobMember {
+                hit_points: member.hit_points,
+                conditions: member.conditions.clone(),
+            });
+        }
+        first.conditions.clear();
+        first.display_name = Some(crate::naming::plural(first.base_name()));
+        first.mob = Some(mob);
+        first.update_mob_hit_points();
+        Some(index)
+    }
+
+    /// Resolve an attack by a mob against each of the targets with the Dungeon Master's Guide's
+    /// mob rules: the mob's living members are split evenly between the targets, and instead of
+    /// rolling, every group of attackers large enough to hit a target lands one hit.
+    ///
+    /// Each hit's damage is rolled. Attackers that aren't mobs count as one attacker.
+    pub fn roll_mob_attack(
+        &self,
+        attacker: usize,
+        action: &Action,
+        targets: &[usize],
+        rng: &mut impl Rng,
+    ) -> Vec<AttackRoll> {
+        let attackers = self.combatants[attacker].mob.as_ref().map_or(1, Mob::alive) as u32;
+        let bonus = action.attack_bonus.unwrap_or_default();
+        targets
+            .iter()
+            .enumerate()
+            .map(|(i, &target)| {
+                let share = split_attackers(attackers, targets.len(), i);
+                let target_combatant = &self.combatants[target];
+                let armor_class = target_combatant.armor_class();
+                let hits = mob_attack_hits(share, bonus, armor_class);
+
+                let damage = action.damage
+                    .iter()
+                    .filter_map(|damage| {
+                        let expr = damage.dice.parse::<Expr>().ok()?;
+                        Some(DamageRoll {
+                            damage_type: damage.damage_type.clone(),
+                            amount: (0..hits).map(|_| expr.roll(rng).total).sum(),
+                            critical: 0,
+                            resistance: target_combatant.resistance(&damage.damage_type),
+                        })
+                    })
+                    .collect();
+
+                AttackRoll {
+                    target,
+                    advantage: Advantage::Normal,
+                    roll: 0,
+                    dropped: None,
+                    total: 0,
+                    armor_class,
+                    hit: hits > 0,
+                    critical: false,
+                    damage,
+                    adjustment: 0,
+                    mob_hits: Some(hits),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attack::attack_advantage,
+        monster::{ArmorClass, Damage},
+        ConditionDuration,
+        Monster,
+        Player,
+    };
+
+    /// Ensure damage is dealt to individual members, and the mob's hit points follow.
+    #[test]
+    fn mob_damage() {
+        let zombie = Monster { name: "Zombie".to_string(), hit_points: 22, ..Default::default() };
+        let mut mob = Combatant::from(zombie).into_mob(12);
+        assert_eq!(mob.name(), "Zombies");
+        assert_eq!((mob.hit_points, mob.max_hit_points), (264, 264));
+
+        assert_eq!(mob.damage_members(30, 3), 3);
+        mob.damage(10);
+        assert_eq!(mob.mob.as_ref().unwrap().alive(), 9);
+        assert_eq!(mob.hit_points, 9 * 22 - 10);
+    }
+
+    /// Ensure conditions can be applied to single members, and only affect the mob's rolls once
+    /// every living member has them.
+    #[test]
+    fn member_conditions() {
+        let zombie = Monster { name: "Zombie".to_string(), hit_points: 22, ..Default::default() };
+        let mut tracker = Tracker::new(vec![
+            Combatant::from(Player { name: "Kira".to_string(), ..Default::default() }),
+            Combatant::from(zombie).into_mob(3),
+        ]);
+        let prone = || Condition {
+            kind: ConditionKind::Prone,
+            duration: ConditionDuration::Forever,
+        };
+        assert!(!tracker.inflict_member_condition(0, 0, prone()));
+        assert!(!tracker.inflict_member_condition(1, 3, prone()));
+
+        assert!(tracker.inflict_member_condition(1, 0, prone()));
+        assert!(tracker.inflict_member_condition(1, 1, prone()));
+        assert!(tracker.combatants[1].condition_kinds().is_empty());
+        assert_eq!(tracker.combatants[0].stats.conditions_applied, 2);
+
+        // the last member without the condition drops, so every living member is prone
+        tracker.combatants[1].mob.as_mut().unwrap().members[2].hit_points = 0;
+        assert_eq!(tracker.combatants[1].condition_kinds(), [ConditionKind::Prone]);
+        let (kira, zombies) = (&tracker.combatants[0], &tracker.combatants[1]);
+        assert_eq!(attack_advantage(kira, zombies, true), Advantage::Advantage);
+    }
+
+    /// Ensure mob attacks follow the DMG table.
+    #[test]
+    fn mob_attack() {
+        assert_eq!(attackers_per_hit(4, 17), 3);
+        assert_eq!(mob_attack_hits(9, 3, 8), 9);
+        assert_eq!(mob_attack_hits(9, 0, 25), 0);
+
+        let zombie = Monster { name: "Zombie".to_string(), hit_points: 22, ..Default::default() };
+        let tracker = Tracker::new(vec![
+            Combatant::from(zombie).into_mob(7),
+            Combatant::from(Player {
+                name: "Thorin".to_string(),
+                armor_class: 16,
+                ..Default::default()
+            }),
+            Combatant::from(Monster {
+                name: "Ally".to_string(),
+                armor_class: ArmorClass { value: 8, ..Default::default() },
+                ..Default::default()
+            }),
+        ]);
+        let slam = Action {
+            name: "Slam".to_string(),
+            attack_bonus: Some(3),
+            damage: vec![Damage { dice: "1d6+1".to_string(), damage_type: "bludgeoning".to_string() }],
+            ..Default::default()
+        };
+        let rolls = tracker.roll_mob_attack(0, &slam, &[1, 2], &mut rand::thread_rng());
+        // 4 zombies need 13s against AC 16 (3 per hit), 3 zombies need 5s against AC 8
+        assert_eq!((rolls[0].mob_hits, rolls[1].mob_hits), (Some(1), Some(3)));
+        assert!((6..=21).contains(&rolls[1].total_damage()));
+    }
+}
@@ -1,8 +1,51 @@
-use crate::{ability::{Modifier, Score, Skill}, Ability};
-use serde::{Deserialize, Deserializer, Serialize};
+use crate::{ability::{Modifier, Score, Skill}, Ability, ChallengeRating};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+/// A value in either the format of the SRD API, which is an array, or h5t's native format, which
+/// is what serializing the value produces.
+#[derive(Debug)]
+enum Format<Api, Native> {
+    Api(Api),
+    Native(Native),
+}
+
+impl<'de, Api, Native> Deserialize<'de> for Format<Api, Native>
+where
+    Api: Deserialize<'de>,
+    Native: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        // pick the format by the shape of the value, rather than trying both, so that errors point
+        // at the invalid value inside it
+        struct Visitor<Api, Native>(std::marker::PhantomData<(Api, Native)>);
+
+        impl<'de, Api, Native> de::Visitor<'de> for Visitor<Api, Native>
+        where
+            Api: Deserialize<'de>,
+            Native: Deserialize<'de>,
+        {
+            type Value = Format<Api, Native>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "an array in the SRD API format, or an object in the h5t format")
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                Api::deserialize(de::value::SeqAccessDeserializer::new(seq)).map(Format::Api)
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                Native::deserialize(de::value::MapAccessDeserializer::new(map)).map(Format::Native)
+            }
+        }
+
+        d.deserialize_any(Visitor(std::marker::PhantomData))
+    }
+}
 
 /// The source of a monster's armor class value.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub enum ArmorClassSource {
     /// The armor class is calculated from the monster's Dexterity modifier (i.e., 10 + DEX mod).
     #[default]
@@ -16,7 +59,7 @@ pub enum ArmorClassSource {
 }
 
 /// A monster's armor class.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ArmorClass {
     /// The source of the armor class value.
     pub source: ArmorClassSource,
@@ -40,7 +83,10 @@ where D: Deserializer<'de>
         value: u32,
     }
 
-    let data = Vec::<AcData>::deserialize(d)?;
+    let data = match Format::<Vec<AcData>, ArmorClass>::deserialize(d)? {
+        Format::Api(data) => data,
+        Format::Native(armor_class) => return Ok(armor_class),
+    };
     data.into_iter()
         .find_map(|data| {
             let source = match data.r#type.as_str() {
@@ -57,26 +103,56 @@ where D: Deserializer<'de>
 /// A creature's speed on all types of movement.
 ///
 /// Each field is given as a descriptive string, such as "30 ft.".
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Speed {
     /// Basic movement speed.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub walk: Option<String>,
 
     /// Movement speed when moving through sand, earth, mud, or ice.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub burrow: Option<String>,
 
     /// Movement speed when climbing.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub climb: Option<String>,
 
     /// Movement speed when flying.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub fly: Option<String>,
 
     /// Movement speed when swimming.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub swim: Option<String>,
 }
 
+impl std::fmt::Display for Speed {
+    /// Formats the speeds as in a stat block, such as "30 ft., fly 60 ft.".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts = [
+            ("", &self.walk),
+            ("burrow ", &self.burrow),
+            ("climb ", &self.climb),
+            ("fly ", &self.fly),
+            ("swim ", &self.swim),
+        ];
+        let mut first = true;
+        for (label, speed) in parts {
+            let Some(speed) = speed else {
+                continue;
+            };
+            if !first {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}{}", label, speed)?;
+            first = false;
+        }
+        Ok(())
+    }
+}
+
 /// A creature's size.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
 pub enum Size {
     #[default]
     Tiny,
@@ -87,9 +163,15 @@ pub enum Size {
     Gargantuan,
 }
 
+impl std::fmt::Display for Size {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 /// A creature's type.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
 pub enum Type {
     Aberration,
     Beast,
@@ -106,13 +188,163 @@ pub enum Type {
     Plant,
     Undead,
 
-    #[default]
-    #[serde(other)] // TODO: capture the value of the "other" case
-    Other,
+    /// Any other type, such as "swarm of Tiny beasts", kept as written.
+    Other(String),
+}
+
+impl Default for Type {
+    fn default() -> Self {
+        Type::Other(String::new())
+    }
+}
+
+impl From<String> for Type {
+    fn from(s: String) -> Self {
+        match s.to_lowercase().as_str() {
+            "aberration" => Type::Aberration,
+            "beast" => Type::Beast,
+            "celestial" => Type::Celestial,
+            "construct" => Type::Construct,
+            "dragon" => Type::Dragon,
+            "elemental" => Type::Elemental,
+            "fey" => Type::Fey,
+            "fiend" => Type::Fiend,
+            "giant" => Type::Giant,
+            "humanoid" => Type::Humanoid,
+            "monstrosity" => Type::Monstrosity,
+            "ooze" => Type::Ooze,
+            "plant" => Type::Plant,
+            "undead" => Type::Undead,
+            _ => Type::Other(s),
+        }
+    }
+}
+
+impl From<Type> for String {
+    fn from(r#type: Type) -> Self {
+        match r#type {
+            Type::Other(s) => s,
+            known => format!("{:?}", known).to_lowercase(),
+        }
+    }
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Other(s) => write!(f, "{}", s),
+            known => write!(f, "{:?}", known),
+        }
+    }
+}
+
+/// The range of one of a creature's special senses, such as darkvision.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Sense {
+    /// The range of the sense, in feet.
+    pub range: u32,
+
+    /// Any note that follows the range, such as "(blind beyond this radius)".
+    pub note: Option<String>,
+}
+
+impl TryFrom<String> for Sense {
+    type Error = String;
+
+    /// Parses a sense as written in a stat block, such as "60 ft. (blind beyond this radius)".
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let (range, note) = s.trim()
+            .split_once(" ft.")
+            .ok_or_else(|| format!("invalid sense range `{}`", s))?;
+        let range = range.trim().parse().map_err(|_| format!("invalid sense range `{}`", s))?;
+        let note = note.trim();
+        Ok(Sense { range, note: (!note.is_empty()).then(|| note.to_string()) })
+    }
+}
+
+impl From<Sense> for String {
+    fn from(sense: Sense) -> Self {
+        sense.to_string()
+    }
+}
+
+impl std::fmt::Display for Sense {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ft.", self.range)?;
+        if let Some(note) = &self.note {
+            write!(f, " {}", note)?;
+        }
+        Ok(())
+    }
+}
+
+/// A creature's special senses and passive Perception.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Senses {
+    /// The creature perceives its surroundings without relying on sight.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blindsight: Option<Sense>,
+
+    /// The creature sees in darkness as if it were dim light.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub darkvision: Option<Sense>,
+
+    /// The creature detects vibrations through the ground.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tremorsense: Option<Sense>,
+
+    /// The creature sees through darkness, illusions, and invisibility.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truesight: Option<Sense>,
+
+    /// The creature's passive Wisdom (Perception) score, if the stat block gives one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passive_perception: Option<i32>,
+}
+
+impl std::fmt::Display for Senses {
+    /// Formats the senses as in a stat block, such as "darkvision 60 ft., passive Perception 9".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts = [
+            ("blindsight", &self.blindsight),
+            ("darkvision", &self.darkvision),
+            ("tremorsense", &self.tremorsense),
+            ("truesight", &self.truesight),
+        ];
+        let mut first = true;
+        for (label, sense) in parts {
+            let Some(sense) = sense else {
+                continue;
+            };
+            if !first {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} {}", label, sense)?;
+            first = false;
+        }
+        if let Some(passive_perception) = self.passive_perception {
+            if !first {
+                write!(f, ", ")?;
+            }
+            write!(f, "passive Perception {}", passive_perception)?;
+        }
+        Ok(())
+    }
+}
+
+/// A reference to another monster's stat block, such as one of a shapechanger's other forms.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MonsterRef {
+    /// The other monster's index.
+    pub index: String,
+
+    /// The other monster's name.
+    pub name: String,
 }
 
 /// A monster's proficiencies.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Proficiencies {
     /// The monster's skill proficiencies.
     ///
@@ -152,8 +384,11 @@ where D: Deserializer<'de>
         proficiency: ProfDataInner,
     }
 
+    let data = match Format::<Vec<ProfData>, Proficiencies>::deserialize(d)? {
+        Format::Api(data) => data,
+        Format::Native(proficiencies) => return Ok(proficiencies),
+    };
     let mut proficiencies = Proficiencies::default();
-    let data = Vec::<ProfData>::deserialize(d)?;
     for prof in data {
         let modifier = Some(prof.value as i32);
         match prof.proficiency.index.as_str() {
@@ -189,7 +424,7 @@ where D: Deserializer<'de>
 }
 
 /// A trait that a monster has.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Trait {
     /// The name of the trait.
     pub name: String,
@@ -200,6 +435,115 @@ pub struct Trait {
     /// The usage of the trait.
     #[serde(default, deserialize_with = "deserialize_usage")]
     pub usage: Usage,
+
+    /// The monster's spellcasting, if this is a Spellcasting trait.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spellcasting: Option<Spellcasting>,
+}
+
+/// The details of a monster's Spellcasting trait.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Spellcasting {
+    /// The monster's spellcaster level, if it casts spells like a member of a class.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<u32>,
+
+    /// The monster's spell save DC.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dc: Option<u32>,
+
+    /// The monster's spell attack modifier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modifier: Option<i32>,
+
+    /// The number of spell slots the monster has, by spell level.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub slots: BTreeMap<u32, u32>,
+}
+
+/// An action a monster can take, such as a weapon attack.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Action {
+    /// The name of the action.
+    pub name: String,
+
+    /// The description of the action.
+    #[serde(default)]
+    pub desc: String,
+
+    /// The bonus to hit, if the action is an attack.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attack_bonus: Option<Modifier>,
+
+    /// The damage the action deals on a hit.
+    #[serde(default, deserialize_with = "deserialize_damage", skip_serializing_if = "Vec::is_empty")]
+    pub damage: Vec<Damage>,
+}
+
+impl Action {
+    /// Returns `true` if the action is an attack roll.
+    pub fn is_attack(&self) -> bool {
+        self.attack_bonus.is_some()
+    }
+
+    /// Returns `true` if the action is a melee attack, as opposed to a ranged attack.
+    ///
+    /// Attacks that can be made either way, like a thrown dagger, count as melee.
+    pub fn is_melee(&self) -> bool {
+        self.desc.starts_with("Melee")
+    }
+}
+
+/// Damage dealt by an [`Action`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Damage {
+    /// The dice to roll for the damage, such as `"1d6+2"`.
+    #[serde(rename = "damage_dice")]
+    pub dice: String,
+
+    /// The type of damage, such as `"slashing"`.
+    pub damage_type: String,
+}
+
+fn deserialize_damage<'de, D>(d: D) -> Result<Vec<Damage>, D::Error>
+where D: Deserializer<'de>
+{
+    // api provides the damage type as an object, like
+    //
+    // {"damage_type": {"index": "slashing", ...}, "damage_dice": "1d6+2"}
+    //
+    // and some damage entries are a choice between options, which are skipped
+
+    #[derive(Debug, Deserialize)]
+    struct DamageTypeData {
+        index: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[serde(untagged)]
+    enum DamageType {
+        Api(DamageTypeData),
+        Name(String),
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct DamageData {
+        damage_dice: Option<String>,
+        damage_type: Option<DamageType>,
+    }
+
+    let data = Vec::<DamageData>::deserialize(d)?;
+    Ok(data
+        .into_iter()
+        .filter_map(|damage| {
+            let damage_type = match damage.damage_type {
+                Some(DamageType::Api(data)) => data.index,
+                Some(DamageType::Name(name)) => name,
+                None => String::new(),
+            };
+            Some(Damage { dice: damage.damage_dice?, damage_type })
+        })
+        .collect())
 }
 
 fn deserialize_usage<'de, D>(d: D) -> Result<Usage, D::Error>
@@ -210,14 +554,30 @@ where D: Deserializer<'de>
     // {"type": "per day", "times": 3}
     // or
     // {"type": "recharge after rest", "rest_types": ["short", "long"]}
+    //
+    // while the native format is the serialized `Usage`, which is a string or an object like
+    //
+    // {"PerDay": 3}
 
     #[derive(Debug, Deserialize)]
     struct UsageData {
+        #[serde(rename = "type")]
+        _type: String,
         times: Option<usize>,
         rest_types: Option<Vec<String>>,
     }
 
-    let data = UsageData::deserialize(d)?;
+    #[derive(Debug, Deserialize)]
+    #[serde(untagged, expecting = "a usage in the SRD API format or the h5t format")]
+    enum UsageFormat {
+        Api(UsageData),
+        Native(Usage),
+    }
+
+    let data = match UsageFormat::deserialize(d)? {
+        UsageFormat::Api(data) => data,
+        UsageFormat::Native(usage) => return Ok(usage),
+    };
     match (data.times, data.rest_types) {
         (Some(times), _) if times > 0 => Ok(Usage::PerDay(times)),
         (_, Some(rest_types)) if !rest_types.is_empty() => {
@@ -235,7 +595,7 @@ where D: Deserializer<'de>
 }
 
 /// Usage constraints for a trait.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Usage {
     /// The trait has a limited number of usages per day. Effectively, this is a limit to how many
     /// times the trait can be used in this combat encounter.
@@ -255,7 +615,10 @@ pub enum Usage {
 }
 
 /// A pre-made monster from the System Reference Document (SRD), or a custom monster.
-#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+///
+/// Monsters can be read from the format of the SRD API, or from h5t's native format, which is what
+/// serializing a monster produces. The native format reads back into exactly the same monster.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Monster {
     /// The monster's index, used for identification.
     pub index: String,
@@ -287,6 +650,10 @@ pub struct Monster {
     /// The monster's hit points.
     pub hit_points: i32,
 
+    /// The monster's hit dice, such as "2d6".
+    #[serde(default)]
+    pub hit_dice: String,
+
     /// The expression to roll for the monster's hit points.
     pub hit_points_roll: String,
 
@@ -297,9 +664,17 @@ pub struct Monster {
     #[serde(default, deserialize_with = "deserialize_proficiencies")]
     pub proficiencies: Proficiencies,
 
-    /// The monster's chalenge rating. Can be `0.0`, `0.125`, `0.25`, `0.5`, or an integer from `1`
-    /// to `30`.
-    pub challenge_rating: f32,
+    /// The monster's special senses and passive Perception.
+    #[serde(default)]
+    pub senses: Senses,
+
+    /// The languages the monster speaks or understands, as written in its stat block. Empty if it
+    /// knows none.
+    #[serde(default)]
+    pub languages: String,
+
+    /// The monster's challenge rating.
+    pub challenge_rating: ChallengeRating,
 
     /// The XP value of the monster. If the DM is using XP to determine rewards, this is the amount
     /// of XP the party gains for defeating the monster.
@@ -312,12 +687,96 @@ pub struct Monster {
     /// things like Legendary Resistances, Lair Actions, etc.
     #[serde(rename = "special_abilities")]
     pub traits: Vec<Trait>,
+
+    /// The actions the monster can take on its turn, such as attacks.
+    #[serde(default)]
+    pub actions: Vec<Action>,
+
+    /// The legendary actions the monster can take at the end of other creatures' turns.
+    #[serde(default)]
+    pub legendary_actions: Vec<Trait>,
+
+    /// Damage types the monster is vulnerable to, such as "fire".
+    #[serde(default)]
+    pub damage_vulnerabilities: Vec<String>,
+
+    /// Damage types the monster is resistant to. Entries can be qualified, such as "bludgeoning,
+    /// piercing, and slashing from nonmagical attacks".
+    #[serde(default)]
+    pub damage_resistances: Vec<String>,
+
+    /// Damage types the monster is immune to. Entries can be qualified, like
+    /// [`Monster::damage_resistances`].
+    #[serde(default)]
+    pub damage_immunities: Vec<String>,
+
+    /// Tags for the environments the monster can be found in, such as "forest" or "underdark".
+    ///
+    /// The SRD does not provide environments, so these are typically only set for homebrew
+    /// monsters.
+    #[serde(default)]
+    pub environments: Vec<String>,
+
+    /// A description of the monster and its lore, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+
+    /// The monster's other forms, if it is a shapechanger with a stat block for each form.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forms: Vec<MonsterRef>,
+
+    /// The path or URL of an image of the monster, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+}
+
+impl Monster {
+    /// Returns `true` if the monster can take legendary actions.
+    pub fn has_legendary_actions(&self) -> bool {
+        !self.legendary_actions.is_empty()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::score_to_modifier;
 
+    /// Ensure unusual types keep their original string, and senses parse and display as written.
+    #[test]
+    fn types_and_senses() {
+        let r#type = serde_json::from_str::<Type>("\"swarm of Tiny beasts\"").unwrap();
+        assert_eq!(r#type, Type::Other("swarm of Tiny beasts".to_string()));
+        assert_eq!(serde_json::from_str::<Type>("\"dragon\"").unwrap(), Type::Dragon);
+        assert_eq!(serde_json::to_string(&Type::Dragon).unwrap(), "\"dragon\"");
+
+        let senses = serde_json::from_str::<Senses>(r#"{
+            "blindsight": "30 ft. (blind beyond this radius)",
+            "darkvision": "60 ft.",
+            "passive_perception": 12
+        }"#).unwrap();
+        assert_eq!(senses.darkvision.as_ref().map(|sense| sense.range), Some(60));
+        assert_eq!(
+            senses.to_string(),
+            "blindsight 30 ft. (blind beyond this radius), darkvision 60 ft., passive Perception 12",
+        );
+        assert!(serde_json::from_str::<Sense>("\"far\"").is_err());
+    }
+
+    /// Ensure every SRD monster reads back exactly after being written in the native format.
+    #[test]
+    fn round_trip() {
+        let monsters = serde_json::from_str::<Vec<Monster>>(include_str!("../../data/monsters.json"))
+            .unwrap();
+        for monster in monsters {
+            let native = serde_json::to_string(&monster).unwrap();
+            let read = serde_json::from_str::<Monster>(&native)
+                .unwrap_or_else(|err| panic!("{}: {}", monster.index, err));
+            assert_eq!(read, monster);
+            assert_eq!(serde_json::to_string(&read).unwrap(), native);
+        }
+    }
+
     /// Ensure the modifier calculation is correct.
     #[test]
     fn modifier_calculation() {
@@ -0,0 +1,106 @@
+//! Display names that tell apart combatants sharing a stat block, such as four goblins.
+
+use serde::{Deserialize, Serialize};
+
+/// Colors used by [`NamingScheme::Colors`], matching common sets of colored mini bases.
+pub const COLORS: [&str; 12] = [
+    "Red", "Blue", "Green", "Yellow", "Purple", "Orange",
+    "Black", "White", "Pink", "Brown", "Gray", "Teal",
+];
+
+/// Adjectives used by [`NamingScheme::Adjectives`].
+pub const ADJECTIVES: [&str; 12] = [
+    "Big", "Small", "Scarred", "Grim", "Sly", "Loud",
+    "Old", "Young", "Quick", "Limping", "Tall", "Masked",
+];
+
+/// How to name combatants that share a stat block.
+///
+/// Only combatants without a display name are renamed; once assigned, names stay the same, so
+/// removing "Goblin 2" leaves "Goblin 1" and "Goblin 3" as they are.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NamingScheme {
+    /// Number duplicates: "Goblin 1", "Goblin 2", ...
+    #[default]
+    Numbers,
+
+    /// Prefix duplicates with a color: "Red Goblin", "Blue Goblin", ...
+    Colors,
+
+    /// Prefix duplicates with an adjective: "Big Goblin", "Small Goblin", ...
+    Adjectives,
+
+    /// Prefix duplicates with the given labels, e.g. to match the markings on a set of minis.
+    Labels(Vec<String>),
+}
+
+impl NamingScheme {
+    /// Returns the `n`th name (starting from 0) for a combatant whose stat block is named `base`.
+    ///
+    /// Once the scheme runs out of labels, they are reused with a number, such as "Red Goblin 2".
+    pub fn name(&self, base: &str, n: usize) -> String {
+        let labels = match self {
+            NamingScheme::Numbers => return format!("{} {}", base, n + 1),
+            NamingScheme::Colors => &COLORS.map(String::from)[..],
+            NamingScheme::Adjectives => &ADJECTIVES.map(String::from)[..],
+            NamingScheme::Labels(labels) if labels.is_empty() => {
+                return NamingScheme::Numbers.name(base, n);
+            },
+            NamingScheme::Labels(labels) => &labels[..],
+        };
+
+        let label = &labels[n % labels.len()];
+        match n / labels.len() {
+            0 => format!("{} {}", label, base),
+            cycle => format!("{} {} {}", label, base, cycle + 1),
+        }
+    }
+}
+
+/// Returns the plural of a stat block's name, such as "Zombies" for "Zombie", to name a mob.
+///
+/// Only the last word is made plural, so "Giant Rat" becomes "Giant Rats". Irregular plurals
+/// common in stat blocks are handled; other names follow the usual English endings.
+pub fn plural(name: &str) -> String {
+    let (head, last) = match name.rsplit_once(' ') {
+        Some((head, last)) => (format!("{} ", head), last),
+        None => (String::new(), name),
+    };
+    let irregular = [
+        ("Wolf", "Wolves"), ("Werewolf", "Werewolves"), ("Dwarf", "Dwarves"), ("Elf", "Elves"),
+        ("Thief", "Thieves"), ("Mouse", "Mice"), ("Ox", "Oxen"), ("Man", "Men"), ("Woman", "Women"),
+        ("Deer", "Deer"), ("Sheep", "Sheep"), ("Fish", "Fish"), ("Swarm", "Swarms"),
+    ];
+    let plural = if let Some((_, plural)) = irregular.iter().find(|(one, _)| *one == last) {
+        plural.to_string()
+    } else if ["s", "x", "ch", "sh"].iter().any(|ending| last.ends_with(ending)) {
+        format!("{}es", last)
+    } else if last.ends_with('y') && !["ay", "ey", "oy"].iter().any(|ending| last.ends_with(ending)) {
+        format!("{}ies", &last[..last.len() - 1])
+    } else {
+        format!("{}s", last)
+    };
+    head + &plural
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ensure names follow the scheme and stay unique once labels run out.
+    #[test]
+    fn names() {
+        assert_eq!(NamingScheme::Numbers.name("Goblin", 0), "Goblin 1");
+        assert_eq!(NamingScheme::Colors.name("Goblin", 1), "Blue Goblin");
+
+        let scheme = NamingScheme::Labels(vec!["Left".to_string(), "Right".to_string()]);
+        assert_eq!(scheme.name("Orc", 1), "Right Orc");
+        assert_eq!(scheme.name("Orc", 2), "Left Orc 2");
+
+        assert_eq!(plural("Zombie"), "Zombies");
+        assert_eq!(plural("Giant Wolf Spider"), "Giant Wolf Spiders");
+        assert_eq!(plural("Dire Wolf"), "Dire Wolves");
+        assert_eq!(plural("Harpy"), "Harpies");
+    }
+}
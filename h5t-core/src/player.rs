@@ -0,0 +1,97 @@
+use crate::{ability::{Modifier, Score}, monster::Speed, score_to_modifier, Ability};
+use serde::{Deserialize, Serialize};
+
+/// Returns the ability scores used when none are given: 10 in every ability.
+fn default_scores() -> Ability<Score> {
+    Ability {
+        strength: 10,
+        dexterity: 10,
+        constitution: 10,
+        intelligence: 10,
+        wisdom: 10,
+        charisma: 10,
+    }
+}
+
+/// Returns the speed used when none is given: 30 feet of walking speed.
+fn default_speed() -> Speed {
+    Speed {
+        walk: Some("30 ft.".to_string()),
+        ..Default::default()
+    }
+}
+
+/// Returns the hit die used when none is given: a d8.
+fn default_hit_die() -> u32 {
+    8
+}
+
+/// A player character.
+///
+/// Players manage their own character sheets, so only the details the DM needs to run combat are
+/// tracked here.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Player {
+    /// The character's name.
+    pub name: String,
+
+    /// The character's total level, used for encounter difficulty and proficiency bonus.
+    pub level: u32,
+
+    /// The character's armor class.
+    pub armor_class: u32,
+
+    /// The character's hit point maximum.
+    pub max_hit_points: i32,
+
+    /// The character's ability scores.
+    #[serde(default = "default_scores")]
+    pub scores: Ability<Score>,
+
+    /// The character's speeds.
+    #[serde(default = "default_speed")]
+    pub speed: Speed,
+
+    /// The character's passive Wisdom (Perception). If not given, it is 10 plus the character's
+    /// Wisdom modifier.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passive_perception: Option<i32>,
+
+    /// The number of sides of the character's hit dice. Multiclassed characters should use their
+    /// most common hit die.
+    #[serde(default = "default_hit_die")]
+    pub hit_die: u32,
+
+    /// The character's spell slots: the first entry is the number of 1st-level slots, and so on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spell_slots: Vec<u32>,
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            level: 1,
+            armor_class: 10,
+            max_hit_points: 1,
+            scores: default_scores(),
+            speed: default_speed(),
+            passive_perception: None,
+            hit_die: default_hit_die(),
+            spell_slots: Vec::new(),
+        }
+    }
+}
+
+impl Player {
+    /// Returns the character's proficiency bonus, determined by their level.
+    pub fn proficiency_bonus(&self) -> Modifier {
+        2 + (self.level.clamp(1, 20) as Modifier - 1) / 4
+    }
+
+    /// Returns the character's passive Wisdom (Perception).
+    pub fn passive_perception(&self) -> i32 {
+        self.passive_perception
+            .unwrap_or_else(|| 10 + score_to_modifier(self.scores.wisdom))
+    }
+}
@@ -0,0 +1,161 @@
+//! The player view: what the players at the table may see of the fight.
+//!
+//! Players see the order of play, but not the monsters' statistics. A monster is shown under its
+//! alias while it is unidentified, its health is described in broad terms instead of exact hit
+//! points, and only the conditions the DM has marked as visible are listed.
+
+use crate::{CombatantKind, Combatant, ConditionKind, Tracker};
+use serde::{Deserialize, Serialize};
+
+/// How healthy a combatant looks to the players.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Health {
+    /// The exact hit points of a player character.
+    HitPoints {
+        /// The current hit points.
+        current: i32,
+
+        /// The hit point maximum.
+        max: i32,
+    },
+
+    /// More than half of its hit points left.
+    Healthy,
+
+    /// Half of its hit points or fewer left.
+    Bloodied,
+
+    /// A quarter of its hit points or fewer left.
+    NearDeath,
+
+    /// No hit points left.
+    Down,
+}
+
+impl Health {
+    /// Returns how healthy the combatant looks. Player characters show their exact hit points.
+    pub fn of(combatant: &Combatant) -> Self {
+        let (current, max) = (combatant.hit_points, combatant.max_hit_points);
+        if matches!(combatant.kind, CombatantKind::Player(_)) {
+            Self::HitPoints { current, max }
+        } else if current <= 0 {
+            Self::Down
+        } else if current * 4 <= max {
+            Self::NearDeath
+        } else if current * 2 <= max {
+            Self::Bloodied
+        } else {
+            Self::Healthy
+        }
+    }
+}
+
+impl std::fmt::Display for Health {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HitPoints { current, max } => write!(f, "{} / {}", current, max),
+            Self::Healthy => write!(f, "Healthy"),
+            Self::Bloodied => write!(f, "Bloodied"),
+            Self::NearDeath => write!(f, "Near death"),
+            Self::Down => write!(f, "Down"),
+        }
+    }
+}
+
+/// A combatant, as the players see it.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PlayerViewEntry {
+    /// The combatant's name, or its alias if it has one.
+    pub name: String,
+
+    /// How healthy the combatant looks.
+    pub health: Health,
+
+    /// The conditions the players can see on the combatant.
+    pub conditions: Vec<ConditionKind>,
+
+    /// Whether the combatant is delaying their turn.
+    pub delayed: bool,
+}
+
+/// The fight, as the players see it. Built with [`Tracker::player_view`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PlayerView {
+    /// The current round, starting at 0.
+    pub round: usize,
+
+    /// The index of the combatant taking their turn.
+    pub turn: usize,
+
+    /// The combatants, in order of play.
+    pub combatants: Vec<PlayerViewEntry>,
+}
+
+impl Combatant {
+    /// Returns the name the players know the combatant by: its alias if it has one, otherwise its
+    /// name.
+    pub fn player_name(&self) -> &str {
+        self.alias.as_deref().unwrap_or_else(|| self.name())
+    }
+
+    /// Returns the conditions on the combatant the players can see. Players always see their own
+    /// conditions, but a monster's conditions are only shown once the DM marks them as visible.
+    pub fn player_conditions(&self) -> Vec<ConditionKind> {
+        let player = matches!(self.kind, CombatantKind::Player(_));
+        self.conditions
+            .iter()
+            .map(|condition| condition.kind)
+            .filter(|kind| player || self.visible_conditions.contains(kind))
+            .collect()
+    }
+}
+
+impl Tracker {
+    /// Returns the fight as the players see it.
+    pub fn player_view(&self) -> PlayerView {
+        PlayerView {
+            round: self.round,
+            turn: self.turn,
+            combatants: self.combatants
+                .iter()
+                .map(|combatant| PlayerViewEntry {
+                    name: combatant.player_name().to_string(),
+                    health: Health::of(combatant),
+                    conditions: combatant.player_conditions(),
+                    delayed: combatant.delayed,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Condition, ConditionDuration, Monster, Player};
+
+    /// Ensure the player view hides monster names, hit points, and unmarked conditions.
+    #[test]
+    fn hides_monster_details() {
+        let mut tracker = Tracker::new(vec![
+            Combatant::from(Player { name: "Kira".to_string(), max_hit_points: 20, ..Default::default() }),
+            Combatant::from(Monster { name: "Doppelganger".to_string(), hit_points: 52, ..Default::default() }),
+        ]);
+        tracker.combatants[1].alias = Some("Hooded figure".to_string());
+        tracker.combatants[1].visible_conditions.push(ConditionKind::Prone);
+        for kind in [ConditionKind::Prone, ConditionKind::Charmed] {
+            for combatant in &mut tracker.combatants {
+                combatant.apply_condition(Condition { kind, duration: ConditionDuration::Forever });
+            }
+        }
+        tracker.deal_damage(1, 30);
+
+        let view = tracker.player_view();
+        assert_eq!(view.combatants[0].health, Health::HitPoints { current: 20, max: 20 });
+        assert_eq!(view.combatants[0].conditions, [ConditionKind::Prone, ConditionKind::Charmed]);
+        assert_eq!(view.combatants[1].name, "Hooded figure");
+        assert_eq!(view.combatants[1].health, Health::Bloodied);
+        assert_eq!(view.combatants[1].conditions, [ConditionKind::Prone]);
+    }
+}
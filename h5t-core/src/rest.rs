@@ -0,0 +1,443 @@
+//! Short and long rests, and the limited-use resources they restore.
+
+use crate::{
+    dice::{Expr, Term},
+    monster::Usage,
+    score_to_modifier,
+    Combatant,
+    CombatantKind,
+    ConditionDuration,
+    ConditionKind,
+    Monster,
+    Resources,
+    Tracker,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A kind of rest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Rest {
+    /// A short rest, at least an hour long.
+    Short,
+
+    /// A long rest, at least eight hours long.
+    Long,
+}
+
+impl fmt::Display for Rest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rest::Short => write!(f, "Short rest"),
+            Rest::Long => write!(f, "Long rest"),
+        }
+    }
+}
+
+/// A trait that can only be used a limited number of times before the combatant rests.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LimitedUse {
+    /// The name of the trait.
+    pub name: String,
+
+    /// The number of times the trait can be used between rests.
+    pub max: u32,
+
+    /// The number of times the trait has been used.
+    #[serde(default)]
+    pub used: u32,
+
+    /// The shortest rest that recharges the trait.
+    pub recharge: Rest,
+}
+
+impl LimitedUse {
+    /// Returns the number of uses left.
+    pub fn remaining(&self) -> u32 {
+        self.max.saturating_sub(self.used)
+    }
+}
+
+/// A combatant's spell slots of one spell level.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SpellSlots {
+    /// The spell level.
+    pub level: u32,
+
+    /// The number of slots.
+    pub max: u32,
+
+    /// The number of slots that have been expended.
+    #[serde(default)]
+    pub used: u32,
+}
+
+impl SpellSlots {
+    /// Returns the number of slots left.
+    pub fn remaining(&self) -> u32 {
+        self.max.saturating_sub(self.used)
+    }
+}
+
+/// Returns the limited-use traits of a monster.
+///
+/// Traits usable a number of times per day recharge on a long rest.
+pub(crate) fn limited_uses(monster: &Monster) -> Vec<LimitedUse> {
+    monster.traits
+        .iter()
+        .chain(&monster.legendary_actions)
+        .filter_map(|ability| {
+            let (max, recharge) = match ability.usage {
+                Usage::PerDay(count) => (count as u32, Rest::Long),
+                Usage::RechargeAfterRest => (1, Rest::Short),
+                Usage::RechargeAfterLongRest => (1, Rest::Long),
+                Usage::AtWill => return None,
+            };
+            Some(LimitedUse { name: ability.name.clone(), max, used: 0, recharge })
+        })
+        .collect()
+}
+
+/// Returns the spell slots of a monster, from its Spellcasting trait.
+pub(crate) fn spell_slots(monster: &Monster) -> Vec<SpellSlots> {
+    monster.traits
+        .iter()
+        .filter_map(|ability| ability.spellcasting.as_ref())
+        .flat_map(|spellcasting| &spellcasting.slots)
+        .map(|(&level, &max)| SpellSlots { level, max, used: 0 })
+        .collect()
+}
+
+/// What a rest restored for one combatant.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RestSummary {
+    /// The name of the combatant.
+    pub name: String,
+
+    /// A description of each thing that was restored, such as `"Regained 12 HP"`.
+    pub restored: Vec<String>,
+}
+
+impl Combatant {
+    /// Returns the number and size of the combatant's hit dice, such as `(3, 8)` for 3d8, or
+    /// `None` if it has no usable hit dice.
+    pub fn hit_dice(&self) -> Option<(u32, u32)> {
+        let hit_dice = match &self.kind {
+            CombatantKind::Monster(monster) => monster.hit_points_roll
+                .parse::<Expr>()
+                .ok()?
+                .terms
+                .into_iter()
+                .find_map(|term| match term {
+                    Term::Dice { count, sides, negative: false } => Some((count, sides)),
+                    _ => None,
+                }),
+            CombatantKind::Player(player) => Some((player.level, player.hit_die)),
+        };
+
+        // a hit die size of 0 can come from a hand-written encounter file
+        hit_dice.filter(|&(_, sides)| sides > 0)
+    }
+
+    /// Use one of the combatant's limited-use traits. Returns `false` if the combatant has no such
+    /// trait or no uses left.
+    pub fn use_limited(&mut self, name: &str) -> bool {
+        let Some(ability) = self.limited_uses
+            .iter_mut()
+            .find(|ability| ability.name.eq_ignore_ascii_case(name)) else {
+            return false;
+        };
+        if ability.remaining() == 0 {
+            return false;
+        }
+        ability.used += 1;
+        true
+    }
+
+    /// Expend a spell slot of the given level. Returns `false` if the combatant has no slots of
+    /// that level left.
+    pub fn use_spell_slot(&mut self, level: u32) -> bool {
+        match self.spell_slots.iter_mut().find(|slots| slots.level == level) {
+            Some(slots) if slots.remaining() > 0 => {
+                slots.used += 1;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Take a short rest, spending up to the given number of hit dice to regain hit points.
+    pub fn short_rest(&mut self, hit_dice: u32, rng: &mut impl Rng) -> RestSummary {
+        let mut summary = RestSummary { name: self.name().to_string(), restored: Vec::new() };
+
+        if let Some((count, sides)) = self.hit_dice() {
+            let spend = hit_dice.min(count.saturating_sub(self.hit_dice_spent));
+            let constitution = score_to_modifier(self.scores().constitution);
+            let mut healed = 0;
+            for _ in 0..spend {
+                healed += (rng.gen_range(1..=sides) as i32 + constitution).max(0);
+            }
+            self.hit_dice_spent += spend;
+
+            let before = self.hit_points.max(0);
+            self.hit_points = (before + healed).min(self.max_hit_points);
+            if spend > 0 {
+                summary.restored.push(format!(
+                    "Spent {} hit {} to regain {} HP",
+                    spend,
+                    if spend == 1 { "die" } else { "dice" },
+                    self.hit_points - before,
+                ));
+            }
+        }
+
+        self.recharge(Rest::Short, &mut summary);
+        self.end_timed_effects(&mut summary);
+        summary
+    }
+
+    /// Take a long rest: regain all hit points, half of the spent hit dice, and every limited-use
+    /// trait and spell slot, and reduce exhaustion by one level.
+    ///
+    /// A combatant must have at least 1 hit point to benefit from a long rest. In a mob, only the
+    /// living members regain hit points.
+    pub fn long_rest(&mut self) -> RestSummary {
+        let mut summary = RestSummary { name: self.name().to_string(), restored: Vec::new() };
+        if self.hit_points <= 0 {
+            summary.restored.push("No benefit at 0 HP".to_string());
+            return summary;
+        }
+
+        let before = self.hit_points;
+        match &mut self.mob {
+            // the living members of a mob recover fully, but the dead stay dead
+            Some(mob) => {
+                let max = mob.member_max_hit_points;
+                for member in mob.members.iter_mut().filter(|member| member.is_alive()) {
+                    member.hit_points = max;
+                }
+                self.update_mob_hit_points();
+            },
+            None => self.hit_points = self.max_hit_points,
+        }
+        if self.hit_points > before {
+            summary.restored.push(format!("Regained {} HP", self.hit_points - before));
+        }
+
+        if let Some((count, _)) = self.hit_dice() {
+            let regained = self.hit_dice_spent.min((count / 2).max(1));
+            if regained > 0 {
+                self.hit_dice_spent -= regained;
+                summary.restored.push(format!(
+                    "Regained {} hit {}",
+                    regained,
+                    if regained == 1 { "die" } else { "dice" },
+                ));
+            }
+        }
+
+        self.recharge(Rest::Long, &mut summary);
+
+        if self.exhaustion > 0 {
+            self.exhaustion -= 1;
+            summary.restored.push(format!("Exhaustion reduced to level {}", self.exhaustion));
+            if self.exhaustion == 0 {
+                self.conditions.retain(|condition| condition.kind != ConditionKind::Exhaustion);
+            }
+        }
+
+        self.end_timed_effects(&mut summary);
+        summary
+    }
+
+    /// Recharge the limited-use traits and spell slots restored by the given rest.
+    fn recharge(&mut self, rest: Rest, summary: &mut RestSummary) {
+        for ability in &mut self.limited_uses {
+            // a long rest recharges everything a short rest does
+            if ability.used > 0 && (rest == Rest::Long || ability.recharge == Rest::Short) {
+                ability.used = 0;
+                summary.restored.push(format!("Recharged {}", ability.name));
+            }
+        }
+
+        if rest == Rest::Long && self.spell_slots.iter().any(|slots| slots.used > 0) {
+            for slots in &mut self.spell_slots {
+                slots.used = 0;
+            }
+            summary.restored.push("Regained all spell slots".to_string());
+        }
+    }
+
+    /// End everything that can't last through an hour of rest: conditions with a duration of an
+    /// hour or less, readied actions, and the resources of the last turn.
+    fn end_timed_effects(&mut self, summary: &mut RestSummary) {
+        let mut ended = Vec::new();
+        self.conditions.retain(|condition| {
+            let expires = match condition.duration {
+                ConditionDuration::UntilNextTurn | ConditionDuration::Rounds(_) => true,
+                ConditionDuration::Minutes(minutes) => minutes.get() <= 60,
+                ConditionDuration::Forever => false,
+            };
+            if expires {
+                ended.push(condition.kind.to_string());
+            }
+            !expires
+        });
+        if !ended.is_empty() {
+            summary.restored.push(format!("Ended {}", ended.join(", ")));
+        }
+
+        self.resources = Resources::default();
+        self.readied = None;
+        self.delayed = false;
+        self.surprised = false;
+    }
+}
+
+impl Tracker {
+    /// Have the combatants at the given indices take a short rest, each spending up to the given
+    /// number of hit dice. Indices out of range are ignored.
+    pub fn short_rest(
+        &mut self,
+        rests: impl IntoIterator<Item = (usize, u32)>,
+        rng: &mut impl Rng,
+    ) -> Vec<RestSummary> {
+        rests
+            .into_iter()
+            .filter_map(|(index, hit_dice)| {
+                Some(self.combatants.get_mut(index)?.short_rest(hit_dice, rng))
+            })
+            .collect()
+    }
+
+    /// Have the combatants at the given indices take a long rest. Indices out of range are ignored.
+    pub fn long_rest(&mut self, indices: impl IntoIterator<Item = usize>) -> Vec<RestSummary> {
+        indices
+            .into_iter()
+            .filter_map(|index| Some(self.combatants.get_mut(index)?.long_rest()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{monster::Trait, Ability, Condition, Player};
+
+    /// Ensure rests recharge the right traits and restore hit points.
+    #[test]
+    fn rests() {
+        let mut combatant = Combatant::from(Monster {
+            name: "Dragon".to_string(),
+            hit_points: 50,
+            hit_points_roll: "10d8+5".to_string(),
+            scores: Ability { constitution: 10, ..Default::default() },
+            traits: vec![
+                Trait {
+                    name: "Legendary Resistance".to_string(),
+                    usage: Usage::PerDay(3),
+                    ..Default::default()
+                },
+                Trait {
+                    name: "Second Wind".to_string(),
+                    usage: Usage::RechargeAfterRest,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        });
+        assert!(combatant.use_limited("legendary resistance"));
+        assert!(combatant.use_limited("Second Wind"));
+        assert!(!combatant.use_limited("Second Wind"));
+        combatant.damage(30);
+        combatant.apply_condition(Condition {
+            kind: ConditionKind::Poisoned,
+            duration: ConditionDuration::Rounds(10.try_into().unwrap()),
+        });
+
+        let summary = combatant.short_rest(2, &mut rand::thread_rng());
+        assert_eq!(combatant.hit_dice_spent, 2);
+        assert!(combatant.hit_points > 20);
+        assert_eq!(combatant.limited_uses[0].used, 1);
+        assert_eq!(combatant.limited_uses[1].used, 0);
+        assert!(combatant.conditions.is_empty());
+        assert_eq!(summary.restored.len(), 3);
+
+        let summary = combatant.long_rest();
+        assert_eq!(combatant.hit_points, 50);
+        assert_eq!(combatant.hit_dice_spent, 0);
+        assert_eq!(combatant.limited_uses[0].used, 0);
+        assert!(summary.restored.contains(&"Recharged Legendary Resistance".to_string()));
+    }
+
+    /// Ensure a short rest with no hit dice to roll still recharges, without healing.
+    #[test]
+    fn no_hit_die() {
+        let mut combatant = Combatant::from(Player {
+            max_hit_points: 10,
+            level: 3,
+            hit_die: 0,
+            ..Default::default()
+        });
+        combatant.damage(5);
+        assert_eq!(combatant.hit_dice(), None);
+        combatant.short_rest(3, &mut rand::thread_rng());
+        assert_eq!(combatant.hit_points, 5);
+        assert_eq!(combatant.hit_dice_spent, 0);
+    }
+
+    /// Ensure a long rest reduces exhaustion one level at a time.
+    #[test]
+    fn exhaustion() {
+        let mut combatant = Combatant::from(Player {
+            max_hit_points: 10,
+            ..Default::default()
+        });
+        for _ in 0..2 {
+            combatant.apply_condition(Condition {
+                kind: ConditionKind::Exhaustion,
+                duration: ConditionDuration::Forever,
+            });
+        }
+        assert_eq!(combatant.exhaustion, 2);
+
+        combatant.long_rest();
+        assert_eq!(combatant.exhaustion, 1);
+        assert_eq!(combatant.conditions.len(), 1);
+        combatant.long_rest();
+        assert!(combatant.conditions.is_empty());
+    }
+
+    /// Ensure a long rest heals the living members of a mob, and leaves the dead where they lie.
+    #[test]
+    fn mob_long_rest() {
+        let mut mob = Combatant::from(Monster {
+            name: "Goblin".to_string(),
+            hit_points: 7,
+            ..Default::default()
+        }).into_mob(3);
+        mob.damage_members(7, 1);
+        mob.damage_members(5, 1);
+
+        let summary = mob.long_rest();
+        assert_eq!(summary.restored, ["Regained 5 HP"]);
+        let members = mob.mob.as_ref().unwrap().members.iter().map(|member| member.hit_points);
+        assert_eq!(members.collect::<Vec<_>>(), [0, 7, 7]);
+        assert_eq!((mob.hit_points, mob.max_hit_points), (14, 21));
+    }
+
+    /// Ensure rests for indices outside the tracker are ignored, rather than panicking.
+    #[test]
+    fn out_of_range() {
+        let mut tracker = Tracker::new(vec![Combatant::from(Player {
+            max_hit_points: 10,
+            ..Default::default()
+        })]);
+        tracker.combatants[0].damage(4);
+        assert_eq!(tracker.long_rest([3, 0]).len(), 1);
+        assert_eq!(tracker.combatants[0].hit_points, 10);
+        assert!(tracker.short_rest([(1, 1)], &mut rand::thread_rng()).is_empty());
+    }
+}
@@ -0,0 +1,376 @@
+//! Saved tracker sessions, which capture everything needed to resume a combat where it left off.
+//!
+//! Unlike an [`Encounter`], which describes how a fight starts, a [`Save`] records the exact state
+//! of a fight in progress: the order of play, the current round and turn, and each combatant's
+//! hit points, conditions, and remaining resources. Monsters are stored by their index in the
+//! [`Bestiary`], so the same monster data must be available when resuming.
+//!
+//! [`Encounter`]: crate::Encounter
+
+use crate::{
+    difficulty::Method,
+    encounter::EncounterError,
+    form::Form,
+    mob::Mob,
+    rest::{LimitedUse, SpellSlots},
+    stats::CombatStats,
+    Bestiary,
+    Combatant,
+    CombatantKind,
+    Condition,
+    ConditionKind,
+    InitiativeMode,
+    LogEntry,
+    NamingScheme,
+    Party,
+    Player,
+    Resources,
+    Side,
+    Tracker,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Returns true if the value is zero, to skip writing it.
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+/// The alternate form of a combatant in a [`Save`].
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SavedForm {
+    /// The form's monster index in the bestiary.
+    pub monster: String,
+
+    /// The combatant's hit points in its original form.
+    pub original_hit_points: i32,
+
+    /// The combatant's hit point maximum in its original form.
+    pub original_max_hit_points: i32,
+}
+
+/// A combatant in a [`Save`].
+///
+/// Exactly one of `monster` and `player` is set.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SavedCombatant {
+    /// The monster's index in the bestiary, if the combatant is a monster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monster: Option<String>,
+
+    /// The combatant's display name, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The combatant's current hit points.
+    pub hit_points: i32,
+
+    /// The combatant's hit point maximum.
+    pub max_hit_points: i32,
+
+    /// The combatant's initiative.
+    pub initiative: i32,
+
+    /// The resources the combatant has left this turn.
+    #[serde(default)]
+    pub resources: Resources,
+
+    /// The combatant's conditions.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<Condition>,
+
+    /// Whether the combatant is delaying their turn.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub delayed: bool,
+
+    /// Whether the combatant is surprised.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub surprised: bool,
+
+    /// The trigger of the combatant's readied action, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readied: Option<String>,
+
+    /// The combatant's limited-use traits, and how many times each has been used.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub limited_uses: Vec<LimitedUse>,
+
+    /// The combatant's spell slots, and how many of each have been expended.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub spell_slots: Vec<SpellSlots>,
+
+    /// The number of hit dice the combatant has spent.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub hit_dice_spent: u32,
+
+    /// The combatant's level of exhaustion.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub exhaustion: u32,
+
+    /// The alternate form the combatant has assumed, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub form: Option<SavedForm>,
+
+    /// The side the combatant fights on, if not the usual side for a player or monster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub side: Option<Side>,
+
+    /// The name shown to players instead of the combatant's name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+
+    /// The conditions the players can see on the combatant.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub visible_conditions: Vec<ConditionKind>,
+
+    /// Whether the combatant has taken their turn this round.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub acted: bool,
+
+    /// The members of the combatant, if it is a mob.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mob: Option<Mob>,
+
+    /// The minion group of the combatant, if it is a minion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minion: Option<String>,
+
+    /// What the combatant has done and suffered so far in the fight.
+    #[serde(default, skip_serializing_if = "CombatStats::is_empty")]
+    pub stats: CombatStats,
+
+    /// The player character, if the combatant is a player.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player: Option<Player>,
+}
+
+impl From<&Combatant> for SavedCombatant {
+    fn from(combatant: &Combatant) -> Self {
+        let (monster, player) = match &combatant.kind {
+            CombatantKind::Monster(monster) => (Some(monster.index.clone()), None),
+            CombatantKind::Player(player) => (None, Some(player.clone())),
+        };
+        Self {
+            monster,
+            name: combatant.display_name.clone(),
+            hit_points: combatant.hit_points,
+            max_hit_points: combatant.max_hit_points,
+            initiative: combatant.initiative,
+            resources: combatant.resources,
+            conditions: combatant.conditions.clone(),
+            delayed: combatant.delayed,
+            surprised: combatant.surprised,
+            readied: combatant.readied.clone(),
+            limited_uses: combatant.limited_uses.clone(),
+            spell_slots: combatant.spell_slots.clone(),
+            hit_dice_spent: combatant.hit_dice_spent,
+            exhaustion: combatant.exhaustion,
+            form: combatant.form.as_ref().map(|form| SavedForm {
+                monster: form.monster.index.clone(),
+                original_hit_points: form.original_hit_points,
+                original_max_hit_points: form.original_max_hit_points,
+            }),
+            side: (combatant.side != Side::default_for(&combatant.kind))
+                .then(|| combatant.side.clone()),
+            alias: combatant.alias.clone(),
+            visible_conditions: combatant.visible_conditions.clone(),
+            acted: combatant.acted,
+            mob: combatant.mob.clone(),
+            minion: combatant.minion.clone(),
+            stats: combatant.stats.clone(),
+            player,
+        }
+    }
+}
+
+impl SavedCombatant {
+    /// Recreate the combatant, looking up its monster in the given bestiary.
+    fn restore(&self, bestiary: &Bestiary) -> Result<Combatant, EncounterError> {
+        let mut combatant = match (&self.monster, &self.player) {
+            (Some(index), None) => bestiary
+                .get(index)
+                .cloned()
+                .map(Combatant::from)
+                .ok_or_else(|| EncounterError::UnknownMonster(index.clone()))?,
+            (None, Some(player)) => Combatant::from(player.clone()),
+            _ => return Err(EncounterError::InvalidCombatant),
+        };
+        combatant.display_name = self.name.clone();
+        combatant.hit_points = self.hit_points;
+        combatant.max_hit_points = self.max_hit_points;
+        combatant.initiative = self.initiative;
+        combatant.resources = self.resources;
+        combatant.conditions = self.conditions.clone();
+        combatant.delayed = self.delayed;
+        combatant.surprised = self.surprised;
+        combatant.readied = self.readied.clone();
+        combatant.limited_uses = self.limited_uses.clone();
+        combatant.spell_slots = self.spell_slots.clone();
+        combatant.hit_dice_spent = self.hit_dice_spent;
+        combatant.exhaustion = self.exhaustion;
+        if let Some(side) = &self.side {
+            combatant.side = side.clone();
+        }
+        combatant.alias = self.alias.clone();
+        combatant.visible_conditions = self.visible_conditions.clone();
+        combatant.acted = self.acted;
+        combatant.mob = self.mob.clone();
+        combatant.minion = self.minion.clone();
+        combatant.stats = self.stats.clone();
+        if let Some(form) = &self.form {
+            let monster = bestiary
+                .get(&form.monster)
+                .cloned()
+                .ok_or_else(|| EncounterError::UnknownMonster(form.monster.clone()))?;
+            combatant.form = Some(Form {
+                monster,
+                original_hit_points: form.original_hit_points,
+                original_max_hit_points: form.original_max_hit_points,
+            });
+        }
+        Ok(combatant)
+    }
+}
+
+/// A saved tracker session.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Save {
+    /// The current round.
+    pub round: usize,
+
+    /// The index of the combatant taking their turn.
+    pub turn: usize,
+
+    /// The levels of the characters in the party.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub party: Vec<u32>,
+
+    /// The method used to rate the encounter's difficulty.
+    #[serde(default)]
+    pub difficulty_method: Method,
+
+    /// How to name combatants that share a stat block.
+    #[serde(default)]
+    pub naming: NamingScheme,
+
+    /// How initiative is rolled, and how the turn passes from one combatant to the next.
+    #[serde(default)]
+    pub initiative_mode: InitiativeMode,
+
+    /// The combatants, in order of play.
+    #[serde(rename = "combatant")]
+    pub combatants: Vec<SavedCombatant>,
+
+    /// The combat log.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub log: Vec<LogEntry>,
+}
+
+impl From<&Tracker> for Save {
+    fn from(tracker: &Tracker) -> Self {
+        Self {
+            round: tracker.round,
+            turn: tracker.turn,
+            party: tracker.party.levels.clone(),
+            difficulty_method: tracker.difficulty_method,
+            naming: tracker.naming.clone(),
+            initiative_mode: tracker.initiative_mode,
+            combatants: tracker.combatants.iter().map(SavedCombatant::from).collect(),
+            log: tracker.log.clone(),
+        }
+    }
+}
+
+impl Save {
+    /// Parse a save from TOML.
+    pub fn from_toml(text: &str) -> Result<Self, EncounterError> {
+        toml::from_str(text).map_err(EncounterError::Parse)
+    }
+
+    /// Write the save as TOML.
+    pub fn to_toml(&self) -> Result<String, EncounterError> {
+        toml::to_string(self).map_err(EncounterError::Serialize)
+    }
+
+    /// Load a save from a TOML file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, EncounterError> {
+        let text = std::fs::read_to_string(path).map_err(EncounterError::Io)?;
+        Self::from_toml(&text)
+    }
+
+    /// Write the save to a TOML file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), EncounterError> {
+        std::fs::write(path, self.to_toml()?).map_err(EncounterError::Io)
+    }
+
+    /// Recreate the saved [`Tracker`], looking up monsters in the given bestiary.
+    pub fn restore(&self, bestiary: &Bestiary) -> Result<Tracker, EncounterError> {
+        let combatants = self.combatants
+            .iter()
+            .map(|combatant| combatant.restore(bestiary))
+            .collect::<Result<Vec<_>, _>>()?;
+        if self.turn >= combatants.len() {
+            return Err(EncounterError::InvalidCombatant);
+        }
+
+        let mut tracker = Tracker::new(combatants);
+        tracker.round = self.round;
+        tracker.turn = self.turn;
+        tracker.party = Party::new(self.party.clone());
+        tracker.difficulty_method = self.difficulty_method;
+        tracker.naming = self.naming.clone();
+        tracker.initiative_mode = self.initiative_mode;
+        tracker.log = self.log.clone();
+        Ok(tracker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConditionDuration, ConditionKind, Monster};
+
+    /// Ensure a tracker in the middle of combat is restored exactly.
+    #[test]
+    fn save_and_restore() {
+        let bestiary = Bestiary::from_iter([Monster {
+            index: "goblin".to_string(),
+            name: "Goblin".to_string(),
+            hit_points: 7,
+            ..Default::default()
+        }]);
+        let mut tracker = Tracker::new(vec![
+            Player {
+                name: "Thorin".to_string(),
+                level: 3,
+                max_hit_points: 28,
+                ..Default::default()
+            }.into(),
+            bestiary.get("goblin").unwrap().clone().into(),
+            Combatant::from(bestiary.get("goblin").unwrap().clone()).into_mob(4),
+        ]);
+        tracker.combatants[2].damage_members(7, 3);
+        tracker.combatants[0].assume_form(bestiary.get("goblin").unwrap().clone());
+        tracker.next_turn();
+        tracker.use_action();
+        tracker.current_combatant_mut().damage(3);
+        tracker.current_combatant_mut().apply_condition(Condition {
+            kind: ConditionKind::Prone,
+            duration: ConditionDuration::Forever,
+        });
+
+        let text = Save::from(&tracker).to_toml().unwrap();
+        let mut restored = Save::from_toml(&text).unwrap().restore(&bestiary).unwrap();
+        assert_eq!(restored.turn, 1);
+        assert_eq!(restored.current_combatant().name(), "Goblin");
+        assert_eq!(restored.current_combatant().hit_points, 4);
+        assert_eq!(restored.current_combatant().resources.actions, 0);
+        assert_eq!(restored.current_combatant().conditions.len(), 1);
+        assert_eq!(restored.combatants[0].name(), "Thorin");
+        assert_eq!(restored.combatants[0].hit_points, 7);
+        assert_eq!(restored.combatants[0].revert_form().unwrap().monster.name, "Goblin");
+        assert_eq!(restored.combatants[0].hit_points, 28);
+        assert_eq!(restored.combatants[2].name(), "Goblins");
+        assert_eq!(restored.combatants[2].mob.as_ref().unwrap().alive(), 1);
+    }
+}
@@ -0,0 +1,167 @@
+//! Sides, which decide who is fighting whom, and when the fight is over.
+
+use crate::{CombatantKind, Combatant, ConditionKind, Tracker};
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, str::FromStr};
+
+/// The side a combatant fights on.
+///
+/// Combatants on different sides are enemies, except for neutral combatants, who are no one's
+/// enemy and don't count towards deciding when combat is over.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum Side {
+    /// The player characters and their allies.
+    Party,
+
+    /// The party's enemies.
+    Enemy,
+
+    /// Bystanders who aren't fighting anyone.
+    Neutral,
+
+    /// Any other side, such as a third faction in a three-way fight, by name.
+    Custom(String),
+}
+
+impl Side {
+    /// Returns the side a combatant of the given kind starts on: players are in the party, and
+    /// monsters are enemies.
+    pub fn default_for(kind: &CombatantKind) -> Self {
+        match kind {
+            CombatantKind::Player(_) => Side::Party,
+            CombatantKind::Monster(_) => Side::Enemy,
+        }
+    }
+
+    /// Returns `true` if combatants on the two sides are enemies.
+    pub fn is_hostile_to(&self, other: &Side) -> bool {
+        self != other && *self != Side::Neutral && *other != Side::Neutral
+    }
+}
+
+impl From<String> for Side {
+    fn from(name: String) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "party" => Side::Party,
+            "enemy" => Side::Enemy,
+            "neutral" => Side::Neutral,
+            _ => Side::Custom(name.trim().to_string()),
+        }
+    }
+}
+
+impl From<Side> for String {
+    fn from(side: Side) -> Self {
+        side.to_string()
+    }
+}
+
+impl FromStr for Side {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Side::from(s.to_string()))
+    }
+}
+
+impl std::fmt::Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Side::Party => write!(f, "party"),
+            Side::Enemy => write!(f, "enemy"),
+            Side::Neutral => write!(f, "neutral"),
+            Side::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl Combatant {
+    /// Returns `true` if the combatant can still fight: it has hit points left and isn't
+    /// unconscious.
+    pub fn is_conscious(&self) -> bool {
+        self.hit_points > 0 && !self.conditions
+            .iter()
+            .any(|condition| condition.kind == ConditionKind::Unconscious)
+    }
+}
+
+impl Tracker {
+    /// Returns the indices of the combatants on the same side as the given combatant, including
+    /// itself.
+    pub fn allies(&self, index: usize) -> Vec<usize> {
+        let side = &self.combatants[index].side;
+        self.combatants
+            .iter()
+            .enumerate()
+            .filter(|(_, combatant)| combatant.side == *side)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the indices of the combatants hostile to the given combatant.
+    pub fn enemies(&self, index: usize) -> Vec<usize> {
+        let side = &self.combatants[index].side;
+        self.combatants
+            .iter()
+            .enumerate()
+            .filter(|(_, combatant)| combatant.side.is_hostile_to(side))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns the sides, other than neutral, that still have a conscious combatant, in order of
+    /// play.
+    pub fn standing_sides(&self) -> Vec<&Side> {
+        let mut sides = Vec::new();
+        for combatant in &self.combatants {
+            if combatant.side != Side::Neutral
+                && combatant.is_conscious()
+                && !sides.contains(&&combatant.side)
+            {
+                sides.push(&combatant.side);
+            }
+        }
+        sides
+    }
+
+    /// Returns `true` if combat is over: the fight involved at least two sides (not counting
+    /// neutral combatants), and no more than one of them has anyone conscious left.
+    pub fn is_over(&self) -> bool {
+        let mut sides = self.combatants
+            .iter()
+            .map(|combatant| &combatant.side)
+            .filter(|side| **side != Side::Neutral);
+        let Some(first) = sides.next() else {
+            return false;
+        };
+        sides.any(|side| side != first) && self.standing_sides().len() < 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Monster, Player};
+
+    /// Ensure combat is over once one side is down, and neutral bystanders don't keep it going.
+    #[test]
+    fn combat_over() {
+        let mut tracker = Tracker::new(vec![
+            Combatant::from(Player { name: "Thorin".to_string(), max_hit_points: 20, ..Default::default() }),
+            Combatant::from(Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }),
+            Combatant::from(Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }),
+            Combatant::from(Monster { name: "Merchant".to_string(), hit_points: 4, ..Default::default() }),
+        ]);
+        tracker.combatants[3].side = "Neutral".parse().unwrap();
+        assert_eq!(tracker.enemies(0), [1, 2]);
+        assert_eq!(tracker.allies(1), [1, 2]);
+        assert!(!tracker.is_over());
+
+        tracker.combatants[1].damage(7);
+        assert!(!tracker.is_over());
+        tracker.combatants[2].damage(9);
+        assert!(tracker.is_over());
+        assert_eq!(tracker.standing_sides(), [&Side::Party]);
+    }
+}
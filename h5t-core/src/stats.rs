@@ -0,0 +1,276 @@
+//! Statistics about what each combatant did and suffered over the course of a fight.
+//!
+//! Damage, healing, and conditions can be credited to the combatant who caused them, which is the
+//! combatant whose turn it is unless another source is given, such as with
+//! [`Tracker::deal_damage_from`].
+
+use crate::{Combatant, Condition, ConditionKind, Tracker};
+use serde::{Deserialize, Serialize};
+
+/// A combatant's running totals for the fight.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CombatStats {
+    /// The total damage the combatant dealt to others, not counting damage past 0 hit points.
+    #[serde(default)]
+    pub damage_dealt: i32,
+
+    /// The total hit points the combatant lost to damage.
+    #[serde(default)]
+    pub damage_taken: i32,
+
+    /// The total hit points the combatant restored to others.
+    #[serde(default)]
+    pub healing_done: i32,
+
+    /// The number of combatants (or mob members) the combatant dropped to 0 hit points.
+    #[serde(default)]
+    pub kills: u32,
+
+    /// The number of critical hits the combatant scored.
+    #[serde(default)]
+    pub crits: u32,
+
+    /// The number of conditions the combatant applied to others.
+    #[serde(default)]
+    pub conditions_applied: u32,
+
+    /// Each condition inflicted on the combatant during the fight, in the order they were first
+    /// inflicted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions_inflicted: Vec<ConditionKind>,
+}
+
+impl CombatStats {
+    /// Returns `true` if nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Combatant {
+    /// Add the damage the combatant is about to take from the given amount to its statistics.
+    ///
+    /// Only the hit points the combatant actually loses count, so damage past 0 hit points isn't
+    /// counted. A transformed combatant can also lose its original form's hit points, and a mob
+    /// takes the damage on its first living member. Healing isn't damage, and isn't counted.
+    pub(crate) fn record_damage_taken(&mut self, amount: i32) {
+        let taken = match (&self.mob, &self.form) {
+            (Some(mob), _) => mob.damage_taken(amount, 1),
+            (None, Some(form)) => {
+                let remaining = self.hit_points
+                    .max(0)
+                    .saturating_add(form.original_hit_points.max(0));
+                amount.clamp(0, remaining)
+            },
+            (None, None) => amount.clamp(0, self.hit_points.max(0)),
+        };
+        self.stats.damage_taken = self.stats.damage_taken.saturating_add(taken);
+    }
+
+    /// Returns the number of creatures in the combatant still above 0 hit points: each living
+    /// member of a mob, or the combatant itself.
+    fn standing(&self) -> usize {
+        match &self.mob {
+            Some(mob) => mob.alive(),
+            None => usize::from(self.hit_points > 0),
+        }
+    }
+}
+
+/// The totals across every combatant before a change, to work out what the change did.
+#[derive(Clone, Copy, Debug)]
+struct Tally {
+    /// The total damage taken by every combatant.
+    damage_taken: i32,
+
+    /// The number of creatures above 0 hit points.
+    standing: usize,
+
+    /// The hit points of the target.
+    hit_points: i32,
+}
+
+impl Tracker {
+    /// Deal damage to the combatant at the given index, crediting the damage, or the healing if
+    /// the amount is negative, to the combatant at `source`. With no source, the damage is still
+    /// counted as taken, but isn't credited to anyone.
+    ///
+    /// This is otherwise the same as [`Tracker::deal_damage`], which credits the combatant whose
+    /// turn it is.
+    pub fn deal_damage_from(
+        &mut self,
+        source: Option<usize>,
+        index: usize,
+        amount: i32,
+    ) -> Vec<usize> {
+        let before = self.tally(index);
+        let dropped = self.carry_damage(index, amount);
+        self.credit(source, index, before);
+        dropped
+    }
+
+    /// Deal the given damage to each of the first `count` living members of the mob at the given
+    /// index, crediting it to the combatant whose turn it is. Returns the number of members it
+    /// dropped. See [`Combatant::damage_members`].
+    pub fn damage_members(&mut self, index: usize, amount: i32, count: usize) -> usize {
+        self.damage_members_from(Some(self.turn), index, amount, count)
+    }
+
+    /// Deal the given damage to each of the first `count` living members of the mob at the given
+    /// index, crediting it to the combatant at `source`, if any. Returns the number of members it
+    /// dropped.
+    pub fn damage_members_from(
+        &mut self,
+        source: Option<usize>,
+        index: usize,
+        amount: i32,
+        count: usize,
+    ) -> usize {
+        let before = self.tally(index);
+        let dropped = self.combatants[index].damage_members(amount, count);
+        self.credit(source, index, before);
+        dropped
+    }
+
+    /// Apply a condition to the combatant at the given index, crediting it to the combatant whose
+    /// turn it is, and record it in the target's statistics.
+    pub fn inflict_condition(&mut self, index: usize, condition: Condition) {
+        self.inflict_condition_from(Some(self.turn), index, condition);
+    }
+
+    /// Apply a condition to the combatant at the given index, crediting it to the combatant at
+    /// `source`, if any, and record it in the target's statistics.
+    pub fn inflict_condition_from(
+        &mut self,
+        source: Option<usize>,
+        index: usize,
+        condition: Condition,
+    ) {
+        let combatant = &mut self.combatants[index];
+        if !combatant.stats.conditions_inflicted.contains(&condition.kind) {
+            combatant.stats.conditions_inflicted.push(condition.kind);
+        }
+        combatant.apply_condition(condition);
+
+        if let Some(source) = source.filter(|&source| source != index) {
+            if let Some(source) = self.combatants.get_mut(source) {
+                source.stats.conditions_applied += 1;
+            }
+        }
+    }
+
+    /// Returns the index of the combatant who dealt the most damage, if anyone dealt any.
+    pub fn mvp(&self) -> Option<usize> {
+        self.combatants
+            .iter()
+            .enumerate()
+            .filter(|(_, combatant)| combatant.stats.damage_dealt > 0)
+            .max_by_key(|(_, combatant)| combatant.stats.damage_dealt)
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the totals before a change to the combatant at the given index.
+    fn tally(&self, index: usize) -> Tally {
+        Tally {
+            damage_taken: self.combatants
+                .iter()
+                .map(|combatant| combatant.stats.damage_taken)
+                .fold(0, i32::saturating_add),
+            standing: self.combatants.iter().map(Combatant::standing).sum(),
+            hit_points: self.combatants[index].hit_points,
+        }
+    }
+
+    /// Credit the damage, healing, and kills since the `before` tally to the source, unless they
+    /// hurt or healed themselves.
+    fn credit(&mut self, source: Option<usize>, target: usize, before: Tally) {
+        let after = self.tally(target);
+        let Some(source) = source.filter(|&source| source != target) else {
+            return;
+        };
+        let Some(source) = self.combatants.get_mut(source) else {
+            return;
+        };
+        let dealt = after.damage_taken.saturating_sub(before.damage_taken);
+        let healed = after.hit_points.saturating_sub(before.hit_points).max(0);
+        source.stats.damage_dealt = source.stats.damage_dealt.saturating_add(dealt);
+        source.stats.healing_done = source.stats.healing_done.saturating_add(healed);
+        source.stats.kills += before.standing.saturating_sub(after.standing) as u32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConditionDuration, Monster, Player};
+
+    /// Ensure damage is credited to whoever's turn it is, including damage carried over between
+    /// minions, and that conditions are only listed once.
+    #[test]
+    fn damage_and_conditions() {
+        let mut minion = Combatant::from(Monster {
+            name: "Skeleton".to_string(),
+            hit_points: 5,
+            ..Default::default()
+        });
+        minion.minion = Some("skeletons".to_string());
+        let mut tracker = Tracker::new(vec![
+            Combatant::from(Player { name: "Kira".to_string(), max_hit_points: 20, ..Default::default() }),
+            minion.clone(),
+            minion,
+        ]);
+
+        tracker.deal_damage(1, 8);
+        assert_eq!(tracker.combatants[1].stats.damage_taken, 5);
+        assert_eq!(tracker.combatants[2].stats.damage_taken, 3);
+        assert_eq!(tracker.combatants[0].stats.damage_dealt, 8);
+        assert_eq!(tracker.combatants[0].stats.kills, 2);
+
+        tracker.deal_damage(0, 4);
+        tracker.deal_damage(0, -2);
+        assert_eq!(tracker.combatants[0].stats.damage_taken, 4);
+        assert_eq!(tracker.combatants[0].stats.damage_dealt, 8);
+        assert_eq!(tracker.combatants[0].stats.healing_done, 0);
+
+        for _ in 0..2 {
+            tracker.inflict_condition(0, Condition {
+                kind: ConditionKind::Prone,
+                duration: ConditionDuration::Forever,
+            });
+        }
+        assert_eq!(tracker.combatants[0].stats.conditions_inflicted, [ConditionKind::Prone]);
+        assert_eq!(tracker.combatants[0].stats.conditions_applied, 0);
+    }
+
+    /// Ensure kills and healing are credited to the given source, that damage with no source
+    /// isn't credited to anyone, and that damage past 0 hit points isn't counted for any target.
+    #[test]
+    fn sources() {
+        let player = |name: &str| Combatant::from(Player {
+            name: name.to_string(),
+            max_hit_points: 20,
+            ..Default::default()
+        });
+        let goblin = Combatant::from(Monster {
+            name: "Goblin".to_string(),
+            hit_points: 7,
+            ..Default::default()
+        });
+        let mut tracker = Tracker::new(vec![player("Kira"), player("Thorin"), goblin.into_mob(3)]);
+
+        tracker.deal_damage_from(Some(2), 1, 12);
+        tracker.deal_damage_from(Some(0), 1, -5);
+        tracker.damage_members_from(Some(1), 2, 10, 2);
+        tracker.deal_damage_from(None, 2, 3);
+        assert_eq!(tracker.combatants[0].stats.healing_done, 5);
+        assert_eq!(tracker.combatants[1].stats.damage_dealt, 14);
+        assert_eq!(tracker.combatants[1].stats.kills, 2);
+        assert_eq!(tracker.combatants[2].stats.damage_dealt, 12);
+        assert_eq!(tracker.combatants[2].stats.damage_taken, 17);
+        assert_eq!(tracker.mvp(), Some(1));
+
+        tracker.deal_damage_from(Some(1), 0, 50);
+        assert_eq!(tracker.combatants[0].stats.damage_taken, 20);
+        assert_eq!(tracker.combatants[1].stats.damage_dealt, 34);
+    }
+}
@@ -0,0 +1,243 @@
+//! Summaries of a finished fight: who won, who fell, and how much XP the party earned.
+
+use crate::{CombatStats, CombatantKind, Side, Tracker};
+
+/// How one combatant ended the fight.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CombatantSummary {
+    /// The combatant's name.
+    pub name: String,
+
+    /// The side the combatant fought on.
+    pub side: Side,
+
+    /// The combatant's hit points at the end of the fight.
+    pub hit_points: i32,
+
+    /// The combatant's hit point maximum.
+    pub max_hit_points: i32,
+
+    /// Whether the combatant was defeated: knocked out, killed, or otherwise out of the fight.
+    pub defeated: bool,
+
+    /// Whether the combatant was killed. Monsters die at 0 hit points, while players only die
+    /// outright from damage that leaves them at the negative of their hit point maximum.
+    pub killed: bool,
+
+    /// The XP awarded for defeating the combatant. Only defeated monsters hostile to the party are
+    /// worth XP, and a mob is worth the XP of each member that fell.
+    pub xp: i32,
+
+    /// What the combatant did and suffered during the fight.
+    pub stats: CombatStats,
+}
+
+/// A summary of a fight, built with [`Tracker::summary`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncounterSummary {
+    /// The number of rounds the fight lasted.
+    pub rounds: usize,
+
+    /// The sides, other than neutral, that still have someone standing.
+    pub standing: Vec<Side>,
+
+    /// Each combatant's summary, in order of play.
+    pub combatants: Vec<CombatantSummary>,
+
+    /// The number of player characters in the party, who split the XP.
+    pub party_size: usize,
+}
+
+impl EncounterSummary {
+    /// Returns the total XP of every defeated monster.
+    pub fn total_xp(&self) -> i32 {
+        self.combatants.iter().map(|combatant| combatant.xp).sum()
+    }
+
+    /// Returns each player character's share of the XP, rounded down. Returns the total XP if there
+    /// are no player characters.
+    pub fn xp_per_character(&self) -> i32 {
+        self.total_xp() / self.party_size.max(1) as i32
+    }
+
+    /// Returns a sentence describing how the fight ended, such as "The party side wins after 3
+    /// rounds".
+    pub fn outcome(&self) -> String {
+        let outcome = match &self.standing[..] {
+            [] => "No one is left standing".to_string(),
+            [side] => format!("The {} side wins", side),
+            sides => format!(
+                "Still standing: {}",
+                sides.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "),
+            ),
+        };
+        let rounds = if self.rounds == 1 { "round" } else { "rounds" };
+        format!("{} after {} {}", outcome, self.rounds, rounds)
+    }
+
+    /// Write the summary as a Markdown report.
+    pub fn to_markdown(&self) -> String {
+        let mut report = String::from("# Encounter summary\n\n");
+        report.push_str(&format!("{}.\n\n", self.outcome()));
+
+        report.push_str("| Name | Side | HP | Status | Dealt | Taken | Healed | Kills | Crits ");
+        report.push_str("| Conditions |\n|---|---|---|---|---|---|---|---|---|---|\n");
+        for combatant in &self.combatants {
+            let stats = &combatant.stats;
+            let conditions = stats.conditions_inflicted
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            report.push_str(&format!(
+                "| {} | {} | {} / {} | {} | {} | {} | {} | {} | {} | {} |\n",
+                escape(&combatant.name),
+                escape(&combatant.side.to_string()),
+                combatant.hit_points,
+                combatant.max_hit_points,
+                combatant.status(),
+                stats.damage_dealt,
+                stats.damage_taken,
+                stats.healing_done,
+                stats.kills,
+                stats.crits,
+                if conditions.is_empty() { "—".to_string() } else { conditions },
+            ));
+        }
+
+        report.push_str("\n## Experience\n\n");
+        for combatant in self.combatants.iter().filter(|combatant| combatant.xp > 0) {
+            report.push_str(&format!("- {}: {} XP\n", escape(&combatant.name), combatant.xp));
+        }
+        report.push_str(&format!("\n**Total:** {} XP", self.total_xp()));
+        if self.party_size > 0 {
+            report.push_str(&format!(
+                ", or {} XP for each of {} character{}",
+                self.xp_per_character(),
+                self.party_size,
+                if self.party_size == 1 { "" } else { "s" },
+            ));
+        }
+        report.push('\n');
+        report
+    }
+}
+
+impl CombatantSummary {
+    /// Returns a word describing how the combatant ended the fight.
+    pub fn status(&self) -> &'static str {
+        if self.killed {
+            "Killed"
+        } else if self.defeated {
+            "Defeated"
+        } else {
+            "Standing"
+        }
+    }
+}
+
+/// Escape the characters in the text that would break a Markdown table.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+impl Tracker {
+    /// Summarize the fight so far.
+    pub fn summary(&self) -> EncounterSummary {
+        let combatants = self.combatants
+            .iter()
+            .map(|combatant| {
+                let defeated = !combatant.is_conscious();
+                let (killed, xp) = match &combatant.kind {
+                    CombatantKind::Monster(monster) => {
+                        let fallen = match &combatant.mob {
+                            Some(mob) => (mob.members.len() - mob.alive()) as i32,
+                            None => i32::from(defeated),
+                        };
+                        // only enemies count, as in the encounter's difficulty rating
+                        let xp = if combatant.side.is_hostile_to(&Side::Party) {
+                            monster.xp * fallen
+                        } else {
+                            0
+                        };
+                        (combatant.hit_points <= 0, xp)
+                    },
+                    CombatantKind::Player(_) => {
+                        (combatant.hit_points <= -combatant.max_hit_points, 0)
+                    },
+                };
+                CombatantSummary {
+                    name: combatant.name().to_string(),
+                    side: combatant.side.clone(),
+                    hit_points: combatant.hit_points,
+                    max_hit_points: combatant.max_hit_points,
+                    defeated,
+                    killed,
+                    xp,
+                    stats: combatant.stats.clone(),
+                }
+            })
+            .collect();
+
+        EncounterSummary {
+            rounds: self.round + 1,
+            standing: self.standing_sides().into_iter().cloned().collect(),
+            combatants,
+            party_size: self.combatants
+                .iter()
+                .filter(|combatant| {
+                    matches!(combatant.kind, CombatantKind::Player(_))
+                        && combatant.side == Side::Party
+                })
+                .count(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Combatant, Monster, Player};
+
+    /// Ensure only defeated enemies are worth XP, not neutral bystanders, split between the party,
+    /// and that the report lists everyone.
+    #[test]
+    fn xp_awards() {
+        let player = |name: &str| Combatant::from(Player {
+            name: name.to_string(),
+            max_hit_points: 10,
+            ..Default::default()
+        });
+        let goblin = || Combatant::from(Monster {
+            name: "Goblin".to_string(),
+            hit_points: 7,
+            xp: 50,
+            ..Default::default()
+        });
+        let mut tracker = Tracker::new(vec![
+            player("Thorin"),
+            player("Kira"),
+            goblin(),
+            goblin(),
+            goblin(),
+        ]);
+        tracker.combatants[3].display_name = Some("Goblin Boss".to_string());
+        tracker.combatants[4].side = Side::Neutral;
+        tracker.deal_damage(2, 7);
+        tracker.deal_damage_from(None, 4, 7);
+        tracker.deal_damage(1, 12);
+        tracker.round = 2;
+
+        let summary = tracker.summary();
+        assert_eq!(summary.rounds, 3);
+        assert_eq!(summary.combatants[1].status(), "Defeated");
+        assert_eq!(summary.combatants[2].status(), "Killed");
+        assert_eq!((summary.total_xp(), summary.xp_per_character()), (50, 25));
+        assert_eq!(summary.combatants[0].stats.damage_dealt, 17);
+
+        let report = summary.to_markdown();
+        let boss = "| Goblin Boss | enemy | 7 / 7 | Standing | 0 | 0 | 0 | 0 | 0 | — |";
+        assert!(report.contains(boss));
+        assert!(report.contains("**Total:** 50 XP, or 25 XP for each of 2 characters"));
+    }
+}
@@ -0,0 +1,353 @@
+//! The command-line interface.
+//!
+//! Running `h5t` with no subcommand launches the tracker with every monster in the bestiary. The
+//! other subcommands are meant for scripts and for preparing outside of combat.
+
+use clap::{Parser, Subcommand};
+use crate::{theme::Theme, widgets::stat_block::{fmt_cr, fmt_languages, fmt_usage}};
+use h5t_core::{
+    bestiary,
+    dice::Expr,
+    difficulty::Method,
+    Bestiary,
+    Encounter,
+    Monster,
+    Save,
+    Tracker,
+};
+use std::path::{Path, PathBuf};
+
+/// A hyper-efficient initiative tracker for D&D 5th Edition.
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Args {
+    /// The directory holding the SRD monster data, `monsters.json`.
+    #[arg(long, global = true, value_name = "DIR", default_value = "data")]
+    pub data_dir: PathBuf,
+
+    /// The color theme of the tracker.
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        default_value = "default",
+        value_parser = clap::builder::PossibleValuesParser::new(Theme::NAMES),
+    )]
+    pub theme: String,
+
+    /// Campaign-specific monster files or directories, loaded after the SRD and the user's
+    /// homebrew monsters. Can be given multiple times.
+    #[arg(short, long, global = true, value_name = "PATH")]
+    pub monsters: Vec<PathBuf>,
+
+    /// Share the player view on the given address, such as `127.0.0.1:7878`, for `h5t players` to
+    /// show on another screen.
+    #[arg(long, global = true, value_name = "ADDR")]
+    pub share: Option<String>,
+
+    /// Accept JSON-RPC requests on the given address, such as `127.0.0.1:7879`, to query and
+    /// drive the tracker from other programs.
+    #[arg(long, global = true, value_name = "ADDR")]
+    pub control: Option<String>,
+
+    /// What to do. Defaults to running the tracker with every monster in the bestiary.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// A subcommand.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the tracker with the combatants of an encounter file.
+    Run {
+        /// The encounter file. If omitted, every monster in the bestiary is tracked.
+        encounter: Option<PathBuf>,
+    },
+
+    /// Resume a session saved from the tracker.
+    Resume {
+        /// The save file.
+        save: PathBuf,
+    },
+
+    /// Show the player view of a tracker running with `--share`, which hides monster statistics,
+    /// for a screen facing the players.
+    Players {
+        /// The address the tracker is sharing on, such as `127.0.0.1:7878`.
+        address: String,
+    },
+
+    /// Write the summary of a saved session as a Markdown report, with each combatant's damage,
+    /// conditions, and the XP earned.
+    Report {
+        /// The save file.
+        save: PathBuf,
+
+        /// The file to write. If omitted, the report is printed to standard output.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Roll a dice expression, such as `2d6+3`, and print the result.
+    Roll {
+        /// The dice expression.
+        expr: Expr,
+    },
+
+    /// Print a monster's stat block as text.
+    Monster {
+        /// The monster's name or index. The closest match is used.
+        #[arg(required = true)]
+        name: Vec<String>,
+    },
+
+    /// Print the difficulty of an encounter file.
+    Difficulty {
+        /// The encounter file.
+        encounter: PathBuf,
+
+        /// The rules used to rate the encounter, overriding the one in the file.
+        #[arg(long, value_parser = parse_method)]
+        method: Option<Method>,
+    },
+
+    /// Check monster data and encounter files for errors.
+    ///
+    /// JSON files and directories are loaded as monsters; TOML files are checked as encounters
+    /// against the bestiary.
+    Validate {
+        /// The files or directories to check.
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+
+    /// Convert a monster file from the SRD API format to h5t's native format.
+    ///
+    /// The native format is written as a JSON array of monsters. Files already in the native format
+    /// are accepted too, and are written back unchanged apart from formatting.
+    Convert {
+        /// The monster file to convert.
+        input: PathBuf,
+
+        /// The file to write. If omitted, the monsters are printed to standard output.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Parse the name of a difficulty method.
+fn parse_method(s: &str) -> Result<Method, String> {
+    match s {
+        "dmg2014" => Ok(Method::Dmg2014),
+        "dmg2024" => Ok(Method::Dmg2024),
+        _ => Err("expected `dmg2014` or `dmg2024`".to_string()),
+    }
+}
+
+/// Returns the directory that holds the user's homebrew monsters, following the XDG base directory
+/// specification: `$XDG_DATA_HOME/h5t/monsters`, or `~/.local/share/h5t/monsters`.
+fn user_data_dir() -> Option<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+    Some(data_home.join("h5t").join("monsters"))
+}
+
+/// Print an error and exit.
+pub fn fail(err: impl std::fmt::Display) -> ! {
+    eprintln!("error: {}", err);
+    std::process::exit(1);
+}
+
+impl Args {
+    /// Load the bestiary.
+    ///
+    /// Monsters are loaded in layers, with later layers overriding earlier ones:
+    ///
+    /// 1. the SRD
+    /// 2. the user's homebrew monsters
+    /// 3. campaign-specific files given on the command line
+    pub fn bestiary(&self) -> Bestiary {
+        // NOTE: monster JSON data provided courtesy of https://www.dnd5eapi.co/
+        let mut layers = vec![self.data_dir.join("monsters.json")];
+        layers.extend(user_data_dir().filter(|dir| dir.is_dir()));
+        layers.extend(self.monsters.iter().cloned());
+
+        Bestiary::load_layers(&layers).unwrap_or_else(|err| fail(err))
+    }
+}
+
+/// Load an encounter file and build its tracker, or exit with an error.
+pub fn build_encounter(path: &Path, bestiary: &Bestiary) -> Tracker {
+    Encounter::load(path)
+        .and_then(|encounter| encounter.build(bestiary, &mut rand::thread_rng()))
+        .unwrap_or_else(|err| fail(format!("{}: {}", path.display(), err)))
+}
+
+/// Load a save file and restore its tracker, or exit with an error.
+pub fn resume(path: &Path, bestiary: &Bestiary) -> Tracker {
+    Save::load(path)
+        .and_then(|save| save.restore(bestiary))
+        .unwrap_or_else(|err| fail(format!("{}: {}", path.display(), err)))
+}
+
+/// Write the Markdown report of a saved session.
+pub fn report(path: &Path, output: Option<&Path>, bestiary: &Bestiary) {
+    let report = resume(path, bestiary).summary().to_markdown();
+    match output {
+        Some(path) => std::fs::write(path, report)
+            .unwrap_or_else(|err| fail(format!("{}: {}", path.display(), err))),
+        None => print!("{}", report),
+    }
+}
+
+/// Roll a dice expression and print each die and the total.
+pub fn roll(expr: &Expr) {
+    let outcome = expr.roll(&mut rand::thread_rng());
+    if outcome.rolls.is_empty() {
+        println!("{}", outcome.total);
+    } else {
+        let rolls = outcome.rolls.iter().map(u32::to_string).collect::<Vec<_>>();
+        println!("{} = {} ({})", expr, outcome.total, rolls.join(", "));
+    }
+}
+
+/// Format a monster's stat block as plain text.
+pub fn stat_block_text(monster: &Monster) -> String {
+    let mut lines = vec![monster.name.clone()];
+    let subtype = monster.subtype
+        .as_ref()
+        .map(|subtype| format!(" ({})", subtype))
+        .unwrap_or_default();
+    lines.push(format!("{} {}{}, {}", monster.size, monster.r#type, subtype, monster.alignment));
+    lines.push(String::new());
+
+    lines.push(format!("Armor Class {}", monster.armor_class.value));
+    lines.push(format!("Hit Points {} ({})", monster.hit_points, monster.hit_points_roll));
+    lines.push(format!("Speed {}", monster.speed));
+    lines.push(String::new());
+
+    let scores = &monster.scores;
+    let modifiers = scores.modifiers();
+    lines.push("STR      DEX      CON      INT      WIS      CHA".to_string());
+    lines.push([
+        (scores.strength, modifiers.strength),
+        (scores.dexterity, modifiers.dexterity),
+        (scores.constitution, modifiers.constitution),
+        (scores.intelligence, modifiers.intelligence),
+        (scores.wisdom, modifiers.wisdom),
+        (scores.charisma, modifiers.charisma),
+    ]
+        .iter()
+        .map(|(score, modifier)| format!("{:<9}", format!("{} ({:+})", score, modifier)))
+        .collect::<String>()
+        .trim_end()
+        .to_string());
+    lines.push(String::new());
+
+    for (label, list) in [
+        ("Damage Vulnerabilities", &monster.damage_vulnerabilities),
+        ("Damage Resistances", &monster.damage_resistances),
+        ("Damage Immunities", &monster.damage_immunities),
+    ] {
+        if !list.is_empty() {
+            lines.push(format!("{} {}", label, list.join(", ")));
+        }
+    }
+    let senses = monster.senses.to_string();
+    if !senses.is_empty() {
+        lines.push(format!("Senses {}", senses));
+    }
+    lines.push(format!("Languages {}", fmt_languages(&monster.languages)));
+    lines.push(format!("Challenge {}", fmt_cr(monster.challenge_rating, monster.xp)));
+    lines.push(format!("Proficiency Bonus {:+}", monster.proficiency_bonus));
+
+    for ability in &monster.traits {
+        lines.push(String::new());
+        lines.push(format!("{}{}{}", ability.name, fmt_usage(&ability.usage), ability.desc));
+    }
+
+    if !monster.actions.is_empty() {
+        lines.push(String::new());
+        lines.push("Actions".to_string());
+        for action in &monster.actions {
+            lines.push(String::new());
+            lines.push(format!("{}. {}", action.name, action.desc));
+        }
+    }
+
+    if !monster.legendary_actions.is_empty() {
+        lines.push(String::new());
+        lines.push("Legendary Actions".to_string());
+        for action in &monster.legendary_actions {
+            lines.push(String::new());
+            lines.push(format!("{}. {}", action.name, action.desc));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Convert a monster file to the native format, writing it to the given path or printing it.
+pub fn convert(input: &Path, output: Option<&Path>) {
+    let monsters = bestiary::read_monsters(input).unwrap_or_else(|err| fail(err));
+    let json = serde_json::to_string_pretty(&monsters).unwrap_or_else(|err| fail(err));
+    match output {
+        Some(path) => std::fs::write(path, json + "\n")
+            .unwrap_or_else(|err| fail(format!("{}: {}", path.display(), err))),
+        None => println!("{}", json),
+    }
+}
+
+/// Print the stat block of the monster best matching the given name.
+pub fn monster(name: &str, bestiary: &Bestiary) {
+    let monster = bestiary.get(name)
+        .or_else(|| bestiary.query().name(name).run().first().copied())
+        .unwrap_or_else(|| fail(format!("no monster matches `{}`", name)));
+    println!("{}", stat_block_text(monster));
+}
+
+/// Print the difficulty of an encounter file.
+pub fn difficulty(path: &Path, method: Option<Method>, bestiary: &Bestiary) {
+    let mut tracker = build_encounter(path, bestiary);
+    if let Some(method) = method {
+        tracker.difficulty_method = method;
+    }
+
+    match tracker.difficulty() {
+        Some(rating) => println!(
+            "{} ({} XP, {} adjusted)",
+            rating.difficulty,
+            rating.total_xp,
+            rating.adjusted_xp,
+        ),
+        None => fail(format!("{}: the encounter has no players", path.display())),
+    }
+}
+
+/// Check each path for errors, printing them, and return `true` if all paths are valid.
+pub fn validate(paths: &[PathBuf], bestiary: &Bestiary) -> bool {
+    let mut valid = true;
+    for path in paths {
+        let result = if path.extension().is_some_and(|ext| ext == "toml") {
+            Encounter::load(path)
+                .and_then(|encounter| encounter.build(bestiary, &mut rand::thread_rng()))
+                .map(|tracker| format!("{} combatants", tracker.combatants.len()))
+                .map_err(|err| format!("{}: {}", path.display(), err))
+        } else {
+            Bestiary::new()
+                .load(path)
+                .map(|count| format!("{} monsters", count))
+                .map_err(|err| err.to_string())
+        };
+
+        match result {
+            Ok(summary) => println!("{}: ok ({})", path.display(), summary),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                valid = false;
+            },
+        }
+    }
+    valid
+}
@@ -0,0 +1,447 @@
+//! A JSON-RPC API for controlling a running tracker, such as from a macro pad or a bot.
+//!
+//! The tracker listens on an address given with `--control`. Each connection sends JSON-RPC 2.0
+//! requests, one per line, and gets one response line for each request with an `id`. The methods
+//! are:
+//!
+//! - `state`: returns the tracker's state.
+//! - `next_turn`: ends the current turn.
+//! - `damage`: `{"target", "amount", "source"?}` deals damage to a combatant.
+//! - `heal`: `{"target", "amount", "source"?}` restores a combatant's hit points.
+//! - `apply_condition`: `{"target", "condition", "duration"?, "source"?}` applies a condition,
+//!   forever unless a duration such as `{"rounds": 2}` is given.
+//! - `use_resource`: `{"resource", "combatant"?}` uses an `action`, `bonus_action`, or `reaction`.
+//!
+//! Combatants are given by index in the order of play or by name. Damage, healing, and conditions
+//! are credited to the combatant whose turn it is, unless a `source` is given. Every method that
+//! succeeds returns the tracker's state afterwards.
+
+use h5t_core::{
+    CombatStats,
+    Condition,
+    ConditionDuration,
+    ConditionKind,
+    Resources,
+    Side,
+    Tracker,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+/// A combatant, by index in the order of play or by name.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum Target {
+    /// The combatant's index.
+    Index(usize),
+
+    /// The combatant's name, ignoring case.
+    Name(String),
+}
+
+/// A resource a combatant can use.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Resource {
+    /// An action.
+    Action,
+
+    /// A bonus action.
+    BonusAction,
+
+    /// A reaction.
+    Reaction,
+}
+
+/// The parameters of `damage` and `heal`.
+#[derive(Clone, Debug, Deserialize)]
+struct DamageParams {
+    /// The combatant to damage or heal.
+    target: Target,
+
+    /// The amount of damage or healing, which can't be negative.
+    amount: i32,
+
+    /// The combatant to credit, if not the combatant whose turn it is.
+    source: Option<Target>,
+}
+
+/// The parameters of `apply_condition`.
+#[derive(Clone, Debug, Deserialize)]
+struct ConditionParams {
+    /// The combatant to apply the condition to.
+    target: Target,
+
+    /// The condition to apply.
+    condition: ConditionKind,
+
+    /// How long the condition lasts. Defaults to forever.
+    #[serde(default = "forever")]
+    duration: ConditionDuration,
+
+    /// The combatant to credit, if not the combatant whose turn it is.
+    source: Option<Target>,
+}
+
+fn forever() -> ConditionDuration {
+    ConditionDuration::Forever
+}
+
+/// The parameters of `use_resource`.
+#[derive(Clone, Debug, Deserialize)]
+struct ResourceParams {
+    /// The resource to use.
+    resource: Resource,
+
+    /// The combatant using it, if not the combatant whose turn it is.
+    combatant: Option<Target>,
+}
+
+/// A JSON-RPC request.
+#[derive(Clone, Debug, Deserialize)]
+struct Request {
+    /// The request's id. Requests without one are notifications, and get no response.
+    id: Option<Value>,
+
+    /// The method to call.
+    method: String,
+
+    /// The method's parameters.
+    #[serde(default)]
+    params: Value,
+}
+
+/// A JSON-RPC error.
+#[derive(Clone, Debug, Serialize)]
+struct RpcError {
+    /// The error code, as defined by JSON-RPC 2.0.
+    code: i32,
+
+    /// A description of the error.
+    message: String,
+}
+
+impl RpcError {
+    /// The request isn't valid JSON.
+    fn parse(err: serde_json::Error) -> Self {
+        Self { code: -32700, message: format!("parse error: {}", err) }
+    }
+
+    /// The request is JSON, but isn't a valid request.
+    fn invalid_request(err: serde_json::Error) -> Self {
+        Self { code: -32600, message: format!("invalid request: {}", err) }
+    }
+
+    /// The method doesn't exist.
+    fn method_not_found(method: &str) -> Self {
+        Self { code: -32601, message: format!("method not found: {}", method) }
+    }
+
+    /// The method's parameters are invalid.
+    fn invalid_params(message: impl std::fmt::Display) -> Self {
+        Self { code: -32602, message: format!("invalid params: {}", message) }
+    }
+}
+
+/// A JSON-RPC response.
+#[derive(Clone, Debug, Serialize)]
+struct Response {
+    /// The protocol version, always `2.0`.
+    jsonrpc: &'static str,
+
+    /// The id of the request this responds to.
+    id: Value,
+
+    /// The method's result, if it succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+
+    /// The error, if the method failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+/// A combatant in the tracker's state.
+#[derive(Clone, Debug, Serialize)]
+struct CombatantState<'a> {
+    /// The combatant's index in the order of play.
+    index: usize,
+
+    /// The combatant's name.
+    name: &'a str,
+
+    /// The side the combatant fights on.
+    side: &'a Side,
+
+    /// The combatant's initiative.
+    initiative: i32,
+
+    /// The combatant's current hit points.
+    hit_points: i32,
+
+    /// The combatant's hit point maximum.
+    max_hit_points: i32,
+
+    /// The combatant's conditions.
+    conditions: &'a [Condition],
+
+    /// The combatant's remaining actions, bonus actions, and reactions.
+    resources: Resources,
+
+    /// Whether the combatant is delaying their turn.
+    delayed: bool,
+
+    /// The combatant's statistics for the fight.
+    stats: &'a CombatStats,
+}
+
+/// The tracker's state, as returned by every method.
+#[derive(Clone, Debug, Serialize)]
+struct State<'a> {
+    /// The current round, starting at 0.
+    round: usize,
+
+    /// The index of the combatant taking their turn.
+    turn: usize,
+
+    /// Whether combat is over.
+    over: bool,
+
+    /// The combatants, in order of play.
+    combatants: Vec<CombatantState<'a>>,
+}
+
+impl<'a> State<'a> {
+    /// Capture the tracker's state.
+    fn new(tracker: &'a Tracker) -> Self {
+        Self {
+            round: tracker.round,
+            turn: tracker.turn,
+            over: tracker.is_over(),
+            combatants: tracker.combatants
+                .iter()
+                .enumerate()
+                .map(|(index, combatant)| CombatantState {
+                    index,
+                    name: combatant.name(),
+                    side: &combatant.side,
+                    initiative: combatant.initiative,
+                    hit_points: combatant.hit_points,
+                    max_hit_points: combatant.max_hit_points,
+                    conditions: &combatant.conditions,
+                    resources: combatant.resources,
+                    delayed: combatant.delayed,
+                    stats: &combatant.stats,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Parse a method's parameters.
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(RpcError::invalid_params)
+}
+
+/// Returns the index of the given combatant.
+fn resolve(tracker: &Tracker, target: &Target) -> Result<usize, RpcError> {
+    match target {
+        Target::Index(index) if *index < tracker.combatants.len() => Ok(*index),
+        Target::Index(index) => Err(RpcError::invalid_params(format!("no combatant {}", index))),
+        Target::Name(name) => tracker.combatants
+            .iter()
+            .position(|combatant| combatant.name().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| RpcError::invalid_params(format!("no combatant named `{}`", name))),
+    }
+}
+
+/// Returns the index of the given source, or the combatant whose turn it is if there is none.
+fn resolve_source(tracker: &Tracker, source: Option<&Target>) -> Result<usize, RpcError> {
+    source.map_or(Ok(tracker.turn), |source| resolve(tracker, source))
+}
+
+/// Call a method on the tracker, and return the tracker's state afterwards.
+fn call(tracker: &mut Tracker, method: &str, value: Value) -> Result<Value, RpcError> {
+    match method {
+        "state" => (),
+        "next_turn" => tracker.next_turn(),
+        "damage" | "heal" => {
+            let DamageParams { target, amount, source } = params(value)?;
+            let target = resolve(tracker, &target)?;
+            let source = resolve_source(tracker, source.as_ref())?;
+            if amount < 0 {
+                return Err(RpcError::invalid_params("amount can't be negative"));
+            }
+            let amount = if method == "heal" {
+                amount.checked_neg().ok_or_else(|| RpcError::invalid_params("amount is too large"))?
+            } else {
+                amount
+            };
+            tracker.deal_damage_from(Some(source), target, amount);
+        },
+        "apply_condition" => {
+            let ConditionParams { target, condition, duration, source } = params(value)?;
+            let target = resolve(tracker, &target)?;
+            let source = resolve_source(tracker, source.as_ref())?;
+            tracker.inflict_condition_from(Some(source), target, Condition {
+                kind: condition,
+                duration,
+            });
+        },
+        "use_resource" => {
+            let ResourceParams { resource, combatant } = params(value)?;
+            let combatant = resolve_source(tracker, combatant.as_ref())?;
+            let resources = &mut tracker.combatants[combatant].resources;
+            let count = match resource {
+                Resource::Action => &mut resources.actions,
+                Resource::BonusAction => &mut resources.bonus_actions,
+                Resource::Reaction => &mut resources.reactions,
+            };
+            if *count == 0 {
+                return Err(RpcError::invalid_params("no uses of that resource left"));
+            }
+            *count -= 1;
+        },
+        _ => return Err(RpcError::method_not_found(method)),
+    }
+    Ok(serde_json::to_value(State::new(tracker)).unwrap_or_default())
+}
+
+/// Handle one line of a connection, and return the response line to send back, if any.
+fn handle(tracker: &mut Tracker, line: &str) -> Option<String> {
+    let (id, result) = match serde_json::from_str::<Value>(line) {
+        Err(err) => (Some(Value::Null), Err(RpcError::parse(err))),
+        Ok(value) => {
+            let id = value.get("id").cloned();
+            match serde_json::from_value::<Request>(value) {
+                Err(err) => (Some(id.unwrap_or_default()), Err(RpcError::invalid_request(err))),
+                Ok(request) => (request.id, call(tracker, &request.method, request.params)),
+            }
+        },
+    };
+
+    // notifications get no response
+    let id = id?;
+    let (result, error) = match result {
+        Ok(result) => (Some(result), None),
+        Err(error) => (None, Some(error)),
+    };
+    serde_json::to_string(&Response { jsonrpc: "2.0", id, result, error }).ok()
+}
+
+/// A request line from a connection, with where to send the response.
+type Incoming = (String, Sender<Option<String>>);
+
+/// Serves the JSON-RPC API to the connections made to it.
+///
+/// Connections are read on their own threads, but requests are only handled when
+/// [`Control::serve`] is called, so they apply to the tracker between key presses.
+#[derive(Debug)]
+pub struct Control {
+    /// The requests waiting to be handled.
+    requests: Receiver<Incoming>,
+}
+
+impl Control {
+    /// Start listening on the given address, such as `127.0.0.1:7879`.
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let (sender, requests) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sender = sender.clone();
+                std::thread::spawn(move || connection(stream, sender));
+            }
+        });
+        Ok(Self { requests })
+    }
+
+    /// Handle every waiting request. Returns `true` if any request was handled.
+    pub fn serve(&mut self, tracker: &mut Tracker) -> bool {
+        let mut handled = false;
+        while let Ok((line, reply)) = self.requests.try_recv() {
+            // the connection may have closed while waiting, which is fine
+            let _ = reply.send(handle(tracker, &line));
+            handled = true;
+        }
+        handled
+    }
+}
+
+/// Read requests from a connection until it closes, passing each to the tracker and writing back
+/// its response.
+fn connection(stream: TcpStream, requests: Sender<Incoming>) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (reply, response) = mpsc::channel();
+        if requests.send((line, reply)).is_err() {
+            return;
+        }
+        match response.recv() {
+            Ok(Some(response)) => if writeln!(writer, "{}", response).is_err() {
+                return;
+            },
+            Ok(None) => (),
+            Err(_) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use h5t_core::{Combatant, Monster, Player};
+
+    /// Ensure requests change the tracker, and bad requests get JSON-RPC errors.
+    #[test]
+    fn requests() {
+        let mut tracker = Tracker::new(vec![
+            Combatant::from(Player { name: "Kira".to_string(), max_hit_points: 20, ..Default::default() }),
+            Combatant::from(Monster { name: "Goblin".to_string(), hit_points: 7, ..Default::default() }),
+        ]);
+        let mut request = |line: &str| -> Value {
+            serde_json::from_str(&handle(&mut tracker, line).unwrap()).unwrap()
+        };
+
+        let response = request(r#"{"jsonrpc": "2.0", "id": 1, "method": "damage", "params": {"target": "goblin", "amount": 5}}"#);
+        assert_eq!(response["result"]["combatants"][1]["hit_points"], 2);
+        assert_eq!(response["result"]["combatants"][0]["stats"]["damage_dealt"], 5);
+
+        let response = request(r#"{"jsonrpc": "2.0", "id": 2, "method": "apply_condition", "params": {"target": 1, "condition": "prone", "duration": {"rounds": 1}}}"#);
+        assert_eq!(response["result"]["combatants"][1]["conditions"][0]["kind"], "prone");
+
+        let response = request(r#"{"jsonrpc": "2.0", "id": 3, "method": "use_resource", "params": {"resource": "reaction"}}"#);
+        assert_eq!(response["result"]["combatants"][0]["resources"]["reactions"], 0);
+        let response = request(r#"{"jsonrpc": "2.0", "id": 4, "method": "use_resource", "params": {"resource": "reaction"}}"#);
+        assert_eq!(response["error"]["code"], -32602);
+
+        // amounts out of range are rejected, and the largest allowed doesn't overflow
+        let heal = r#"{"jsonrpc": "2.0", "id": 5, "method": "heal", "params": {"target": 0, "amount": 2147483648}}"#;
+        assert_eq!(request(heal)["error"]["code"], -32602);
+        let damage = r#"{"jsonrpc": "2.0", "id": 5, "method": "damage", "params": {"target": 0, "amount": -5}}"#;
+        assert_eq!(request(damage)["error"]["code"], -32602);
+        let damage = r#"{"jsonrpc": "2.0", "id": 5, "method": "damage", "params": {"target": 0, "amount": 2147483647}}"#;
+        request(damage);
+        assert_eq!(request(damage)["result"]["combatants"][0]["hit_points"], i32::MIN);
+
+        let response = request(r#"{"jsonrpc": "2.0", "id": 5, "method": "next_turn"}"#);
+        assert_eq!(response["result"]["turn"], 1);
+        assert_eq!(request(r#"{"jsonrpc": "2.0", "id": 6, "method": "fly"}"#)["error"]["code"], -32601);
+        assert_eq!(request("{")["error"]["code"], -32700);
+        assert!(handle(&mut tracker, r#"{"jsonrpc": "2.0", "method": "next_turn"}"#).is_none());
+    }
+}
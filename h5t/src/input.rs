@@ -19,6 +19,22 @@ pub enum AfterKey<T> {
     Forward(KeyEvent),
 }
 
+/// A number entered by the user, or nothing to have it rolled instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OrRoll(pub Option<i32>);
+
+impl FromStr for OrRoll {
+    type Err = std::num::ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            Ok(Self(None))
+        } else {
+            s.parse().map(|value| Self(Some(value)))
+        }
+    }
+}
+
 /// The allowed character sets for the input field. The chosen character set only restricts the
 /// characters that can be typed into the input field and does not affect input validation.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
@@ -131,18 +147,36 @@ impl<T: FromStr> GetInput<T> {
         self
     }
 
+    /// Set the initial value of the input field, so the user can edit it instead of typing it out.
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = value.into();
+        self
+    }
+
     /// Given a mutable refernece to the input, set the suffix to display after the input value.
     pub fn set_suffix(&mut self, suffix: impl Into<String>) -> &mut Self {
         self.suffix = Some(suffix.into());
         self
     }
 
+    /// Given a mutable reference to the input, set the prompt to display as the title of the input
+    /// box.
+    pub fn set_prompt(&mut self, prompt: impl Into<String>) -> &mut Self {
+        self.prompt = prompt.into();
+        self
+    }
+
     /// Given a mutable reference to the input, set the active state of the widget.
     pub fn set_active(&mut self, active: bool) -> &mut Self {
         self.active = active;
         self
     }
 
+    /// Returns the text currently in the input field, whether or not it is valid.
+    pub fn text(&self) -> &str {
+        &self.value
+    }
+
     /// Get the color of the input field based on the validity of the input.
     pub fn color(&self) -> Rgb {
         if self.value.len() >= self.max_length {
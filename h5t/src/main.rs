@@ -1,26 +1,75 @@
+mod cli;
+mod control;
 mod input;
 mod selectable;
+mod share;
 mod state;
 mod theme;
 mod ui;
 mod widgets;
 
-use h5t_core::{CombatantKind, Monster, Tracker};
+use clap::Parser;
+use cli::{fail, Args, Command};
+use h5t_core::{CombatantKind, Tracker};
 use ui::Ui;
 
 fn main() {
-    // NOTE: monster JSON data provided courtesy of https://www.dnd5eapi.co/
-    let file = std::fs::File::open("data/monsters.json").unwrap();
-    let monsters = serde_json::from_reader::<_, Vec<Monster>>(file).unwrap();
-    // println!("{:#?}", monsters);
+    let args = Args::parse();
+    theme::set_theme(theme::Theme::by_name(&args.theme).unwrap_or_default());
 
-    let mut tracker = Ui::new(
-        ratatui::init(),
-        Tracker::new(monsters
-            .into_iter()
-            .map(|m| CombatantKind::Monster(m).into())
+    if let Some(Command::Roll { expr }) = &args.command {
+        return cli::roll(expr);
+    }
+    if let Some(Command::Convert { input, output }) = &args.command {
+        return cli::convert(input, output.as_deref());
+    }
+    if let Some(Command::Players { address }) = &args.command {
+        return share::watch(address.as_str())
+            .unwrap_or_else(|err| fail(format!("{}: {}", address, err)));
+    }
+
+    let bestiary = args.bestiary();
+    let tracker = match &args.command {
+        Some(Command::Roll { .. } | Command::Convert { .. } | Command::Players { .. }) => {
+            unreachable!("handled before loading the bestiary")
+        },
+        Some(Command::Monster { name }) => return cli::monster(&name.join(" "), &bestiary),
+        Some(Command::Difficulty { encounter, method }) => {
+            return cli::difficulty(encounter, *method, &bestiary);
+        },
+        Some(Command::Validate { paths }) => {
+            if !cli::validate(paths, &bestiary) {
+                std::process::exit(1);
+            }
+            return;
+        },
+        Some(Command::Report { save, output }) => {
+            return cli::report(save, output.as_deref(), &bestiary);
+        },
+        Some(Command::Resume { save }) => cli::resume(save, &bestiary),
+        Some(Command::Run { encounter: Some(path) }) => cli::build_encounter(path, &bestiary),
+        Some(Command::Run { encounter: None }) | None => Tracker::new(bestiary
+            .iter()
+            .map(|m| CombatantKind::Monster(m.clone()).into())
             .collect::<Vec<_>>()),
-    );
+    };
+    if tracker.combatants.is_empty() {
+        fail("there are no combatants to track");
+    }
+
+    // bind before taking over the terminal, so errors are printed normally
+    let share = args.share.as_ref().map(|address| share::Share::bind(address.as_str())
+        .unwrap_or_else(|err| fail(format!("{}: {}", address, err))));
+    let control = args.control.as_ref().map(|address| control::Control::bind(address.as_str())
+        .unwrap_or_else(|err| fail(format!("{}: {}", address, err))));
+
+    let mut tracker = Ui::new(ratatui::init(), tracker, bestiary);
+    if let Some(share) = share {
+        tracker = tracker.share(share);
+    }
+    if let Some(control) = control {
+        tracker = tracker.control(control);
+    }
 
     tracker.run();
 }
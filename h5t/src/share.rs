@@ -0,0 +1,146 @@
+//! Sharing the player view with other terminals over a local TCP connection.
+//!
+//! The DM's tracker listens on an address given with `--share`, and sends the [`PlayerView`] to
+//! every connected terminal as a line of JSON whenever it changes. `h5t players` connects to that
+//! address and shows the player view, for a screen facing the players.
+
+use crate::{theme::THEME, widgets::PlayerView as PlayerViewWidget};
+use crossterm::event::{poll, read, Event, KeyCode};
+use h5t_core::PlayerView;
+use ratatui::{prelude::*, widgets::{canvas::Canvas, Paragraph}};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::mpsc,
+    time::Duration,
+};
+
+/// How often to check for new terminals and changes to share while waiting for a key.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Shares the player view with the terminals connected to it.
+#[derive(Debug)]
+pub struct Share {
+    /// The listener accepting new terminals.
+    listener: TcpListener,
+
+    /// The connected terminals.
+    clients: Vec<TcpStream>,
+
+    /// The last view sent, as a line of JSON, to only send changes.
+    last: String,
+}
+
+impl Share {
+    /// Start sharing on the given address, such as `127.0.0.1:7878`.
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            clients: Vec::new(),
+            last: String::new(),
+        })
+    }
+
+    /// Accept any new terminals, and send them the view. The view is sent to everyone else only if
+    /// it changed. Terminals that can't be written to are dropped.
+    pub fn update(&mut self, view: &PlayerView) {
+        let Ok(line) = serde_json::to_string(view) else {
+            return;
+        };
+        let changed = line != self.last;
+        if changed {
+            self.clients.retain_mut(|client| writeln!(client, "{}", line).is_ok());
+            self.last = line;
+        }
+
+        while let Ok((mut client, _)) = self.listener.accept() {
+            // writes should block, so a full line is always sent, but not forever if the terminal
+            // stops reading
+            let ready = client.set_nonblocking(false).is_ok()
+                && client.set_write_timeout(Some(Duration::from_secs(1))).is_ok();
+            if ready && writeln!(client, "{}", self.last).is_ok() {
+                self.clients.push(client);
+            }
+        }
+    }
+}
+
+/// Connect to a tracker sharing its player view, and show it until `q` or Esc is pressed.
+pub fn watch(addr: impl ToSocketAddrs) -> io::Result<()> {
+    let stream = TcpStream::connect(addr)?;
+
+    // read views on another thread, so keys are still handled while waiting
+    let (sender, views) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if let Ok(view) = serde_json::from_str::<PlayerView>(&line) {
+                if sender.send(view).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    let mut terminal = ratatui::init();
+    let mut view = None;
+    let mut connected = true;
+    let result = loop {
+        loop {
+            match views.try_recv() {
+                Ok(new_view) => view = Some(new_view),
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    connected = false;
+                    break;
+                },
+            }
+        }
+
+        let drawn = terminal.draw(|frame| {
+            frame.render_widget(
+                Canvas::default()
+                    .background_color(THEME.background.into())
+                    .paint(|_| ()),
+                frame.area(),
+            );
+            let [view_area, status_area] = Layout::vertical([
+                Constraint::Fill(1),
+                Constraint::Length(u16::from(!connected)),
+            ])
+                .areas(frame.area());
+            match &view {
+                Some(view) => frame.render_widget(PlayerViewWidget::new(view), view_area),
+                None => frame.render_widget(
+                    Paragraph::new("Waiting for the tracker...").fg(THEME.foreground),
+                    view_area,
+                ),
+            }
+            frame.render_widget(
+                Paragraph::new("The tracker has closed").fg(THEME.warning),
+                status_area,
+            );
+        });
+        if let Err(err) = drawn {
+            break Err(err);
+        }
+
+        match poll(POLL_INTERVAL) {
+            Ok(true) => match read() {
+                Ok(Event::Key(key)) if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) => {
+                    break Ok(());
+                },
+                Ok(_) => (),
+                Err(err) => break Err(err),
+            },
+            Ok(false) => (),
+            Err(err) => break Err(err),
+        }
+    };
+    ratatui::restore();
+    result
+}
@@ -0,0 +1,271 @@
+use canvas::Canvas;
+use crate::{
+    input::{AfterKey as AfterKeyInner, Charset, GetInput, OrRoll},
+    theme::THEME,
+    widgets::popup::popup_area,
+    Tracker,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use h5t_core::{Bestiary, Combatant, CombatantKind, Player};
+use ratatui::{layout::Flex, prelude::*, widgets::*};
+use std::rc::Rc;
+use super::AfterKey;
+
+/// The maximum number of search results to show.
+const MAX_MATCHES: usize = 8;
+
+/// Helper enum to indicate which form field is currently selected.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum Field {
+    #[default]
+    Search,
+    Initiative,
+    ArmorClass,
+    HitPoints,
+}
+
+/// Something the user can add from the search results.
+#[derive(Clone, Debug, PartialEq)]
+enum Choice {
+    /// A monster in the bestiary, by index.
+    Monster(String),
+
+    /// A new player character with the name typed in the search box.
+    Player,
+}
+
+/// State for adding a combatant mid-combat, such as reinforcements, a summon, or a late-arriving
+/// player character.
+///
+/// The user searches the bestiary by name (or picks the last option to add a player with the name
+/// they typed), then enters the combatant's initiative. Leaving the initiative empty rolls it.
+#[derive(Clone, Debug)]
+pub struct AddCombatant {
+    /// The monsters that can be added.
+    bestiary: Rc<Bestiary>,
+
+    /// Indicates which form field is currently selected.
+    selected: Field,
+
+    /// Helper to get the name to search for.
+    search: GetInput<String>,
+
+    /// The search results for the current name.
+    matches: Vec<Choice>,
+
+    /// The index of the highlighted search result.
+    highlighted: usize,
+
+    /// Helper to get the combatant's initiative.
+    initiative: GetInput<OrRoll>,
+
+    /// Helper to get a new player's armor class.
+    armor_class: GetInput<u32>,
+
+    /// Helper to get a new player's hit point maximum.
+    hit_points: GetInput<i32>,
+
+    /// The chosen combatant, once the search is submitted.
+    combatant: Option<Combatant>,
+}
+
+impl AddCombatant {
+    /// Create an [`AddCombatant`] state that searches the given bestiary.
+    pub fn new(bestiary: Rc<Bestiary>) -> Self {
+        let mut initiative = GetInput::new("Initiative", 4, Charset::Numeric)
+            .suffix("empty to roll");
+        initiative.set_active(false);
+        Self {
+            bestiary,
+            selected: Field::default(),
+            search: GetInput::new("Add combatant", 30, Charset::All),
+            matches: Vec::new(),
+            highlighted: 0,
+            initiative,
+            armor_class: GetInput::new("Armor class", 4, Charset::Numeric),
+            hit_points: GetInput::new("Hit point maximum", 4, Charset::Numeric)
+                .suffix("HP"),
+            combatant: None,
+        }
+    }
+
+    /// Update the search results to match the name in the search box.
+    fn update_matches(&mut self) {
+        let name = self.search.text().trim();
+        self.matches = if name.is_empty() {
+            Vec::new()
+        } else {
+            self.bestiary
+                .query()
+                .name(name)
+                .run()
+                .into_iter()
+                .take(MAX_MATCHES)
+                .map(|monster| Choice::Monster(monster.index.clone()))
+                .chain(std::iter::once(Choice::Player))
+                .collect()
+        };
+        self.highlighted = 0;
+    }
+
+    /// Returns the text to display for a search result.
+    fn choice_text(&self, choice: &Choice) -> String {
+        match choice {
+            Choice::Monster(index) => self.bestiary
+                .get(index)
+                .map(|monster| monster.name.clone())
+                .unwrap_or_default(),
+            Choice::Player => format!("New player \"{}\"", self.search.text().trim()),
+        }
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        let area = frame.area();
+        match self.selected {
+            Field::Initiative => return self.initiative.draw(frame, area),
+            Field::ArmorClass => return self.armor_class.draw(frame, area),
+            Field::HitPoints => return self.hit_points.draw(frame, area),
+            Field::Search if self.matches.is_empty() => return self.search.draw(frame, area),
+            Field::Search => (),
+        }
+
+        // show the search results above the search box
+        let [results, input_area] = Layout::vertical([
+            Constraint::Length(self.matches.len() as u16 + 2),
+            Constraint::Length(3),
+        ])
+            .flex(Flex::End)
+            .areas(area);
+        self.search.draw(frame, input_area);
+
+        let lines = self.matches
+            .iter()
+            .enumerate()
+            .map(|(i, choice)| {
+                let line = Line::raw(self.choice_text(choice));
+                if i == self.highlighted {
+                    line.bold().bg(THEME.select)
+                } else {
+                    line
+                }
+            })
+            .collect::<Vec<_>>();
+        let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 4;
+        let area = popup_area(results, Flex::Center, Flex::End, (width, results.height), 0);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Canvas::default()
+                .background_color(THEME.background.into())
+                .paint(|_| ()),
+            area,
+        );
+        frame.render_widget(
+            Paragraph::new(lines)
+                .fg(THEME.foreground)
+                .block(Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(THEME.foreground)
+                    .title("Matches")
+                    .padding(Padding::horizontal(1))),
+            area,
+        );
+    }
+
+    /// Handle a key event and apply any needed changes to the tracker.
+    pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        match self.selected {
+            Field::Search => match self.search.handle_key(key) {
+                AfterKeyInner::Handled => self.update_matches(),
+                AfterKeyInner::Submit(_) => {
+                    let Some(choice) = self.matches.get(self.highlighted) else {
+                        return AfterKey::Stay;
+                    };
+                    self.combatant = match choice {
+                        Choice::Monster(index) => self.bestiary
+                            .get(index)
+                            .map(|monster| monster.clone().into()),
+                        Choice::Player => Some(Player {
+                            name: self.search.text().trim().to_string(),
+                            ..Default::default()
+                        }.into()),
+                    };
+                    self.select(Field::Initiative);
+                },
+                AfterKeyInner::Cancel => return AfterKey::Exit,
+                AfterKeyInner::Forward(key) => match key.code {
+                    KeyCode::Up | KeyCode::BackTab => {
+                        self.highlighted = self.highlighted.saturating_sub(1);
+                    },
+                    KeyCode::Down | KeyCode::Tab => {
+                        self.highlighted = (self.highlighted + 1)
+                            .min(self.matches.len().saturating_sub(1));
+                    },
+                    _ => (),
+                },
+            },
+            Field::Initiative => match self.initiative.handle_key(key) {
+                AfterKeyInner::Submit(OrRoll(initiative)) => {
+                    let combatant = self.combatant.as_mut().unwrap();
+                    match initiative {
+                        Some(initiative) => combatant.initiative = initiative,
+                        None => {
+                            combatant.roll_initiative(&mut rand::thread_rng());
+                        },
+                    }
+                    if matches!(self.matches.get(self.highlighted), Some(Choice::Player)) {
+                        self.select(Field::ArmorClass);
+                    } else {
+                        return self.add(tracker);
+                    }
+                },
+                AfterKeyInner::Cancel => self.select(Field::Search),
+                _ => (),
+            },
+            Field::ArmorClass => match self.armor_class.handle_key(key) {
+                AfterKeyInner::Submit(armor_class) => {
+                    let combatant = self.combatant.as_mut().unwrap();
+                    if let CombatantKind::Player(player) = &mut combatant.kind {
+                        player.armor_class = armor_class;
+                    }
+                    self.select(Field::HitPoints);
+                },
+                AfterKeyInner::Cancel => self.select(Field::Initiative),
+                _ => (),
+            },
+            Field::HitPoints => match self.hit_points.handle_key(key) {
+                AfterKeyInner::Submit(hit_points) => {
+                    let combatant = self.combatant.as_mut().unwrap();
+                    if let CombatantKind::Player(player) = &mut combatant.kind {
+                        player.max_hit_points = hit_points;
+                    }
+                    combatant.hit_points = hit_points;
+                    combatant.max_hit_points = hit_points;
+                    return self.add(tracker);
+                },
+                AfterKeyInner::Cancel => self.select(Field::ArmorClass),
+                _ => (),
+            },
+        }
+
+        AfterKey::Stay
+    }
+
+    /// Move to the given form field.
+    fn select(&mut self, field: Field) {
+        self.selected = field;
+        self.search.set_active(field == Field::Search);
+        self.initiative.set_active(field == Field::Initiative);
+        self.armor_class.set_active(field == Field::ArmorClass);
+        self.hit_points.set_active(field == Field::HitPoints);
+    }
+
+    /// Add the chosen combatant to the tracker in initiative order.
+    fn add(&mut self, tracker: &mut Tracker) -> AfterKey {
+        if let Some(combatant) = self.combatant.take() {
+            tracker.add(combatant);
+        }
+        AfterKey::Exit
+    }
+}
@@ -54,6 +54,9 @@ impl std::fmt::Display for Unit {
 }
 
 /// State for applying conditions to combatants.
+///
+/// If a single mob is chosen, the user is then asked which of its members the conditions apply to,
+/// or the whole mob.
 #[derive(Clone, Debug)]
 pub struct ApplyCondition {
     /// The combatant indices to apply damage to.
@@ -70,12 +73,24 @@ pub struct ApplyCondition {
 
     /// Duration of the conditions.
     unit: Unit,
+
+    /// Helper to get the number of the mob member to apply the conditions to, if a single mob was
+    /// chosen.
+    member: Option<GetInput<usize>>,
+
+    /// The duration amount entered, once the user is picking the mob member.
+    amount: Option<u32>,
 }
 
 impl ApplyCondition {
     /// Create an [`ApplyCondition`] state with the initial state.
-    pub fn new(combatants: Vec<usize>) -> Self {
+    pub fn new(combatants: Vec<usize>, tracker: &Tracker) -> Self {
+        let single_mob = combatants.len() == 1 && tracker.combatants[combatants[0]].mob.is_some();
         Self {
+            member: single_mob.then(|| {
+                GetInput::new("Mob member (0 for the whole mob)", 3, Charset::Numeric).value("0")
+            }),
+            amount: None,
             combatants,
             conditions: HashSet::new(),
             selected: Field::default(),
@@ -87,6 +102,11 @@ impl ApplyCondition {
 
     /// Draw the state to the given [`Frame`].
     pub fn draw(&self, frame: &mut Frame) {
+        if let (Some(member), Some(_)) = (&self.member, self.amount) {
+            member.draw(frame, frame.area());
+            return;
+        }
+
         let area = frame.area();
         let area = popup_area(area, Flex::Center, Flex::End, (area.width, area.height / 2), 0);
         let [conditions, duration] = Layout::horizontal([
@@ -125,6 +145,17 @@ impl ApplyCondition {
 
     /// Handle a key event and apply any needed changes to the tracker.
     pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        if let (Some(member), Some(amount)) = (&mut self.member, self.amount) {
+            return match member.handle_key(key) {
+                AfterKeyInput::Submit(member) => {
+                    self.apply(tracker, amount, member.checked_sub(1));
+                    AfterKey::Exit
+                },
+                AfterKeyInput::Cancel => AfterKey::Exit,
+                _ => AfterKey::Stay,
+            };
+        }
+
         // generate labels for all conditions
         if self.selected == Field::Conditions {
             let label_to_option = LABELS
@@ -164,10 +195,7 @@ impl ApplyCondition {
                         self.selected = Field::Conditions;
                         return AfterKey::Stay;
                     },
-                    KeyCode::Enter => {
-                        self.apply(tracker, 0);
-                        return AfterKey::Exit;
-                    },
+                    KeyCode::Enter => return self.submit(tracker, 0),
                     KeyCode::Char(label) => {
                         let selected = &mut self.unit;
                         if let Some(option) = label_to_option.get(&label) {
@@ -180,10 +208,7 @@ impl ApplyCondition {
             } else {
                 match self.input.handle_key(key) {
                     AfterKeyInput::Handled => return AfterKey::Stay,
-                    AfterKeyInput::Submit(amount) => {
-                        self.apply(tracker, amount);
-                        return AfterKey::Exit;
-                    },
+                    AfterKeyInput::Submit(amount) => return self.submit(tracker, amount),
                     AfterKeyInput::Cancel => {
                         self.selected = Field::Conditions;
                         self.input.set_active(false);
@@ -207,8 +232,19 @@ impl ApplyCondition {
         AfterKey::Stay
     }
 
-    /// Apply the conditions to the tracker.
-    fn apply(&self, tracker: &mut h5t_core::Tracker, amount: u32) {
+    /// Apply the conditions once their duration is entered, or first ask which mob member they
+    /// apply to.
+    fn submit(&mut self, tracker: &mut Tracker, amount: u32) -> AfterKey {
+        if self.member.is_some() {
+            self.amount = Some(amount);
+            return AfterKey::Stay;
+        }
+        self.apply(tracker, amount, None);
+        AfterKey::Exit
+    }
+
+    /// Apply the conditions to the tracker, or only to the given member of the chosen mob.
+    fn apply(&self, tracker: &mut h5t_core::Tracker, amount: u32, member: Option<usize>) {
         for condition in &self.conditions {
             let duration = match self.unit {
                 Unit::UntilNextTurn => ConditionDuration::UntilNextTurn,
@@ -218,26 +254,12 @@ impl ApplyCondition {
             };
 
             for combatant_idx in &self.combatants {
-                let combatant = &mut tracker.combatants[*combatant_idx];
-
-                // if the condition is already present, override its length if the new one is longer
-                // otherwise, add the condition
-                let existing_condition = combatant
-                    .conditions
-                    .iter_mut()
-                    .find(|c| c.kind == *condition);
-
-                if let Some(existing_condition) = existing_condition {
-                    // override its length if the new one is longer
-                    if duration > existing_condition.duration {
-                        existing_condition.duration = duration;
-                    }
-                } else {
-                    // add new condition
-                    combatant.conditions.push(Condition {
-                        kind: *condition,
-                        duration,
-                    });
+                let condition = Condition { kind: *condition, duration };
+                match member {
+                    Some(member) => {
+                        tracker.inflict_member_condition(*combatant_idx, member, condition);
+                    },
+                    None => tracker.inflict_condition(*combatant_idx, condition),
                 }
             }
         }
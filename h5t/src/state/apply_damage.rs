@@ -1,45 +1,125 @@
 use crate::{input::{AfterKey as AfterKeyInner, Charset, GetInput}, Tracker};
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::prelude::*;
 use super::AfterKey;
 
 /// State for applying damage to combatants.
+///
+/// The damage is credited to the combatant whose turn it is. Pressing Tab while entering the amount
+/// credits it to the next combatant instead, or to no one.
+///
+/// If any of the combatants is a mob, the user is then asked how many of its members take the
+/// damage.
 #[derive(Clone, Debug, Default)]
 pub struct ApplyDamage {
     /// The combatant indices to apply damage to.
     combatants: Vec<usize>,
 
+    /// Whether any of the combatants is a mob.
+    has_mob: bool,
+
     /// Helper to get input from the user.
     input: GetInput<i32>,
+
+    /// The damage amount entered, once the user is picking the number of mob members.
+    amount: Option<i32>,
+
+    /// Helper to get the number of mob members to damage.
+    members: GetInput<usize>,
+
+    /// The name of each combatant, to show who the damage is credited to.
+    names: Vec<String>,
+
+    /// The index of the combatant the damage is credited to, if any.
+    source: Option<usize>,
 }
 
 impl ApplyDamage {
     /// Create an [`ApplyDamage`] state with the given combatants.
-    pub fn new(combatants: Vec<usize>) -> Self {
-        Self {
+    pub fn new(combatants: Vec<usize>, tracker: &Tracker) -> Self {
+        let mut state = Self {
+            has_mob: combatants.iter().any(|&i| tracker.combatants[i].mob.is_some()),
             combatants,
             input: GetInput::new("Damage amount", 4, Charset::Numeric) // damage is usually 1-2 digits
                 .suffix("HP"),
-        }
+            amount: None,
+            members: GetInput::new("Mob members to damage", 3, Charset::Numeric)
+                .value("1"),
+            names: tracker.combatants
+                .iter()
+                .map(|combatant| combatant.name().to_string())
+                .collect(),
+            source: Some(tracker.turn),
+        };
+        state.update_prompt();
+        state
+    }
+
+    /// Credit the damage to the next combatant in order, or to no one after the last.
+    fn cycle_source(&mut self) {
+        self.source = match self.source {
+            Some(source) if source + 1 < self.names.len() => Some(source + 1),
+            Some(_) => None,
+            None => Some(0),
+        };
+        self.update_prompt();
+    }
+
+    /// Show who the damage is credited to in the prompt.
+    fn update_prompt(&mut self) {
+        let source = self.source.map_or("no one", |source| &self.names[source]);
+        self.input.set_prompt(format!("Damage amount (from {}, Tab to change)", source));
     }
 
     /// Draw the state to the given [`Frame`].
     pub fn draw(&self, frame: &mut Frame) {
-        self.input.draw(frame, frame.area());
+        if self.amount.is_some() {
+            self.members.draw(frame, frame.area());
+        } else {
+            self.input.draw(frame, frame.area());
+        }
+    }
+
+    /// Apply the damage to each combatant, and to the given number of members of each mob. Damage
+    /// to minions carries over to the rest of their group.
+    fn apply(&self, tracker: &mut Tracker, amount: i32, members: usize) {
+        for &combatant_idx in &self.combatants {
+            if tracker.combatants[combatant_idx].mob.is_some() && amount >= 0 {
+                tracker.damage_members_from(self.source, combatant_idx, amount, members);
+            } else {
+                tracker.deal_damage_from(self.source, combatant_idx, amount);
+            }
+        }
     }
 
     /// Handle a key event and apply any needed changes to the tracker.
     pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        if let Some(amount) = self.amount {
+            return match self.members.handle_key(key) {
+                AfterKeyInner::Submit(members) => {
+                    self.apply(tracker, amount, members);
+                    AfterKey::Exit
+                },
+                AfterKeyInner::Cancel => AfterKey::Exit,
+                _ => AfterKey::Stay,
+            };
+        }
+
         match self.input.handle_key(key) {
             AfterKeyInner::Handled => AfterKey::Stay,
+            AfterKeyInner::Submit(value) if self.has_mob && value > 0 => {
+                self.amount = Some(value);
+                AfterKey::Stay
+            },
             AfterKeyInner::Submit(value) => {
-                for combatant_idx in &self.combatants {
-                    let combatant = &mut tracker.combatants[*combatant_idx];
-                    combatant.damage(value);
-                }
+                self.apply(tracker, value, 1);
                 AfterKey::Exit
             },
             AfterKeyInner::Cancel => AfterKey::Exit,
+            AfterKeyInner::Forward(KeyEvent { code: KeyCode::Tab, .. }) => {
+                self.cycle_source();
+                AfterKey::Stay
+            },
             _ => AfterKey::Stay,
         }
     }
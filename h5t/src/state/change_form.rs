@@ -0,0 +1,210 @@
+use canvas::Canvas;
+use crate::{
+    input::{AfterKey as AfterKeyInner, Charset, GetInput},
+    theme::THEME,
+    widgets::popup::popup_area,
+    Tracker,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use h5t_core::{ActiveKind, Bestiary};
+use ratatui::{layout::Flex, prelude::*, widgets::*};
+use std::rc::Rc;
+use super::AfterKey;
+
+/// The maximum number of search results to show.
+const MAX_MATCHES: usize = 8;
+
+/// A form the user can pick.
+#[derive(Clone, Debug, PartialEq)]
+enum Choice {
+    /// Revert to the combatant's original form.
+    Revert,
+
+    /// A monster in the bestiary, by index.
+    Monster(String),
+}
+
+/// State for a combatant assuming an alternate form, such as a Wild Shape or a polymorph, or
+/// reverting to its original form.
+///
+/// With an empty search, the choices are the forms listed in the combatant's stat block. Typing
+/// searches the whole bestiary instead.
+#[derive(Clone, Debug)]
+pub struct ChangeForm {
+    /// The index of the combatant changing form.
+    combatant: usize,
+
+    /// The monsters that can be used as forms.
+    bestiary: Rc<Bestiary>,
+
+    /// The name of the combatant's original form, for the revert choice.
+    original_name: String,
+
+    /// The forms listed in the combatant's current stat block, by index.
+    listed_forms: Vec<String>,
+
+    /// Whether the combatant is currently transformed.
+    transformed: bool,
+
+    /// Helper to get the name to search for.
+    search: GetInput<String>,
+
+    /// The choices for the current search.
+    matches: Vec<Choice>,
+
+    /// The index of the highlighted choice.
+    highlighted: usize,
+}
+
+impl ChangeForm {
+    /// Create a [`ChangeForm`] state for the given combatant.
+    pub fn new(combatant: usize, bestiary: Rc<Bestiary>, tracker: &Tracker) -> Self {
+        let combatant_ref = &tracker.combatants[combatant];
+        let listed_forms = match combatant_ref.active_kind() {
+            ActiveKind::Monster(monster) => monster.forms
+                .iter()
+                .map(|form| form.index.clone())
+                .filter(|index| bestiary.get(index).is_some())
+                .collect(),
+            ActiveKind::Player(_) => Vec::new(),
+        };
+        let mut state = Self {
+            combatant,
+            original_name: combatant_ref.base_name().to_string(),
+            transformed: combatant_ref.form.is_some(),
+            bestiary,
+            listed_forms,
+            search: GetInput::new(format!("New form for {}", combatant_ref.name()), 30, Charset::All),
+            matches: Vec::new(),
+            highlighted: 0,
+        };
+        state.update_matches();
+        state
+    }
+
+    /// Update the choices to match the name in the search box.
+    fn update_matches(&mut self) {
+        let name = self.search.text().trim();
+        let revert = self.transformed.then_some(Choice::Revert);
+        self.matches = if name.is_empty() {
+            revert
+                .into_iter()
+                .chain(self.listed_forms.iter().cloned().map(Choice::Monster))
+                .collect()
+        } else {
+            self.bestiary
+                .query()
+                .name(name)
+                .run()
+                .into_iter()
+                .take(MAX_MATCHES)
+                .map(|monster| Choice::Monster(monster.index.clone()))
+                .chain(revert)
+                .collect()
+        };
+        self.highlighted = 0;
+    }
+
+    /// Returns the text to display for a choice.
+    fn choice_text(&self, choice: &Choice) -> String {
+        match choice {
+            Choice::Revert => format!("Revert to {}", self.original_name),
+            Choice::Monster(index) => self.bestiary
+                .get(index)
+                .map(|monster| monster.name.clone())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        let area = frame.area();
+        if self.matches.is_empty() {
+            return self.search.draw(frame, area);
+        }
+
+        // show the choices above the search box
+        let [results, input_area] = Layout::vertical([
+            Constraint::Length(self.matches.len() as u16 + 2),
+            Constraint::Length(3),
+        ])
+            .flex(Flex::End)
+            .areas(area);
+        self.search.draw(frame, input_area);
+
+        let lines = self.matches
+            .iter()
+            .enumerate()
+            .map(|(i, choice)| {
+                let line = Line::raw(self.choice_text(choice));
+                if i == self.highlighted {
+                    line.bold().bg(THEME.select)
+                } else {
+                    line
+                }
+            })
+            .collect::<Vec<_>>();
+        let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 4;
+        let area = popup_area(results, Flex::Center, Flex::End, (width, results.height), 0);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Canvas::default()
+                .background_color(THEME.background.into())
+                .paint(|_| ()),
+            area,
+        );
+        frame.render_widget(
+            Paragraph::new(lines)
+                .fg(THEME.foreground)
+                .block(Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(THEME.foreground)
+                    .title("Forms")
+                    .padding(Padding::horizontal(1))),
+            area,
+        );
+    }
+
+    /// Handle a key event and apply any needed changes to the tracker.
+    pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        match self.search.handle_key(key) {
+            AfterKeyInner::Handled => self.update_matches(),
+            AfterKeyInner::Submit(_) => {
+                let Some(choice) = self.matches.get(self.highlighted) else {
+                    return AfterKey::Stay;
+                };
+                let combatant = &mut tracker.combatants[self.combatant];
+                let name = combatant.name().to_string();
+                let message = match choice {
+                    Choice::Revert => {
+                        combatant.revert_form();
+                        format!("{} reverts to its original form", name)
+                    },
+                    Choice::Monster(index) => {
+                        let Some(monster) = self.bestiary.get(index) else {
+                            return AfterKey::Stay;
+                        };
+                        combatant.assume_form(monster.clone());
+                        format!("{} assumes the form of {}", name, monster.name)
+                    },
+                };
+                tracker.log(message);
+                return AfterKey::Exit;
+            },
+            AfterKeyInner::Cancel => return AfterKey::Exit,
+            AfterKeyInner::Forward(key) => match key.code {
+                KeyCode::Up | KeyCode::BackTab => {
+                    self.highlighted = self.highlighted.saturating_sub(1);
+                },
+                KeyCode::Down | KeyCode::Tab => {
+                    self.highlighted = (self.highlighted + 1)
+                        .min(self.matches.len().saturating_sub(1));
+                },
+                _ => (),
+            },
+        }
+
+        AfterKey::Stay
+    }
+}
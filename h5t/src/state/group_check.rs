@@ -0,0 +1,165 @@
+use canvas::Canvas;
+use crate::{
+    input::{AfterKey as AfterKeyInner, Charset, GetInput},
+    theme::THEME,
+    widgets::popup::popup_area,
+    Tracker,
+};
+use crossterm::event::KeyEvent;
+use h5t_core::{check::CheckRoll, Check};
+use ratatui::{layout::Flex, prelude::*, widgets::*};
+use super::AfterKey;
+
+/// Helper enum to indicate which form field is currently selected.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum Field {
+    #[default]
+    Check,
+    Dc,
+    Results,
+}
+
+/// State for rolling a saving throw or ability check for a group of combatants, such as "all six
+/// cultists make a WIS save" or a group Stealth check, and showing who passed.
+#[derive(Clone, Debug)]
+pub struct GroupCheck {
+    /// The combatant indices of the group.
+    combatants: Vec<usize>,
+
+    /// The names of the combatants in the group, for the results table.
+    names: Vec<String>,
+
+    /// Indicates which form field is currently selected.
+    selected: Field,
+
+    /// Helper to get the check to roll.
+    check_input: GetInput<Check>,
+
+    /// Helper to get the DC.
+    dc_input: GetInput<i32>,
+
+    /// The check to roll, once entered.
+    check: Option<Check>,
+
+    /// The DC, once entered.
+    dc: i32,
+
+    /// Each combatant's roll, once rolled.
+    rolls: Vec<CheckRoll>,
+}
+
+impl GroupCheck {
+    /// Create a [`GroupCheck`] state for the given combatants.
+    pub fn new(mut combatants: Vec<usize>, tracker: &Tracker) -> Self {
+        combatants.sort_unstable();
+        Self {
+            names: combatants
+                .iter()
+                .map(|&combatant| tracker.combatants[combatant].name().to_string())
+                .collect(),
+            combatants,
+            selected: Field::default(),
+            check_input: GetInput::new("Save or check", 20, Charset::All)
+                .suffix("e.g. WIS save, Stealth"),
+            dc_input: GetInput::new("DC", 3, Charset::Numeric),
+            check: None,
+            dc: 0,
+            rolls: Vec::new(),
+        }
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        let area = frame.area();
+        match self.selected {
+            Field::Check => return self.check_input.draw(frame, area),
+            Field::Dc => return self.dc_input.draw(frame, area),
+            Field::Results => (),
+        }
+
+        let rows = self.rolls.iter().zip(&self.names).map(|(roll, name)| {
+            let d20 = match roll.dropped {
+                Some(dropped) => format!("{} ({}, {})", roll.roll, dropped, roll.advantage),
+                None => roll.roll.to_string(),
+            };
+            let (result, color) = if roll.auto_fail {
+                ("Auto-fail", THEME.error)
+            } else if roll.passes(self.dc) {
+                ("Pass", THEME.foreground)
+            } else {
+                ("Fail", THEME.error)
+            };
+            Row::new([
+                Text::raw(name.clone()),
+                Text::raw(d20),
+                Text::raw(roll.total.to_string()),
+                Text::styled(result, color),
+            ])
+        });
+        let passed = self.rolls.iter().filter(|roll| roll.passes(self.dc)).count();
+        let title = format!(
+            "DC {} {}: {}/{} pass",
+            self.dc,
+            self.check.map(|check| check.to_string()).unwrap_or_default(),
+            passed,
+            self.rolls.len(),
+        );
+        let table = Table::new(rows, [
+            Constraint::Fill(2),
+            Constraint::Fill(2),
+            Constraint::Length(5),
+            Constraint::Length(9),
+        ])
+            .header(Row::new(["Combatant", "d20", "Total", "Result"]).bold())
+            .fg(THEME.foreground)
+            .block(Block::bordered()
+                .border_type(BorderType::Rounded)
+                .border_style(THEME.foreground)
+                .title(title)
+                .padding(Padding::horizontal(1)));
+
+        let size = (60.min(area.width), self.rolls.len() as u16 + 3);
+        let area = popup_area(area, Flex::Center, Flex::Center, size, 0);
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Canvas::default()
+                .background_color(THEME.background.into())
+                .paint(|_| ()),
+            area,
+        );
+        frame.render_widget(table, area);
+    }
+
+    /// Handle a key event and apply any needed changes to the tracker.
+    pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        match self.selected {
+            Field::Check => match self.check_input.handle_key(key) {
+                AfterKeyInner::Submit(check) => {
+                    self.check = Some(check);
+                    self.dc_input.set_prompt(format!("DC for {}", check));
+                    self.selected = Field::Dc;
+                },
+                AfterKeyInner::Cancel => return AfterKey::Exit,
+                _ => (),
+            },
+            Field::Dc => match self.dc_input.handle_key(key) {
+                AfterKeyInner::Submit(dc) => {
+                    let check = self.check.expect("check is entered before the DC");
+                    self.dc = dc;
+                    self.rolls = tracker.group_check(
+                        &self.combatants,
+                        check,
+                        dc,
+                        &mut rand::thread_rng(),
+                    );
+                    self.selected = Field::Results;
+                },
+                AfterKeyInner::Cancel => self.selected = Field::Check,
+                _ => (),
+            },
+            // any key closes the results
+            Field::Results => return AfterKey::Exit,
+        }
+        AfterKey::Stay
+    }
+}
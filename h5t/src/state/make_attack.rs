@@ -0,0 +1,246 @@
+use canvas::Canvas;
+use crate::{theme::THEME, widgets::popup::popup_area, Tracker};
+use crossterm::event::{KeyCode, KeyEvent};
+use h5t_core::{attack::AttackRoll, dice::Advantage, monster::Action, ActiveKind};
+use ratatui::{layout::Flex, prelude::*, widgets::*};
+use super::AfterKey;
+
+/// Render a popup with the given title, contents, and help text at the bottom.
+fn draw_popup(frame: &mut Frame, title: String, help: &str, table: Table, size: (u16, u16)) {
+    let area = popup_area(frame.area(), Flex::Center, Flex::Center, size, 0);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Canvas::default()
+            .background_color(THEME.background.into())
+            .paint(|_| ()),
+        area,
+    );
+    frame.render_widget(
+        table
+            .fg(THEME.foreground)
+            .block(Block::bordered()
+                .border_type(BorderType::Rounded)
+                .border_style(THEME.foreground)
+                .title(title)
+                .title_bottom(help)
+                .padding(Padding::horizontal(1))),
+        area,
+    );
+}
+
+/// State for rolling one of the current combatant's attacks against one or more targets.
+///
+/// The user picks an attack from the combatant's stat block, then reviews the rolls against each
+/// target. Any roll can be overruled before the damage is applied and the attack is logged.
+///
+/// A minion attacks together with the rest of its group, with one roll for the whole group.
+#[derive(Clone, Debug)]
+pub struct MakeAttack {
+    /// The index of the attacking combatant.
+    attacker: usize,
+
+    /// The combatant indices of the targets.
+    targets: Vec<usize>,
+
+    /// The names of the targets, for the review table.
+    names: Vec<String>,
+
+    /// The attacks the attacker can make.
+    attacks: Vec<Action>,
+
+    /// The index of the highlighted attack, or the highlighted roll when reviewing.
+    highlighted: usize,
+
+    /// The rolls against each target, once an attack is chosen.
+    rolls: Option<Vec<AttackRoll>>,
+
+    /// Whether the attacker is a mob, which can use the Dungeon Master's Guide's mob attack rules
+    /// instead of rolling.
+    is_mob: bool,
+
+    /// The number of standing minions attacking together, if the attacker is a minion.
+    minions: Option<usize>,
+}
+
+impl MakeAttack {
+    /// Create a [`MakeAttack`] state for the current combatant against the given targets.
+    ///
+    /// Returns [`None`] if the current combatant has no attacks in their stat block.
+    pub fn new(mut targets: Vec<usize>, tracker: &Tracker) -> Option<Self> {
+        let ActiveKind::Monster(monster) = tracker.current_combatant().active_kind() else {
+            return None;
+        };
+        let attacks = monster.actions
+            .iter()
+            .filter(|action| action.is_attack())
+            .cloned()
+            .collect::<Vec<_>>();
+        if attacks.is_empty() {
+            return None;
+        }
+
+        targets.sort_unstable();
+        Some(Self {
+            attacker: tracker.turn,
+            names: targets
+                .iter()
+                .map(|&target| tracker.combatants[target].name().to_string())
+                .collect(),
+            targets,
+            attacks,
+            highlighted: 0,
+            rolls: None,
+            is_mob: tracker.current_combatant().mob.is_some(),
+            minions: tracker.current_combatant().is_minion().then(|| tracker
+                .minion_group(tracker.turn)
+                .into_iter()
+                .filter(|&i| tracker.combatants[i].hit_points > 0)
+                .count()),
+        })
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        let Some(rolls) = &self.rolls else {
+            let rows = self.attacks.iter().enumerate().map(|(i, attack)| {
+                let damage = attack.damage
+                    .iter()
+                    .map(|damage| format!("{} {}", damage.dice, damage.damage_type))
+                    .collect::<Vec<_>>()
+                    .join(" + ");
+                let row = Row::new([
+                    attack.name.clone(),
+                    format!("{:+}", attack.attack_bonus.unwrap_or_default()),
+                    damage,
+                ]);
+                if i == self.highlighted { row.bold().bg(THEME.select) } else { row }
+            });
+            let table = Table::new(rows, [
+                Constraint::Fill(2),
+                Constraint::Length(4),
+                Constraint::Fill(3),
+            ]);
+            let size = (60.min(frame.area().width), self.attacks.len() as u16 + 2);
+            let help = if self.is_mob { "Enter: roll, m: mob attack" } else { "Enter: roll" };
+            return draw_popup(frame, "Choose attack".to_string(), help, table, size);
+        };
+
+        let rows = rolls.iter().enumerate().map(|(i, roll)| {
+            let d20 = match (roll.mob_hits, roll.advantage, roll.dropped) {
+                (Some(hits), _, _) => format!("{} hit{}", hits, if hits == 1 { "" } else { "s" }),
+                (None, Advantage::Normal, _) | (None, _, None) => roll.roll.to_string(),
+                (None, advantage, Some(dropped)) => {
+                    format!("{} ({}, {})", roll.roll, dropped, advantage)
+                },
+            };
+            let result = match (roll.hit, roll.critical) {
+                (false, _) => "Miss",
+                (true, false) => "Hit",
+                (true, true) => "Critical",
+            };
+            let row = Row::new([
+                self.names[i].clone(),
+                d20,
+                format!("{} vs {}", roll.total, roll.armor_class),
+                result.to_string(),
+                roll.total_damage().to_string(),
+            ]);
+            if i == self.highlighted { row.bold().bg(THEME.select) } else { row }
+        });
+        let table = Table::new(rows, [
+            Constraint::Fill(2),
+            Constraint::Fill(2),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(6),
+        ])
+            .header(Row::new(["Target", "d20", "vs AC", "Result", "Damage"]).bold());
+        let size = (80.min(frame.area().width), rolls.len() as u16 + 3);
+        let title = match self.minions {
+            Some(minions) => format!("{} ×{} minions", self.attacks[0].name, minions),
+            None => self.attacks[0].name.clone(),
+        };
+        draw_popup(
+            frame,
+            title,
+            "Space: hit/miss, c: crit, +/-: damage, Enter: apply",
+            table,
+            size,
+        );
+    }
+
+    /// Handle a key event and apply any needed changes to the tracker.
+    pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        let len = self.rolls.as_ref().map_or(self.attacks.len(), Vec::len);
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.highlighted = self.highlighted.saturating_sub(1);
+                return AfterKey::Stay;
+            },
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.highlighted = (self.highlighted + 1).min(len - 1);
+                return AfterKey::Stay;
+            },
+            _ => (),
+        }
+
+        let Some(rolls) = &mut self.rolls else {
+            match key.code {
+                KeyCode::Enter => {
+                    // keep the chosen attack first, so the review knows which one it is
+                    self.attacks.swap(0, self.highlighted);
+                    let mut rng = rand::thread_rng();
+                    let attack = &self.attacks[0];
+                    self.rolls = Some(if self.minions.is_some() {
+                        tracker.roll_minion_attack(self.attacker, attack, &self.targets, &mut rng)
+                    } else {
+                        self.targets
+                            .iter()
+                            .map(|&target| {
+                                tracker.roll_attack(self.attacker, attack, target, &mut rng)
+                            })
+                            .collect()
+                    });
+                    self.highlighted = 0;
+                },
+                KeyCode::Char('m') if self.is_mob => {
+                    self.attacks.swap(0, self.highlighted);
+                    self.rolls = Some(tracker.roll_mob_attack(
+                        self.attacker,
+                        &self.attacks[0],
+                        &self.targets,
+                        &mut rand::thread_rng(),
+                    ));
+                    self.highlighted = 0;
+                },
+                KeyCode::Esc => return AfterKey::Exit,
+                _ => (),
+            }
+            return AfterKey::Stay;
+        };
+
+        let roll = &mut rolls[self.highlighted];
+        match key.code {
+            KeyCode::Char(' ') => {
+                roll.hit = !roll.hit;
+                roll.critical &= roll.hit;
+            },
+            KeyCode::Char('c') => {
+                roll.critical = !roll.critical;
+                roll.hit |= roll.critical;
+            },
+            KeyCode::Char('+') => roll.adjustment += 1,
+            KeyCode::Char('-') => roll.adjustment -= 1,
+            KeyCode::Enter => {
+                tracker.apply_attacks(self.attacker, &self.attacks[0], rolls);
+                return AfterKey::Exit;
+            },
+            KeyCode::Esc => {
+                self.rolls = None;
+                self.highlighted = 0;
+            },
+            _ => (),
+        }
+        AfterKey::Stay
+    }
+}
@@ -1,8 +1,36 @@
+pub mod add_combatant;
 pub mod apply_condition;
 pub mod apply_damage;
+pub mod change_form;
+pub mod group_check;
+pub mod make_attack;
+pub mod move_combatant;
+pub mod ready_action;
+pub mod rename;
+pub mod reveal_conditions;
+pub mod save_encounter;
+pub mod save_for_damage;
+pub mod set_side;
+pub mod stats;
+pub mod summary;
+pub mod take_rest;
 
+pub use add_combatant::AddCombatant;
 pub use apply_condition::ApplyCondition;
 pub use apply_damage::ApplyDamage;
+pub use change_form::ChangeForm;
+pub use group_check::GroupCheck;
+pub use make_attack::MakeAttack;
+pub use move_combatant::MoveCombatant;
+pub use ready_action::ReadyAction;
+pub use rename::Rename;
+pub use reveal_conditions::RevealConditions;
+pub use save_encounter::SaveEncounter;
+pub use save_for_damage::SaveForDamage;
+pub use set_side::SetSide;
+pub use stats::Stats;
+pub use summary::Summary;
+pub use take_rest::TakeRest;
 use h5t_core::Tracker;
 
 /// What to do after handling a key event.
@@ -18,21 +46,79 @@ pub enum AfterKey {
 
 /// The current state the tracker is in. This encompasses states where an action is about to be
 /// taken.
+// only one state is ever active, so boxing the larger states isn't worth the indirection
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum State {
+    /// Adding a combatant mid-combat.
+    AddCombatant(AddCombatant),
+
     /// Applying a condition to one or more combatants.
     ApplyCondition(ApplyCondition),
 
     /// Applying damage to one or more combatants.
     ApplyDamage(ApplyDamage),
+
+    /// Changing a combatant's form, or reverting it.
+    ChangeForm(ChangeForm),
+
+    /// Rolling a saving throw or ability check for a group of combatants.
+    GroupCheck(GroupCheck),
+
+    /// Rolling the current combatant's attack against one or more targets.
+    MakeAttack(MakeAttack),
+
+    /// Moving a combatant up or down the order of play.
+    MoveCombatant(MoveCombatant),
+
+    /// Readying the current combatant's action.
+    ReadyAction(ReadyAction),
+
+    /// Renaming a combatant.
+    Rename(Rename),
+
+    /// Picking which conditions the players can see.
+    RevealConditions(RevealConditions),
+
+    /// Saving the combatants to an encounter file, or the session to a save file.
+    SaveEncounter(SaveEncounter),
+
+    /// Dealing damage that each target makes a saving throw against.
+    SaveForDamage(SaveForDamage),
+
+    /// Moving combatants to another side.
+    SetSide(SetSide),
+
+    /// Showing each combatant's statistics for the fight.
+    Stats(Stats),
+
+    /// Showing how the fight ended.
+    Summary(Summary),
+
+    /// Resting combatants and showing what the rest restored.
+    TakeRest(TakeRest),
 }
 
 impl State {
     /// Allow the state to draw itself.
     pub fn draw(&self, frame: &mut ratatui::Frame) {
         match self {
+            Self::AddCombatant(state) => state.draw(frame),
             Self::ApplyCondition(state) => state.draw(frame),
             Self::ApplyDamage(state) => state.draw(frame),
+            Self::ChangeForm(state) => state.draw(frame),
+            Self::GroupCheck(state) => state.draw(frame),
+            Self::MakeAttack(state) => state.draw(frame),
+            Self::MoveCombatant(state) => state.draw(frame),
+            Self::ReadyAction(state) => state.draw(frame),
+            Self::Rename(state) => state.draw(frame),
+            Self::RevealConditions(state) => state.draw(frame),
+            Self::SaveEncounter(state) => state.draw(frame),
+            Self::SaveForDamage(state) => state.draw(frame),
+            Self::SetSide(state) => state.draw(frame),
+            Self::Stats(state) => state.draw(frame),
+            Self::Summary(state) => state.draw(frame),
+            Self::TakeRest(state) => state.draw(frame),
         }
     }
 
@@ -43,8 +129,22 @@ impl State {
         tracker: &mut Tracker,
     ) -> AfterKey {
         match self {
+            Self::AddCombatant(state) => state.handle_key(key, tracker),
             Self::ApplyCondition(state) => state.handle_key(key, tracker),
             Self::ApplyDamage(state) => state.handle_key(key, tracker),
+            Self::ChangeForm(state) => state.handle_key(key, tracker),
+            Self::GroupCheck(state) => state.handle_key(key, tracker),
+            Self::MakeAttack(state) => state.handle_key(key, tracker),
+            Self::MoveCombatant(state) => state.handle_key(key, tracker),
+            Self::ReadyAction(state) => state.handle_key(key, tracker),
+            Self::Rename(state) => state.handle_key(key, tracker),
+            Self::RevealConditions(state) => state.handle_key(key, tracker),
+            Self::SaveEncounter(state) => state.handle_key(key, tracker),
+            Self::SaveForDamage(state) => state.handle_key(key, tracker),
+            Self::SetSide(state) => state.handle_key(key, tracker),
+            Self::Stats(state) => state.handle_key(key, tracker),
+            Self::Summary(state) => state.handle_key(key, tracker),
+            Self::TakeRest(state) => state.handle_key(key, tracker),
         }
     }
 }
@@ -0,0 +1,57 @@
+use crate::{theme::THEME, widgets::popup::popup_area, Tracker};
+use canvas::Canvas;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{layout::Flex, prelude::*, widgets::*};
+use super::AfterKey;
+
+/// State for moving a combatant up or down the order of play, one place at a time.
+#[derive(Clone, Debug, Default)]
+pub struct MoveCombatant {
+    /// The current index of the combatant being moved.
+    combatant: usize,
+}
+
+impl MoveCombatant {
+    /// Create a [`MoveCombatant`] state for the given combatant.
+    pub fn new(combatant: usize) -> Self {
+        Self { combatant }
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        let text = "j/k to move, Enter when done";
+        let area = popup_area(frame.area(), Flex::Center, Flex::End, (text.len() as u16 + 4, 3), 0);
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Canvas::default()
+                .background_color(THEME.background.into())
+                .paint(|_| ()),
+            area,
+        );
+        frame.render_widget(
+            Paragraph::new(text)
+                .fg(THEME.foreground)
+                .block(Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(THEME.foreground)
+                    .title("Move combatant")
+                    .padding(Padding::horizontal(1))),
+            area,
+        );
+    }
+
+    /// Handle a key event and apply any needed changes to the tracker.
+    pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        let to = match key.code {
+            KeyCode::Char('k') | KeyCode::Up if self.combatant > 0 => self.combatant - 1,
+            KeyCode::Char('j') | KeyCode::Down if self.combatant + 1 < tracker.combatants.len() => {
+                self.combatant + 1
+            },
+            KeyCode::Enter | KeyCode::Esc => return AfterKey::Exit,
+            _ => return AfterKey::Stay,
+        };
+        tracker.move_combatant(self.combatant, to);
+        self.combatant = to;
+        AfterKey::Stay
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{input::{AfterKey as AfterKeyInner, Charset, GetInput}, Tracker};
+use crossterm::event::KeyEvent;
+use ratatui::prelude::*;
+use super::AfterKey;
+
+/// State for readying the current combatant's action, recording what triggers it.
+#[derive(Clone, Debug, Default)]
+pub struct ReadyAction {
+    /// Helper to get the trigger from the user.
+    input: GetInput<String>,
+}
+
+impl ReadyAction {
+    /// Create a [`ReadyAction`] state.
+    pub fn new() -> Self {
+        Self {
+            input: GetInput::new("Ready action until", 40, Charset::All),
+        }
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        self.input.draw(frame, frame.area());
+    }
+
+    /// Handle a key event and apply any needed changes to the tracker.
+    pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        match self.input.handle_key(key) {
+            AfterKeyInner::Handled => AfterKey::Stay,
+            AfterKeyInner::Submit(trigger) => {
+                if tracker.ready(trigger.trim()) {
+                    AfterKey::Exit
+                } else {
+                    self.input.set_prompt("No action left to ready");
+                    AfterKey::Stay
+                }
+            },
+            AfterKeyInner::Cancel => AfterKey::Exit,
+            _ => AfterKey::Stay,
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{input::{AfterKey as AfterKeyInner, Charset, GetInput}, Tracker};
+use crossterm::event::KeyEvent;
+use ratatui::prelude::*;
+use super::AfterKey;
+
+/// State for renaming a combatant, such as to match the mini on the table, or for giving it an
+/// alias to show the players until they identify it.
+#[derive(Clone, Debug, Default)]
+pub struct Rename {
+    /// The index of the combatant to rename.
+    combatant: usize,
+
+    /// Helper to get the new name from the user.
+    input: GetInput<String>,
+
+    /// Whether to set the combatant's alias instead of its name.
+    alias: bool,
+}
+
+impl Rename {
+    /// Create a [`Rename`] state for the given combatant.
+    pub fn new(combatant: usize, tracker: &Tracker) -> Self {
+        let name = tracker.combatants[combatant].name();
+        Self {
+            combatant,
+            input: GetInput::new(format!("Rename {}", name), 30, Charset::All)
+                .value(name),
+            alias: false,
+        }
+    }
+
+    /// Create a [`Rename`] state that sets the alias the players see for the given combatant.
+    pub fn alias(combatant: usize, tracker: &Tracker) -> Self {
+        let combatant_ref = &tracker.combatants[combatant];
+        Self {
+            combatant,
+            input: GetInput::new(
+                format!("Alias players see for {} (empty to reveal)", combatant_ref.name()),
+                30,
+                Charset::All,
+            )
+                .value(combatant_ref.alias.clone().unwrap_or_default()),
+            alias: true,
+        }
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        self.input.draw(frame, frame.area());
+    }
+
+    /// Handle a key event and apply any needed changes to the tracker.
+    pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        match self.input.handle_key(key) {
+            AfterKeyInner::Handled => AfterKey::Stay,
+            AfterKeyInner::Submit(name) if self.alias => {
+                // an empty alias reveals the combatant's name
+                let alias = name.trim();
+                tracker.combatants[self.combatant].alias = if alias.is_empty() {
+                    None
+                } else {
+                    Some(alias.to_string())
+                };
+                AfterKey::Exit
+            },
+            AfterKeyInner::Submit(name) => {
+                // an empty name hands the combatant back to automatic naming
+                let name = name.trim();
+                tracker.combatants[self.combatant].display_name = if name.is_empty() {
+                    None
+                } else {
+                    Some(name.to_string())
+                };
+                tracker.disambiguate_names();
+                AfterKey::Exit
+            },
+            AfterKeyInner::Cancel => AfterKey::Exit,
+            _ => AfterKey::Stay,
+        }
+    }
+}
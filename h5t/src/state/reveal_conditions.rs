@@ -0,0 +1,67 @@
+use crate::{selectable::Selectable, ui::LABELS, widgets::popup::Multiselect, Tracker};
+use crossterm::event::{KeyCode, KeyEvent};
+use h5t_core::ConditionKind;
+use ratatui::prelude::*;
+use std::collections::{HashMap, HashSet};
+use super::AfterKey;
+
+/// State for picking which of the combatants' conditions the players can see in the player view.
+#[derive(Clone, Debug)]
+pub struct RevealConditions {
+    /// The combatant indices to change.
+    combatants: Vec<usize>,
+
+    /// The conditions the players can see.
+    conditions: HashSet<ConditionKind>,
+}
+
+impl RevealConditions {
+    /// Create a [`RevealConditions`] state for the given combatants, starting from the conditions
+    /// visible on the first of them.
+    pub fn new(combatants: Vec<usize>, tracker: &Tracker) -> Self {
+        let conditions = tracker.combatants[combatants[0]].visible_conditions
+            .iter()
+            .copied()
+            .collect();
+        Self { combatants, conditions }
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        frame.render_widget(
+            Multiselect::new("Conditions players can see", &self.conditions, true),
+            frame.area(),
+        );
+    }
+
+    /// Handle a key event and apply any needed changes to the tracker.
+    pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        let label_to_option = LABELS
+            .chars()
+            .zip(ConditionKind::variants())
+            .collect::<HashMap<_, _>>();
+
+        match key.code {
+            KeyCode::Esc => return AfterKey::Exit,
+            KeyCode::Enter => {
+                // keep the order of the conditions stable
+                let visible = ConditionKind::variants()
+                    .filter(|kind| self.conditions.contains(kind))
+                    .collect::<Vec<_>>();
+                for &combatant in &self.combatants {
+                    tracker.combatants[combatant].visible_conditions = visible.clone();
+                }
+                return AfterKey::Exit;
+            },
+            KeyCode::Char(label) => {
+                if let Some(option) = label_to_option.get(&label) {
+                    if !self.conditions.remove(option) {
+                        self.conditions.insert(*option);
+                    }
+                }
+            },
+            _ => (),
+        }
+        AfterKey::Stay
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{input::{AfterKey as AfterKeyInner, Charset, GetInput}, Tracker};
+use crossterm::event::KeyEvent;
+use h5t_core::{Encounter, Save};
+use ratatui::prelude::*;
+use super::AfterKey;
+
+/// State for saving the tracker to a file: either the combatants as an encounter file, so the
+/// setup can be reused, or the whole session, so the combat can be resumed later.
+#[derive(Clone, Debug, Default)]
+pub struct SaveEncounter {
+    /// Helper to get the file path from the user.
+    input: GetInput<String>,
+
+    /// Whether to save the whole session instead of an encounter.
+    session: bool,
+}
+
+impl SaveEncounter {
+    /// Create a [`SaveEncounter`] state that saves an encounter file.
+    pub fn new() -> Self {
+        Self {
+            input: GetInput::new("Save encounter as", 40, Charset::All)
+                .suffix(".toml"),
+            session: false,
+        }
+    }
+
+    /// Create a [`SaveEncounter`] state that saves the session, to be resumed with `h5t resume`.
+    pub fn session() -> Self {
+        Self {
+            input: GetInput::new("Save session as", 40, Charset::All)
+                .suffix(".toml"),
+            session: true,
+        }
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        self.input.draw(frame, frame.area());
+    }
+
+    /// Handle a key event and apply any needed changes to the tracker.
+    pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        match self.input.handle_key(key) {
+            AfterKeyInner::Handled => AfterKey::Stay,
+            AfterKeyInner::Submit(path) => {
+                let path = if path.ends_with(".toml") { path } else { format!("{}.toml", path) };
+                let result = if self.session {
+                    Save::from(&*tracker).save(&path)
+                } else {
+                    Encounter::from_tracker(tracker).save(&path)
+                };
+                match result {
+                    Ok(()) => AfterKey::Exit,
+                    Err(err) => {
+                        // keep the input open so the user can try another path
+                        self.input.set_prompt(format!("Save failed: {}", err));
+                        AfterKey::Stay
+                    },
+                }
+            },
+            AfterKeyInner::Cancel => AfterKey::Exit,
+            _ => AfterKey::Stay,
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use canvas::Canvas;
+use crate::{
+    input::{AfterKey as AfterKeyInner, Charset, GetInput, OrRoll},
+    theme::THEME,
+    widgets::popup::popup_area,
+    Tracker,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use h5t_core::{area::TargetSave, dice::Expr, AbilityKind, AreaEffect, Resistance};
+use ratatui::{layout::Flex, prelude::*, widgets::*};
+use super::AfterKey;
+
+/// Helper enum to indicate which form field is currently selected.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum Field {
+    #[default]
+    Damage,
+    DamageType,
+    Ability,
+    Dc,
+    Saves,
+    Review,
+}
+
+/// State for dealing damage that each target makes a saving throw against, such as Fireball.
+///
+/// The user enters the damage roll, damage type, save ability and DC once, then rolls or enters
+/// each target's save. The results are shown for review, where any save can be flipped, before
+/// the damage is applied.
+#[derive(Clone, Debug)]
+pub struct SaveForDamage {
+    /// The combatant indices of the targets.
+    combatants: Vec<usize>,
+
+    /// The names of the targets, for the review table.
+    names: Vec<String>,
+
+    /// Indicates which form field is currently selected.
+    selected: Field,
+
+    /// Helper to get the damage roll.
+    damage: GetInput<Expr>,
+
+    /// Helper to get the damage type.
+    damage_type: GetInput<String>,
+
+    /// Helper to get the saving throw ability.
+    ability: GetInput<AbilityKind>,
+
+    /// Helper to get the saving throw DC.
+    dc: GetInput<i32>,
+
+    /// Helper to get the current target's saving throw.
+    save: GetInput<OrRoll>,
+
+    /// The effect being resolved, filled in as the form is submitted.
+    effect: AreaEffect,
+
+    /// The resolved saving throws so far.
+    saves: Vec<TargetSave>,
+
+    /// The index of the highlighted save in the review table.
+    highlighted: usize,
+}
+
+impl SaveForDamage {
+    /// Create a [`SaveForDamage`] state targeting the given combatants.
+    pub fn new(mut combatants: Vec<usize>, tracker: &Tracker) -> Self {
+        combatants.sort_unstable();
+        Self {
+            names: combatants
+                .iter()
+                .map(|&combatant| tracker.combatants[combatant].name().to_string())
+                .collect(),
+            combatants,
+            selected: Field::default(),
+            damage: GetInput::new("Damage roll", 12, Charset::All)
+                .suffix("e.g. 8d6 or 28"),
+            damage_type: GetInput::new("Damage type", 15, Charset::All)
+                .suffix("empty for none"),
+            ability: GetInput::new("Saving throw", 12, Charset::All)
+                .suffix("e.g. DEX"),
+            dc: GetInput::new("Save DC", 3, Charset::Numeric),
+            save: GetInput::default(),
+            effect: AreaEffect::default(),
+            saves: Vec::new(),
+            highlighted: 0,
+        }
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        let area = frame.area();
+        match self.selected {
+            Field::Damage => return self.damage.draw(frame, area),
+            Field::DamageType => return self.damage_type.draw(frame, area),
+            Field::Ability => return self.ability.draw(frame, area),
+            Field::Dc => return self.dc.draw(frame, area),
+            Field::Saves => return self.save.draw(frame, area),
+            Field::Review => (),
+        }
+
+        let rows = self.saves.iter().enumerate().map(|(i, save)| {
+            let mut result = if save.success { "Success" } else { "Failure" }.to_string();
+            if save.evasion {
+                result.push_str(" (evasion)");
+            }
+            let mut damage = save.damage.to_string();
+            if save.resistance != Resistance::Normal {
+                damage.push_str(&format!(" ({})", save.resistance));
+            }
+            let row = Row::new([
+                self.names[i].clone(),
+                save.total.to_string(),
+                result,
+                damage,
+            ]);
+            if i == self.highlighted {
+                row.bold().bg(THEME.select)
+            } else {
+                row
+            }
+        });
+        let table = Table::new(rows, [
+            Constraint::Fill(2),
+            Constraint::Length(5),
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+        ])
+            .header(Row::new(["Target", "Save", "Result", "Damage"]).bold())
+            .fg(THEME.foreground)
+            .block(Block::bordered()
+                .border_type(BorderType::Rounded)
+                .border_style(THEME.foreground)
+                .title(format!(
+                    "{} damage, DC {} {} save",
+                    self.effect.damage,
+                    self.effect.dc,
+                    self.effect.ability.map(|ability| ability.to_string()).unwrap_or_default(),
+                ))
+                .title_bottom("Space: flip save, e: evasion, Enter: apply")
+                .padding(Padding::horizontal(1)));
+
+        let height = self.saves.len() as u16 + 3;
+        let area = popup_area(area, Flex::Center, Flex::Center, (70.min(area.width), height), 0);
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Canvas::default()
+                .background_color(THEME.background.into())
+                .paint(|_| ()),
+            area,
+        );
+        frame.render_widget(table, area);
+    }
+
+    /// Handle a key event and apply any needed changes to the tracker.
+    pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        match self.selected {
+            Field::Damage => match self.damage.handle_key(key) {
+                AfterKeyInner::Submit(damage) => {
+                    self.effect.damage = damage.roll(&mut rand::thread_rng()).total.max(0);
+                    let prompt = format!("Damage type ({} damage)", self.effect.damage);
+                    self.damage_type.set_prompt(prompt);
+                    self.selected = Field::DamageType;
+                },
+                AfterKeyInner::Cancel => return AfterKey::Exit,
+                _ => (),
+            },
+            Field::DamageType => match self.damage_type.handle_key(key) {
+                AfterKeyInner::Submit(damage_type) => {
+                    self.effect.damage_type = damage_type.trim().to_string();
+                    self.selected = Field::Ability;
+                },
+                AfterKeyInner::Cancel => self.selected = Field::Damage,
+                _ => (),
+            },
+            Field::Ability => match self.ability.handle_key(key) {
+                AfterKeyInner::Submit(ability) => {
+                    self.effect.ability = Some(ability);
+                    self.selected = Field::Dc;
+                },
+                AfterKeyInner::Cancel => self.selected = Field::DamageType,
+                _ => (),
+            },
+            Field::Dc => match self.dc.handle_key(key) {
+                AfterKeyInner::Submit(dc) => {
+                    self.effect.dc = dc;
+                    self.saves.clear();
+                    self.next_save(tracker);
+                },
+                AfterKeyInner::Cancel => self.selected = Field::Ability,
+                _ => (),
+            },
+            Field::Saves => match self.save.handle_key(key) {
+                AfterKeyInner::Submit(OrRoll(total)) => {
+                    let combatant = self.combatants[self.saves.len()];
+                    let save = match total {
+                        Some(total) => self.effect.resolve(tracker, combatant, total),
+                        None => self.effect.roll(tracker, combatant, &mut rand::thread_rng()),
+                    };
+                    self.saves.push(save);
+                    self.next_save(tracker);
+                },
+                AfterKeyInner::Cancel => self.selected = Field::Dc,
+                _ => (),
+            },
+            Field::Review => match key.code {
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.highlighted = self.highlighted.saturating_sub(1);
+                },
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.highlighted = (self.highlighted + 1).min(self.saves.len() - 1);
+                },
+                KeyCode::Char(' ') => {
+                    let save = &mut self.saves[self.highlighted];
+                    save.success = !save.success;
+                    save.damage = self.effect.damage_for(save);
+                },
+                KeyCode::Char('e') => {
+                    let save = &mut self.saves[self.highlighted];
+                    save.evasion = !save.evasion;
+                    save.damage = self.effect.damage_for(save);
+                },
+                KeyCode::Enter => {
+                    tracker.apply_saves(&self.saves);
+                    return AfterKey::Exit;
+                },
+                KeyCode::Esc => return AfterKey::Exit,
+                _ => (),
+            },
+        }
+
+        AfterKey::Stay
+    }
+
+    /// Ask for the next target's saving throw, or move on to the review once every target has
+    /// saved.
+    fn next_save(&mut self, tracker: &Tracker) {
+        let Some(&combatant) = self.combatants.get(self.saves.len()) else {
+            self.selected = Field::Review;
+            return;
+        };
+        let combatant = &tracker.combatants[combatant];
+        let ability = self.effect.ability.map(|ability| ability.to_string()).unwrap_or_default();
+        let prompt = format!("{} save for {}", ability, combatant.name());
+        self.save = GetInput::new(prompt, 4, Charset::Numeric)
+            .suffix(format!("{:+}, empty to roll", self.effect.modifier(combatant)));
+        self.selected = Field::Saves;
+    }
+}
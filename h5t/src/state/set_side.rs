@@ -0,0 +1,53 @@
+use crate::{input::{AfterKey as AfterKeyInner, Charset, GetInput}, Tracker};
+use crossterm::event::KeyEvent;
+use h5t_core::Side;
+use ratatui::prelude::*;
+use super::AfterKey;
+
+/// State for moving combatants to another side, such as a monster that surrenders or is charmed
+/// into fighting for the party.
+#[derive(Clone, Debug, Default)]
+pub struct SetSide {
+    /// The combatant indices to move.
+    combatants: Vec<usize>,
+
+    /// Helper to get the side from the user.
+    input: GetInput<Side>,
+}
+
+impl SetSide {
+    /// Create a [`SetSide`] state for the given combatants.
+    pub fn new(combatants: Vec<usize>, tracker: &Tracker) -> Self {
+        let side = tracker.combatants[combatants[0]].side.to_string();
+        Self {
+            combatants,
+            input: GetInput::new("Side (party, enemy, neutral, or a name)", 30, Charset::All)
+                .value(side),
+        }
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        self.input.draw(frame, frame.area());
+    }
+
+    /// Handle a key event and apply any needed changes to the tracker.
+    pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        match self.input.handle_key(key) {
+            AfterKeyInner::Handled => AfterKey::Stay,
+            AfterKeyInner::Submit(side) => {
+                // an empty side puts each combatant back on its usual side
+                for &combatant in &self.combatants {
+                    let combatant = &mut tracker.combatants[combatant];
+                    combatant.side = match &side {
+                        Side::Custom(name) if name.is_empty() => Side::default_for(&combatant.kind),
+                        side => side.clone(),
+                    };
+                }
+                AfterKey::Exit
+            },
+            AfterKeyInner::Cancel => AfterKey::Exit,
+            _ => AfterKey::Stay,
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use canvas::Canvas;
+use crate::{theme::THEME, widgets::popup::popup_area, Tracker};
+use crossterm::event::KeyEvent;
+use h5t_core::CombatStats;
+use ratatui::{layout::Flex, prelude::*, widgets::*};
+use super::AfterKey;
+
+/// State showing each combatant's statistics for the fight so far, until any key is pressed.
+#[derive(Clone, Debug)]
+pub struct Stats {
+    /// Each combatant's name and statistics, from the most damage dealt to the least.
+    rows: Vec<(String, CombatStats)>,
+
+    /// The name of the combatant who dealt the most damage, if anyone dealt any.
+    mvp: Option<String>,
+}
+
+impl Stats {
+    /// Create a [`Stats`] state with the tracker's statistics.
+    pub fn new(tracker: &Tracker) -> Self {
+        let mut rows = tracker.combatants
+            .iter()
+            .map(|combatant| (combatant.name().to_string(), combatant.stats.clone()))
+            .collect::<Vec<_>>();
+        rows.sort_by_key(|(_, stats)| std::cmp::Reverse(stats.damage_dealt));
+        Self {
+            rows,
+            mvp: tracker.mvp().map(|mvp| tracker.combatants[mvp].name().to_string()),
+        }
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        let heading = match &self.mvp {
+            Some(mvp) => Line::from(vec![
+                Span::raw("MVP: "),
+                Span::styled(mvp.as_str(), Modifier::BOLD),
+            ]),
+            None => Line::from("No damage dealt yet"),
+        };
+
+        let rows = self.rows.iter().map(|(name, stats)| {
+            ratatui::widgets::Row::new([
+                name.clone(),
+                stats.damage_dealt.to_string(),
+                stats.damage_taken.to_string(),
+                stats.healing_done.to_string(),
+                stats.kills.to_string(),
+                stats.crits.to_string(),
+                stats.conditions_applied.to_string(),
+            ])
+        });
+        let table = Table::new(rows, [
+            Constraint::Fill(1),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(7),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Length(11),
+        ])
+            .header(
+                ratatui::widgets::Row::new([
+                    "Name", "Dealt", "Taken", "Healed", "Kills", "Crits", "Conditions",
+                ])
+                    .bold(),
+            );
+
+        let width = 80.min(frame.area().width);
+        let height = (self.rows.len() as u16 + 5).min(frame.area().height);
+        let area = popup_area(frame.area(), Flex::Center, Flex::Center, (width, height), 0);
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Canvas::default()
+                .background_color(THEME.background.into())
+                .paint(|_| ()),
+            area,
+        );
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(THEME.foreground)
+            .title("Combat statistics")
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let [heading_area, table_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Fill(1),
+        ])
+            .areas(inner);
+        frame.render_widget(Paragraph::new(heading).fg(THEME.foreground), heading_area);
+        frame.render_widget(table.fg(THEME.foreground), table_area);
+    }
+
+    /// Handle a key event. Any key closes the statistics.
+    pub fn handle_key(&mut self, _: KeyEvent, _: &mut Tracker) -> AfterKey {
+        AfterKey::Exit
+    }
+}
@@ -0,0 +1,157 @@
+use canvas::Canvas;
+use crate::{
+    input::{AfterKey as AfterKeyInner, Charset, GetInput},
+    theme::THEME,
+    widgets::popup::popup_area,
+    Tracker,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+use h5t_core::EncounterSummary;
+use ratatui::{layout::Flex, prelude::*, widgets::*};
+use super::AfterKey;
+
+/// State showing how a fight ended, until any key is pressed. This opens on its own once only one
+/// side is left standing.
+///
+/// Pressing `x` exports the summary as a Markdown report instead of closing it.
+#[derive(Clone, Debug)]
+pub struct Summary {
+    /// The summary of the fight.
+    summary: EncounterSummary,
+
+    /// Helper to get the path to export the report to, while exporting.
+    export: Option<GetInput<String>>,
+
+    /// The path the report was last exported to.
+    exported: Option<String>,
+}
+
+impl Summary {
+    /// Create a [`Summary`] of the tracker's fight so far.
+    pub fn new(tracker: &Tracker) -> Self {
+        Self {
+            summary: tracker.summary(),
+            export: None,
+            exported: None,
+        }
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        let summary = &self.summary;
+        let heading = Line::from(Span::styled(summary.outcome(), Modifier::BOLD));
+        let mut xp = format!("{} XP", summary.total_xp());
+        if summary.party_size > 0 {
+            xp.push_str(&format!(" ({} each)", summary.xp_per_character()));
+        }
+        let footer = match &self.exported {
+            Some(path) => format!("Exported to {}", path),
+            None => "Press x to export as Markdown".to_string(),
+        };
+
+        let rows = summary.combatants.iter().map(|combatant| {
+            let style = if combatant.defeated {
+                Style::default().fg(THEME.dead.into())
+            } else {
+                Style::default()
+            };
+            ratatui::widgets::Row::new([
+                combatant.name.clone(),
+                combatant.side.to_string(),
+                format!("{} / {}", combatant.hit_points, combatant.max_hit_points),
+                combatant.status().to_string(),
+                combatant.stats.damage_dealt.to_string(),
+                combatant.stats.damage_taken.to_string(),
+                combatant.stats.conditions_inflicted
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ])
+                .style(style)
+        });
+        let table = Table::new(rows, [
+            Constraint::Fill(3),
+            Constraint::Fill(2),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Length(5),
+            Constraint::Length(5),
+            Constraint::Fill(3),
+        ])
+            .header(
+                ratatui::widgets::Row::new([
+                    "Name", "Side", "HP", "Status", "Dealt", "Taken", "Conditions",
+                ])
+                    .bold(),
+            );
+
+        let width = 100.min(frame.area().width);
+        let height = (summary.combatants.len() as u16 + 8).min(frame.area().height);
+        let area = popup_area(frame.area(), Flex::Center, Flex::Center, (width, height), 0);
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Canvas::default()
+                .background_color(THEME.background.into())
+                .paint(|_| ()),
+            area,
+        );
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(THEME.foreground)
+            .title("Combat over")
+            .title_bottom(footer)
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let [heading_area, table_area, xp_area] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Fill(1),
+            Constraint::Length(2),
+        ])
+            .areas(inner);
+        frame.render_widget(Paragraph::new(heading).fg(THEME.foreground), heading_area);
+        frame.render_widget(table.fg(THEME.foreground), table_area);
+        frame.render_widget(
+            Paragraph::new(format!("\nTotal: {}", xp)).fg(THEME.foreground),
+            xp_area,
+        );
+
+        if let Some(export) = &self.export {
+            export.draw(frame, frame.area());
+        }
+    }
+
+    /// Handle a key event. Any key other than `x` closes the summary.
+    pub fn handle_key(&mut self, key: KeyEvent, _: &mut Tracker) -> AfterKey {
+        let Some(export) = &mut self.export else {
+            if key.code == KeyCode::Char('x') {
+                self.export = Some(
+                    GetInput::new("Export report as", 40, Charset::All).suffix(".md"),
+                );
+                return AfterKey::Stay;
+            }
+            return AfterKey::Exit;
+        };
+
+        match export.handle_key(key) {
+            AfterKeyInner::Submit(path) => {
+                let path = if path.ends_with(".md") { path } else { format!("{}.md", path) };
+                match std::fs::write(&path, self.summary.to_markdown()) {
+                    Ok(()) => {
+                        self.exported = Some(path);
+                        self.export = None;
+                    },
+                    // keep the input open so the user can try another path
+                    Err(err) => {
+                        export.set_prompt(format!("Export failed: {}", err));
+                    },
+                }
+            },
+            AfterKeyInner::Cancel => self.export = None,
+            _ => (),
+        }
+        AfterKey::Stay
+    }
+}
@@ -0,0 +1,123 @@
+use crate::{
+    input::{AfterKey as AfterKeyInner, Charset, GetInput},
+    theme::THEME,
+    widgets::popup::popup_area,
+    Tracker,
+};
+use canvas::Canvas;
+use crossterm::event::KeyEvent;
+use h5t_core::rest::RestSummary;
+use ratatui::{layout::Flex, prelude::*, widgets::*};
+use super::AfterKey;
+
+/// Creates the input asking how many hit dice the given combatant spends.
+fn hit_dice_input(tracker: &Tracker, combatant: usize) -> GetInput<u32> {
+    let combatant = &tracker.combatants[combatant];
+    let prompt = match combatant.hit_dice() {
+        Some((count, sides)) => format!(
+            "Hit dice for {} ({}d{} left)",
+            combatant.name(),
+            count.saturating_sub(combatant.hit_dice_spent),
+            sides,
+        ),
+        None => format!("Hit dice for {}", combatant.name()),
+    };
+    GetInput::new(prompt, 4, Charset::Numeric).value("0")
+}
+
+/// State for resting combatants, then showing what the rest restored.
+#[derive(Clone, Debug)]
+pub enum TakeRest {
+    /// Asking how many hit dice each combatant spends on a short rest, one combatant at a time.
+    HitDice {
+        /// The combatants left to ask about, in reverse order.
+        remaining: Vec<usize>,
+
+        /// The combatants already asked about, and the number of hit dice they spend.
+        rests: Vec<(usize, u32)>,
+
+        /// Helper to get the number of hit dice from the user.
+        input: GetInput<u32>,
+    },
+
+    /// Showing what the rest restored, until any key is pressed.
+    Summary(Vec<RestSummary>),
+}
+
+impl TakeRest {
+    /// Create a [`TakeRest`] state for a short rest of the given combatants.
+    pub fn short(mut combatants: Vec<usize>, tracker: &Tracker) -> Self {
+        // ask in order of play, popping from the back
+        combatants.sort_unstable_by(|a, b| b.cmp(a));
+        let input = hit_dice_input(tracker, combatants[combatants.len() - 1]);
+        Self::HitDice { remaining: combatants, rests: Vec::new(), input }
+    }
+
+    /// Take a long rest with the given combatants, and create a [`TakeRest`] state showing the
+    /// result.
+    pub fn long(combatants: Vec<usize>, tracker: &mut Tracker) -> Self {
+        Self::Summary(tracker.long_rest(combatants))
+    }
+
+    /// Draw the state to the given [`Frame`].
+    pub fn draw(&self, frame: &mut Frame) {
+        let summaries = match self {
+            Self::HitDice { input, .. } => return input.draw(frame, frame.area()),
+            Self::Summary(summaries) => summaries,
+        };
+
+        let mut lines = Vec::new();
+        for summary in summaries {
+            lines.push(Line::styled(summary.name.clone(), Modifier::BOLD));
+            if summary.restored.is_empty() {
+                lines.push(Line::raw("  Nothing to restore").italic());
+            }
+            for restored in &summary.restored {
+                lines.push(Line::raw(format!("  {}", restored)));
+            }
+        }
+        let width = lines.iter().map(Line::width).max().unwrap_or(0).max(20) as u16 + 4;
+        let height = lines.len() as u16 + 2;
+        let area = popup_area(frame.area(), Flex::Center, Flex::Center, (width, height), 0);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Canvas::default()
+                .background_color(THEME.background.into())
+                .paint(|_| ()),
+            area,
+        );
+        frame.render_widget(
+            Paragraph::new(lines)
+                .fg(THEME.foreground)
+                .block(Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(THEME.foreground)
+                    .title("Rest")
+                    .padding(Padding::horizontal(1))),
+            area,
+        );
+    }
+
+    /// Handle a key event and apply any needed changes to the tracker.
+    pub fn handle_key(&mut self, key: KeyEvent, tracker: &mut Tracker) -> AfterKey {
+        let Self::HitDice { remaining, rests, input } = self else {
+            return AfterKey::Exit;
+        };
+
+        match input.handle_key(key) {
+            AfterKeyInner::Submit(hit_dice) => {
+                rests.extend(remaining.pop().map(|combatant| (combatant, hit_dice)));
+                if let Some(&next) = remaining.last() {
+                    *input = hit_dice_input(tracker, next);
+                } else {
+                    let rests = std::mem::take(rests);
+                    *self = Self::Summary(tracker.short_rest(rests, &mut rand::thread_rng()));
+                }
+                AfterKey::Stay
+            },
+            AfterKeyInner::Cancel => AfterKey::Exit,
+            _ => AfterKey::Stay,
+        }
+    }
+}
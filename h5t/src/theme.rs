@@ -1,3 +1,5 @@
+use std::{ops::Deref, sync::OnceLock};
+
 /// An RGB color.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Rgb(pub u8, pub u8, pub u8);
@@ -71,6 +73,37 @@ impl Theme {
         }
     }
 
+    /// Returns a light theme, for terminals with a light background.
+    pub const fn light() -> Self {
+        Theme {
+            background: Rgb(250, 250, 245), // off-white
+            foreground: Rgb(40, 44, 52), // charcoal
+            primary: Rgb(173, 200, 255), // light blue
+            secondary: Rgb(220, 223, 230), // light gray
+            select: Rgb(255, 214, 130), // light yellow
+            accent: Rgb(200, 120, 0),
+            error: Rgb(200, 40, 60), // crimson
+            warning: Rgb(180, 120, 0), // dark yellow
+
+            action: Rgb(60, 140, 40), // green
+            bonus_action: Rgb(190, 120, 0), // dark gold
+            reaction: Rgb(120, 80, 200), // purple
+            dead: Rgb(255, 170, 170), // pink
+        }
+    }
+
+    /// The names of the built-in themes, accepted by [`Theme::by_name`].
+    pub const NAMES: [&'static str; 2] = ["default", "light"];
+
+    /// Returns the built-in theme with the given name.
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Theme::new()),
+            "light" => Some(Theme::light()),
+            _ => None,
+        }
+    }
+
     /// Return a dimmed version of the theme, obtained by mixing each color with the background
     /// color.
     pub fn dim(self) -> Self {
@@ -93,19 +126,24 @@ impl Theme {
     }
 }
 
-/// The default theme for the UI.
-pub static THEME: Theme = Theme {
-    background: Rgb(26, 27, 38), // indigo
-    foreground: Rgb(192, 202, 245), // light blue
-    primary: Rgb(0, 48, 130), // dark blue
-    secondary: Rgb(65, 72, 104), // blue gray
-    select: Rgb(128, 85, 0), // dark yellow
-    accent: Rgb(255, 165, 0),
-    error: Rgb(247, 118, 142), // pastel red
-    warning: Rgb(224, 175, 104), // pastel yellow
-
-    action: Rgb(158, 206, 106), // lime green
-    bonus_action: Rgb(255, 165, 0), // gold
-    reaction: Rgb(187, 154, 247), // pastel purple
-    dead: Rgb(100, 0, 0), // dark red
-};
+/// Holds the theme used by the UI, which can be chosen once at startup with [`set_theme`].
+///
+/// Dereferences to the chosen [`Theme`], or the default theme if none was chosen.
+#[derive(Debug)]
+pub struct ThemeCell(OnceLock<Theme>);
+
+impl Deref for ThemeCell {
+    type Target = Theme;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.get_or_init(Theme::new)
+    }
+}
+
+/// The theme for the UI.
+pub static THEME: ThemeCell = ThemeCell(OnceLock::new());
+
+/// Set the theme for the UI. This has no effect if the theme has already been set or used.
+pub fn set_theme(theme: Theme) {
+    let _ = THEME.0.set(theme);
+}
@@ -1,13 +1,41 @@
 use bimap::BiMap;
 use crate::{
-    state::{AfterKey, ApplyCondition, ApplyDamage, State},
+    control::Control,
+    state::{
+        AddCombatant,
+        AfterKey,
+        ApplyCondition,
+        ApplyDamage,
+        ChangeForm,
+        GroupCheck,
+        MakeAttack,
+        MoveCombatant,
+        ReadyAction,
+        Rename,
+        RevealConditions,
+        SaveEncounter,
+        SaveForDamage,
+        SetSide,
+        State,
+        Stats,
+        Summary,
+        TakeRest,
+    },
+    share::{Share, POLL_INTERVAL},
     theme::THEME,
-    widgets::{max_combatants, CombatantBlock, StatBlock, Tracker as TrackerWidget},
+    widgets::{
+        max_combatants,
+        CombatLog,
+        CombatantBlock,
+        PlayerView,
+        StatBlock,
+        Tracker as TrackerWidget,
+    },
 };
-use crossterm::event::{read, Event, KeyCode};
-use h5t_core::{CombatantKind, Tracker};
+use crossterm::event::{poll, read, Event, KeyCode, KeyEvent};
+use h5t_core::{ActiveKind, Bestiary, InitiativeMode, Tracker};
 use ratatui::{prelude::*, widgets::canvas::Canvas};
-use std::{collections::HashSet, ops::{Deref, DerefMut}};
+use std::{collections::HashSet, ops::{Deref, DerefMut}, rc::Rc};
 
 /// Labels used for label mode. The tracker will choose labels from this string in sequential
 /// order.
@@ -25,14 +53,25 @@ pub enum InfoBlock {
 
     /// Show the combatant's current combat state.
     CombatantCard,
+
+    /// Show the combat log.
+    Log,
 }
 
 impl InfoBlock {
-    /// Toggle the info block.
+    /// Toggle between the stat block and the combatant card.
     pub fn toggle(&mut self) {
         *self = match self {
             InfoBlock::StatBlock => InfoBlock::CombatantCard,
-            InfoBlock::CombatantCard => InfoBlock::StatBlock,
+            InfoBlock::CombatantCard | InfoBlock::Log => InfoBlock::StatBlock,
+        };
+    }
+
+    /// Toggle between the combat log and the combatant card.
+    pub fn toggle_log(&mut self) {
+        *self = match self {
+            InfoBlock::Log => InfoBlock::CombatantCard,
+            InfoBlock::StatBlock | InfoBlock::CombatantCard => InfoBlock::Log,
         };
     }
 }
@@ -55,6 +94,9 @@ pub struct Ui<B: Backend> {
     /// The underlying tracker.
     pub tracker: Tracker,
 
+    /// The monsters that can be added to the tracker mid-combat.
+    bestiary: Rc<Bestiary>,
+
     /// Which info block to show.
     info_block: InfoBlock,
 
@@ -63,6 +105,19 @@ pub struct Ui<B: Backend> {
 
     /// State for label mode.
     label_state: Option<LabelModeState>,
+
+    /// Whether the summary has been shown since combat was last found to be over, so it only opens
+    /// on its own once.
+    summary_shown: bool,
+
+    /// Whether to show the player view in place of the tracker, such as for a mirrored screen.
+    player_view: bool,
+
+    /// The connection sharing the player view with other terminals, if any.
+    share: Option<Share>,
+
+    /// The JSON-RPC API controlling the tracker from other programs, if any.
+    control: Option<Control>,
 }
 
 impl<B: Backend> Drop for Ui<B> {
@@ -72,24 +127,44 @@ impl<B: Backend> Drop for Ui<B> {
 }
 
 impl<B: Backend> Ui<B> {
-    /// Wrap a [`Tracker`] in a new [`UiTracker`].
-    pub fn new(terminal: Terminal<B>, tracker: Tracker) -> Self {
+    /// Wrap a [`Tracker`] in a new [`UiTracker`], with the given bestiary to add monsters from.
+    pub fn new(terminal: Terminal<B>, tracker: Tracker, bestiary: Bestiary) -> Self {
         Self {
             terminal,
             tracker,
+            bestiary: Rc::new(bestiary),
             info_block: InfoBlock::CombatantCard,
             state: None,
             label_state: None,
+            summary_shown: false,
+            player_view: false,
+            share: None,
+            control: None,
         }
     }
 
+    /// Share the player view with the terminals that connect to the given [`Share`].
+    pub fn share(mut self, share: Share) -> Self {
+        self.share = Some(share);
+        self
+    }
+
+    /// Accept requests from other programs through the given [`Control`].
+    pub fn control(mut self, control: Control) -> Self {
+        self.control = Some(control);
+        self
+    }
+
     /// Run off the tracker until the user exits.
     pub fn run(&mut self) {
         loop {
+            if self.serve() {
+                self.check_combat_over();
+            }
             self.draw().unwrap();
 
             // wait for user input
-            let Ok(Event::Key(key)) = read() else {
+            let Some(key) = self.next_key() else {
                 continue;
             };
 
@@ -99,6 +174,7 @@ impl<B: Backend> Ui<B> {
                     AfterKey::Exit => self.label_state = None,
                     AfterKey::Stay => self.state = Some(state),
                 }
+                self.check_combat_over();
                 continue;
             }
 
@@ -109,7 +185,9 @@ impl<B: Backend> Ui<B> {
                         self.label_state = None;
                         continue;
                     }
-                    self.state = Some(State::ApplyCondition(ApplyCondition::new(selected)));
+                    self.state = Some(State::ApplyCondition(
+                        ApplyCondition::new(selected, &self.tracker),
+                    ));
                 },
                 KeyCode::Char('d') => {
                     let selected = self.enter_label_mode();
@@ -117,7 +195,142 @@ impl<B: Backend> Ui<B> {
                         self.label_state = None;
                         continue;
                     }
-                    self.state = Some(State::ApplyDamage(ApplyDamage::new(selected)));
+                    self.state = Some(State::ApplyDamage(ApplyDamage::new(selected, &self.tracker)));
+                },
+                KeyCode::Char('A') => {
+                    let selected = self.enter_label_mode();
+                    self.label_state = None;
+                    if selected.is_empty() {
+                        continue;
+                    }
+                    if let Some(state) = MakeAttack::new(selected, &self.tracker) {
+                        self.state = Some(State::MakeAttack(state));
+                    }
+                },
+                KeyCode::Char('f') => {
+                    let selected = self.enter_label_mode();
+                    if selected.is_empty() {
+                        self.label_state = None;
+                        continue;
+                    }
+                    let state = SaveForDamage::new(selected, &self.tracker);
+                    self.state = Some(State::SaveForDamage(state));
+                },
+                KeyCode::Char('F') => {
+                    // only one combatant can change form at a time
+                    let selected = self.enter_label_mode();
+                    self.label_state = None;
+                    let [combatant] = selected[..] else {
+                        continue;
+                    };
+                    let state = ChangeForm::new(combatant, Rc::clone(&self.bestiary), &self.tracker);
+                    self.state = Some(State::ChangeForm(state));
+                },
+                KeyCode::Char('g') => {
+                    let selected = self.enter_label_mode();
+                    if selected.is_empty() {
+                        self.label_state = None;
+                        continue;
+                    }
+                    self.state = Some(State::GroupCheck(GroupCheck::new(selected, &self.tracker)));
+                },
+                KeyCode::Char('i') => {
+                    let state = AddCombatant::new(Rc::clone(&self.bestiary));
+                    self.state = Some(State::AddCombatant(state));
+                },
+                KeyCode::Char('x') => {
+                    // remove from the bottom up, so the remaining indices stay valid
+                    let mut selected = self.enter_label_mode();
+                    selected.sort_unstable_by(|a, b| b.cmp(a));
+                    for combatant in selected {
+                        self.tracker.remove(combatant);
+                    }
+                    self.label_state = None;
+                },
+                KeyCode::Char('C') => {
+                    let mut selected = self.enter_label_mode();
+                    selected.sort_unstable_by(|a, b| b.cmp(a));
+                    for combatant in selected {
+                        self.tracker.duplicate(combatant);
+                    }
+                    self.label_state = None;
+                },
+                KeyCode::Char('M') => {
+                    // group copies of the same monster into a mob on one initiative entry
+                    let selected = self.enter_label_mode();
+                    self.label_state = None;
+                    if let Some(mob) = self.tracker.form_mob(&selected) {
+                        let message = format!("{} form a mob", self.tracker.combatants[mob].name());
+                        self.tracker.log(message);
+                    }
+                },
+                KeyCode::Char('m') => {
+                    // only one combatant can be moved at a time
+                    let selected = self.enter_label_mode();
+                    self.label_state = None;
+                    let [combatant] = selected[..] else {
+                        continue;
+                    };
+                    self.state = Some(State::MoveCombatant(MoveCombatant::new(combatant)));
+                },
+                KeyCode::Char('D') => {
+                    self.tracker.delay();
+                },
+                KeyCode::Char('E') => {
+                    // only one combatant can re-enter at a time, right after the current one
+                    let selected = self.enter_label_mode();
+                    self.label_state = None;
+                    if let [combatant] = selected[..] {
+                        self.tracker.end_delay(combatant);
+                    }
+                },
+                KeyCode::Char('S') => {
+                    let selected = self.enter_label_mode();
+                    for combatant in selected {
+                        let combatant = &mut self.tracker.combatants[combatant];
+                        if combatant.surprised {
+                            combatant.surprised = false;
+                            combatant.resources.reactions = combatant.resources.reactions.max(1);
+                        } else {
+                            combatant.surprise();
+                        }
+                    }
+                    self.label_state = None;
+                },
+                KeyCode::Char('p') => {
+                    self.state = Some(State::ReadyAction(ReadyAction::new()));
+                },
+                KeyCode::Char('t') => {
+                    let selected = self.enter_label_mode();
+                    for combatant in selected {
+                        self.tracker.trigger_readied(combatant);
+                    }
+                    self.label_state = None;
+                },
+                KeyCode::Char('R') => {
+                    // only one combatant can be renamed at a time
+                    let selected = self.enter_label_mode();
+                    let [combatant] = selected[..] else {
+                        self.label_state = None;
+                        continue;
+                    };
+                    self.state = Some(State::Rename(Rename::new(combatant, &self.tracker)));
+                },
+                KeyCode::Char('z') => {
+                    let selected = self.enter_label_mode();
+                    self.label_state = None;
+                    if selected.is_empty() {
+                        continue;
+                    }
+                    self.state = Some(State::TakeRest(TakeRest::short(selected, &self.tracker)));
+                },
+                KeyCode::Char('Z') => {
+                    let selected = self.enter_label_mode();
+                    self.label_state = None;
+                    if selected.is_empty() {
+                        continue;
+                    }
+                    self.state = Some(State::TakeRest(TakeRest::long(selected, &mut self.tracker)));
                 },
                 KeyCode::Char('a') => {
                     self.use_action();
@@ -131,26 +344,114 @@ impl<B: Backend> Ui<B> {
                 KeyCode::Char('s') => {
                     self.info_block.toggle();
                 },
+                KeyCode::Char('l') => {
+                    self.info_block.toggle_log();
+                },
+                KeyCode::Char('w') => {
+                    self.state = Some(State::SaveEncounter(SaveEncounter::new()));
+                },
+                KeyCode::Char('W') => {
+                    self.state = Some(State::SaveEncounter(SaveEncounter::session()));
+                },
+                KeyCode::Char('n') if self.initiative_mode == InitiativeMode::Popcorn => {
+                    // the current combatant picks who goes next
+                    let selected = self.enter_label_mode();
+                    self.label_state = None;
+                    if let [combatant] = selected[..] {
+                        self.pass_turn(combatant);
+                    }
+                },
                 KeyCode::Char('n') => {
                     self.next_turn();
                 },
                 KeyCode::Char('N') => {
-                    // skip all dead combatants
-                    loop {
+                    // skip all dead combatants, stopping after a full lap if everyone is down
+                    for _ in 0..self.combatants.len() {
                         self.next_turn();
                         if self.current_combatant().hit_points > 0 {
                             break;
                         }
                     }
                 },
+                KeyCode::Char('e') => {
+                    self.state = Some(State::Summary(Summary::new(&self.tracker)));
+                },
+                KeyCode::Char('v') => {
+                    self.state = Some(State::Stats(Stats::new(&self.tracker)));
+                },
+                KeyCode::Char('P') => self.player_view = !self.player_view,
+                KeyCode::Char('I') => {
+                    // only one combatant can be given an alias at a time
+                    let selected = self.enter_label_mode();
+                    let [combatant] = selected[..] else {
+                        self.label_state = None;
+                        continue;
+                    };
+                    self.state = Some(State::Rename(Rename::alias(combatant, &self.tracker)));
+                },
+                KeyCode::Char('V') => {
+                    let selected = self.enter_label_mode();
+                    if selected.is_empty() {
+                        self.label_state = None;
+                        continue;
+                    }
+                    self.state = Some(State::RevealConditions(
+                        RevealConditions::new(selected, &self.tracker),
+                    ));
+                },
+                KeyCode::Char('o') => {
+                    let selected = self.enter_label_mode();
+                    if selected.is_empty() {
+                        self.label_state = None;
+                        continue;
+                    }
+                    self.state = Some(State::SetSide(SetSide::new(selected, &self.tracker)));
+                },
                 KeyCode::Char('q') => break,
                 _ => (),
             }
+            self.check_combat_over();
+        }
+    }
+
+    /// Handle any requests waiting on the control socket, then share the player view with any
+    /// connected terminals. Returns `true` if a request was handled.
+    fn serve(&mut self) -> bool {
+        let served = self.control
+            .as_mut()
+            .is_some_and(|control| control.serve(&mut self.tracker));
+        if let Some(share) = &mut self.share {
+            share.update(&self.tracker.player_view());
+        }
+        served
+    }
+
+    /// Wait for the next event, and return it if it's a key press. While sharing or controlled,
+    /// this only waits for [`POLL_INTERVAL`], to keep serving the others between keys.
+    fn next_key(&self) -> Option<KeyEvent> {
+        let waiting = self.share.is_some() || self.control.is_some();
+        if waiting && !poll(POLL_INTERVAL).unwrap_or(false) {
+            return None;
+        }
+        match read() {
+            Ok(Event::Key(key)) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Open the summary if combat has just ended. If combat starts again, such as when a fallen
+    /// combatant is healed, the summary opens again the next time it ends.
+    fn check_combat_over(&mut self) {
+        if !self.is_over() {
+            self.summary_shown = false;
+        } else if !self.summary_shown && self.state.is_none() {
+            self.summary_shown = true;
+            self.state = Some(State::Summary(Summary::new(&self.tracker)));
         }
     }
 
     /// Draw the tracker to the terminal.
-    pub fn draw(&mut self) -> std::io::Result<ratatui::CompletedFrame> {
+    pub fn draw(&mut self) -> std::io::Result<ratatui::CompletedFrame<'_>> {
         self.terminal.draw(|frame| {
             // clear the area
             frame.render_widget(
@@ -160,6 +461,15 @@ impl<B: Backend> Ui<B> {
                 frame.area(),
             );
 
+            // label mode needs the tracker's labels
+            if self.player_view && self.label_state.is_none() {
+                frame.render_widget(PlayerView::new(&self.tracker.player_view()), frame.area());
+                if let Some(state) = self.state.as_ref() {
+                    state.draw(frame);
+                }
+                return;
+            }
+
             let [tracker_area, info_area] = Layout::horizontal([
                 Constraint::Percentage(50),
                 Constraint::Percentage(50),
@@ -174,13 +484,16 @@ impl<B: Backend> Ui<B> {
             frame.render_widget(tracker_widget, tracker_area);
 
             let combatant = self.tracker.current_combatant();
-            if self.info_block == InfoBlock::StatBlock {
+            match (combatant.active_kind(), self.info_block) {
                 // show stat block in place of the combatant card
-                let CombatantKind::Monster(monster) = &combatant.kind;
-                frame.render_widget(StatBlock::new(monster), info_area);
-            } else {
-                // show combatant card
-                frame.render_widget(CombatantBlock::new(combatant), info_area);
+                // players don't have stat blocks, so they always show the combatant card
+                (ActiveKind::Monster(monster), InfoBlock::StatBlock) => {
+                    frame.render_widget(StatBlock::new(monster), info_area);
+                },
+                (_, InfoBlock::Log) => {
+                    frame.render_widget(CombatLog::new(&self.tracker.log), info_area);
+                },
+                _ => frame.render_widget(CombatantBlock::new(combatant), info_area),
             }
 
             let Some(state) = self.state.as_ref() else {
@@ -200,7 +513,7 @@ impl<B: Backend> Ui<B> {
     /// returning mutable references to the selected combatants.
     pub fn enter_label_mode(&mut self) -> Vec<usize> {
         let size = self.terminal.size().unwrap();
-        let num_combatants_in_view = max_combatants(size).min(self.combatants.len());
+        let num_combatants_in_view = max_combatants(&self.tracker, size).min(self.combatants.len());
 
         // generate labels for all combatants in view
         let label_to_combatant_idx = (0..num_combatants_in_view)
@@ -208,6 +521,21 @@ impl<B: Backend> Ui<B> {
             .map(|i| (LABELS.chars().nth(i).unwrap(), i))
             .collect::<BiMap<_, _>>();
 
+        // select the given combatants in view, or deselect them if they are all selected already
+        let toggle_all = |selected: &mut HashSet<char>, combatants: Vec<usize>| {
+            let labels = combatants
+                .into_iter()
+                .filter_map(|i| label_to_combatant_idx.get_by_right(&i).copied())
+                .collect::<Vec<_>>();
+            if labels.iter().all(|label| selected.contains(label)) {
+                for label in labels {
+                    selected.remove(&label);
+                }
+            } else {
+                selected.extend(labels);
+            }
+        };
+
         // watch for user-input and select combatants
         let mut selected_labels = HashSet::new();
         loop {
@@ -216,22 +544,38 @@ impl<B: Backend> Ui<B> {
                 labels: label_to_combatant_idx.clone(),
                 selected: selected_labels.clone(),
             });
+            self.serve();
             self.draw().unwrap();
 
-            // wait for user input
-            if let Ok(Event::Key(key)) = read() {
+            // wait for user input, while still serving the control socket and shared view
+            if let Some(key) = self.next_key() {
                 match key.code {
                     KeyCode::Esc => return vec![],
                     KeyCode::Enter => break,
-                    KeyCode::Char(label) => {
-                        if label_to_combatant_idx.contains_left(&label) {
-                            if selected_labels.contains(&label) {
-                                selected_labels.remove(&label);
-                            } else {
-                                selected_labels.insert(label);
-                            }
+                    KeyCode::Char(label) if label_to_combatant_idx.contains_left(&label) => {
+                        if selected_labels.contains(&label) {
+                            selected_labels.remove(&label);
+                        } else {
+                            selected_labels.insert(label);
                         }
                     },
+                    // select or deselect everyone on the current combatant's side, or hostile to it
+                    KeyCode::Char(key @ ('+' | '-')) => {
+                        let combatants = if key == '+' {
+                            self.tracker.allies(self.tracker.turn)
+                        } else {
+                            self.tracker.enemies(self.tracker.turn)
+                        };
+                        toggle_all(&mut selected_labels, combatants);
+                    },
+                    // shift and a minion's label toggles its whole group, such as to remove it
+                    KeyCode::Char(upper) if upper.is_ascii_uppercase() => {
+                        let label = upper.to_ascii_lowercase();
+                        let Some(&i) = label_to_combatant_idx.get_by_left(&label) else {
+                            continue;
+                        };
+                        toggle_all(&mut selected_labels, self.tracker.minion_group(i));
+                    },
                     _ => (),
                 }
             }
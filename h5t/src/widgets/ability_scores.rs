@@ -2,8 +2,8 @@ use crate::theme::THEME;
 use h5t_core::{
     ability::{Modifier as AbilityModifier, Score},
     Ability,
+    ActiveKind,
     Combatant,
-    CombatantKind,
     Monster,
     score_to_modifier,
 };
@@ -25,8 +25,12 @@ pub struct AbilityScores {
 impl AbilityScores {
     /// Create a new [`AbilityScores`] widget from a [`Combatant`].
     pub fn new(combatant: &Combatant) -> Self {
-        match &combatant.kind {
-            CombatantKind::Monster(monster) => Self::from(monster),
+        match combatant.active_kind() {
+            ActiveKind::Monster(monster) => Self::from(monster),
+            ActiveKind::Player(player) => Self {
+                scores: player.scores,
+                proficiencies: Ability::default(),
+            },
         }
     }
 }
@@ -60,7 +64,7 @@ impl Widget for AbilityScores {
         );
 
         /// Helper to build a row for the ability scores table.
-        fn row(odd: bool, ability: &str, score: i32, save: Option<i32>) -> Row {
+        fn row(odd: bool, ability: &str, score: i32, save: Option<i32>) -> Row<'_> {
             // more green for high scores, more red for low scores
             // 0: (255, 0, 0)
             // 10: (255, 255, 255)
@@ -0,0 +1,42 @@
+use h5t_core::LogEntry;
+use ratatui::{prelude::*, widgets::*};
+use crate::theme::THEME;
+
+/// A widget for displaying the combat log, newest entries at the bottom.
+#[derive(Debug)]
+pub struct CombatLog<'a> {
+    /// The log entries to display.
+    log: &'a [LogEntry],
+}
+
+impl<'a> CombatLog<'a> {
+    /// Create a new [`CombatLog`] widget.
+    pub fn new(log: &'a [LogEntry]) -> Self {
+        Self { log }
+    }
+}
+
+impl Widget for CombatLog<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(THEME.foreground)
+            .title("Combat Log")
+            .padding(Padding::horizontal(1));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        // only show the newest entries that fit
+        let skip = self.log.len().saturating_sub(inner.height as usize);
+        let lines = self.log[skip..]
+            .iter()
+            .map(|entry| Line::from(vec![
+                Span::styled(format!("R{} ", entry.round + 1), Modifier::BOLD),
+                Span::raw(&entry.message),
+            ]))
+            .collect::<Vec<_>>();
+        Paragraph::new(lines)
+            .fg(THEME.foreground)
+            .render(inner, buf);
+    }
+}
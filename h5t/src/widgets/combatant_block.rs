@@ -1,52 +1,61 @@
-use h5t_core::{monster::Speed, Combatant};
+use h5t_core::{Combatant, Side};
 use ratatui::{prelude::*, widgets::*};
 use crate::{theme::THEME, widgets::conditions::FullConditions};
 
 use super::{AbilityScores, HitPoints};
 
-/// Creates a [`Text`] widget for displaying the combatant's name and whether they are dead.
-fn basic_status_text(combatant: &Combatant) -> Text {
-    if combatant.hit_points <= 0 {
+/// Creates a [`Text`] widget for displaying the combatant's name, whether they are dead, which form
+/// they are in, the members of its mob, and whether they are delaying or have readied an action.
+fn basic_status_text(combatant: &Combatant) -> Text<'_> {
+    let mut text = if combatant.hit_points <= 0 {
         Text::styled(format!("{} (Dead)", combatant.name()), Modifier::BOLD)
     } else {
         Text::styled(combatant.name(), Modifier::BOLD)
-    }.fg(THEME.foreground)
-}
-
-/// Creates a [`Table`] widget for displaying a monster's basic statistics.
-fn basic_stats_table(combatant: &Combatant) -> Table {
-    /// Format's a speed value.
-    fn fmt_speed(speed: &Speed) -> String {
-        let mut parts = String::new();
-        if let Some(speed) = &speed.walk {
-            parts.push_str(speed);
-            parts.push_str(", ");
-        }
-        if let Some(speed) = &speed.burrow {
-            parts.push_str("burrow ");
-            parts.push_str(speed);
-            parts.push_str(", ");
-        }
-        if let Some(speed) = &speed.climb {
-            parts.push_str("climb ");
-            parts.push_str(speed);
-            parts.push_str(", ");
-        }
-        if let Some(speed) = &speed.fly {
-            parts.push_str("fly ");
-            parts.push_str(speed);
-            parts.push_str(", ");
-        }
-        if let Some(speed) = &speed.swim {
-            parts.push_str("swim ");
-            parts.push_str(speed);
-            parts.push_str(", ");
+    };
+    if let Some(form) = &combatant.form {
+        text.push_line(Line::styled(
+            format!("In the form of {}", form.monster.name),
+            Modifier::ITALIC,
+        ));
+    }
+    if let Some(mob) = &combatant.mob {
+        text.push_line(Line::styled(
+            format!("{} of {} members standing", mob.alive(), mob.members.len()),
+            Modifier::ITALIC,
+        ));
+        for (i, member) in mob.members.iter().enumerate() {
+            let mut line = Line::raw(format!(
+                "#{} {}/{} HP",
+                i + 1,
+                member.hit_points.max(0),
+                mob.member_max_hit_points,
+            ));
+            for condition in &member.conditions {
+                line.push_span(Span::styled(format!(" {}", condition.kind), THEME.warning));
+            }
+            if !member.is_alive() {
+                line = line.fg(THEME.foreground.mix(THEME.background));
+            }
+            text.push_line(line);
         }
-        parts.pop(); // remove trailing comma
-        parts.pop(); // remove trailing space
-        parts
     }
+    if combatant.side != Side::default_for(&combatant.kind) {
+        text.push_line(Line::styled(format!("Fights for: {}", combatant.side), Modifier::ITALIC));
+    }
+    if let Some(group) = &combatant.minion {
+        text.push_line(Line::styled(format!("Minion ({})", group), Modifier::ITALIC));
+    }
+    if combatant.delayed {
+        text.push_line(Line::styled("Delaying their turn", Modifier::ITALIC));
+    }
+    if let Some(trigger) = &combatant.readied {
+        text.push_line(Line::styled(format!("Readied until: {}", trigger), Modifier::ITALIC));
+    }
+    text.fg(THEME.foreground)
+}
 
+/// Creates a [`Table`] widget for displaying a monster's basic statistics.
+fn basic_stats_table(combatant: &Combatant) -> Table<'_> {
     Table::new(
         vec![
             Row::new(vec![
@@ -59,7 +68,7 @@ fn basic_stats_table(combatant: &Combatant) -> Table {
             ]),
             Row::new(vec![
                 Text::styled("Speed", Modifier::BOLD),
-                Text::raw(fmt_speed(combatant.speed())),
+                Text::raw(combatant.speed().to_string()),
             ]),
             Row::new(vec![
                 Text::styled("Proficiency Bonus", Modifier::BOLD),
@@ -74,6 +83,38 @@ fn basic_stats_table(combatant: &Combatant) -> Table {
         .fg(THEME.foreground)
 }
 
+/// Creates a [`Text`] widget for displaying the resources the combatant regains by resting: hit
+/// dice, limited-use traits, spell slots, and exhaustion.
+fn rest_resources_text(combatant: &Combatant) -> Text<'_> {
+    /// Builds a line with a bold label and the remaining count out of the maximum.
+    fn resource_line(label: String, remaining: u32, max: u32) -> Line<'static> {
+        Line::from(vec![
+            Span::styled(label, Modifier::BOLD),
+            Span::raw(format!(" {}/{}", remaining, max)),
+        ])
+    }
+
+    let mut text = Text::default();
+    if let Some((count, sides)) = combatant.hit_dice() {
+        let remaining = count.saturating_sub(combatant.hit_dice_spent);
+        text.push_line(resource_line(format!("Hit Dice (d{})", sides), remaining, count));
+    }
+    for ability in &combatant.limited_uses {
+        text.push_line(resource_line(ability.name.clone(), ability.remaining(), ability.max));
+    }
+    for slots in &combatant.spell_slots {
+        let label = format!("Level {} Slots", slots.level);
+        text.push_line(resource_line(label, slots.remaining(), slots.max));
+    }
+    if combatant.exhaustion > 0 {
+        text.push_line(Line::styled(
+            format!("Exhaustion level {}", combatant.exhaustion),
+            THEME.warning,
+        ));
+    }
+    text.fg(THEME.foreground)
+}
+
 /// A widget similar to [`StatBlock`] that displays relevant combat information.
 ///
 /// [`StatBlock`]: crate::widgets::StatBlock
@@ -99,14 +140,18 @@ impl Widget for CombatantBlock<'_> {
             .title("Combatant Block")
             .render(area, buf);
 
+        let status = basic_status_text(self.combatant);
+        let resources = rest_resources_text(self.combatant);
         let [
             name,
             basic_stats,
+            rest_resources,
             conditions,
             ability_scores,
         ] = Layout::vertical([
-            Constraint::Length(1), // name
+            Constraint::Length(status.height() as u16), // name and status
             Constraint::Length(4), // basic stats
+            Constraint::Length(resources.height() as u16), // hit dice, uses, and slots
             Constraint::Fill(1),   // conditions
             Constraint::Length(7), // ability scores
         ])
@@ -115,8 +160,9 @@ impl Widget for CombatantBlock<'_> {
             .spacing(1)
             .areas(area);
 
-        basic_status_text(self.combatant).render(name, buf);
+        status.render(name, buf);
         Widget::render(basic_stats_table(self.combatant), basic_stats, buf);
+        resources.render(rest_resources, buf);
         FullConditions::new(self.combatant).render(conditions, buf);
         AbilityScores::new(self.combatant).render(ability_scores, buf);
     }
@@ -4,7 +4,7 @@ use itertools::Itertools;
 use ratatui::{prelude::*, widgets::*};
 
 /// Returns a unique color for the condition.
-fn condition_color(kind: ConditionKind) -> Color {
+pub(crate) fn condition_color(kind: ConditionKind) -> Color {
     match kind {
         ConditionKind::Blinded => Color::White,
         ConditionKind::Charmed => Color::Magenta,
@@ -54,11 +54,11 @@ impl<'a> CompactConditions<'a> {
             )
         }
 
-        let conditions = self
+        let spans = self
             .current
             .iter()
-            .map(make_span)
-            .intersperse(Span::raw(","))
+            .map(make_span);
+        let conditions = Itertools::intersperse(spans, Span::raw(","))
             .collect::<Vec<_>>();
         Line::from(conditions)
             .fg(THEME.foreground)
@@ -16,7 +16,7 @@ impl HitPoints {
     pub fn new(combatant: &Combatant) -> Self {
         Self {
             current: combatant.hit_points,
-            max: combatant.max_hit_points(),
+            max: combatant.max_hit_points,
         }
     }
 
@@ -1,15 +1,19 @@
 pub mod ability_scores;
+pub mod combat_log;
 pub mod combatant_block;
 pub mod conditions;
 pub mod hit_points;
+pub mod player_view;
 pub mod popup;
 pub mod stat_block;
 pub mod tracker;
 
 pub use ability_scores::AbilityScores;
+pub use combat_log::CombatLog;
 pub use combatant_block::CombatantBlock;
 pub use conditions::CompactConditions;
 pub use hit_points::HitPoints;
+pub use player_view::PlayerView;
 pub use stat_block::StatBlock;
 pub use tracker::Tracker;
 
@@ -0,0 +1,90 @@
+use crate::{theme::THEME, widgets::{conditions::condition_color, HitPoints}};
+use h5t_core::{player_view::Health, PlayerView as CorePlayerView};
+use ratatui::{prelude::*, widgets::*};
+
+/// Creates a [`Line`] widget describing a combatant's health.
+fn health_line(health: Health) -> Line<'static> {
+    let color = match health {
+        Health::HitPoints { current, max } => return HitPoints { current, max }.line(),
+        Health::Healthy => THEME.foreground,
+        Health::Bloodied => THEME.warning,
+        Health::NearDeath => THEME.error,
+        Health::Down => THEME.dead,
+    };
+    Line::styled(health.to_string(), color)
+}
+
+/// A widget to render the fight as the players see it, for a screen facing the players.
+#[derive(Debug)]
+pub struct PlayerView<'a> {
+    /// The player view to display.
+    pub view: &'a CorePlayerView,
+}
+
+impl<'a> PlayerView<'a> {
+    /// Create a new [`PlayerView`] widget.
+    pub fn new(view: &'a CorePlayerView) -> Self {
+        Self { view }
+    }
+}
+
+impl Widget for PlayerView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        Block::bordered()
+            .border_type(BorderType::Rounded)
+            .border_style(THEME.foreground)
+            .title("Initiative")
+            .render(area, buf);
+
+        let [round, combatants] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+        ])
+            .horizontal_margin(2)
+            .vertical_margin(1) // avoid the border
+            .spacing(1)
+            .areas(area);
+
+        Line::styled(format!("Round: {}", self.view.round + 1), Modifier::BOLD)
+            .style(THEME.foreground)
+            .render(round, buf);
+
+        let rows = self.view.combatants.iter().enumerate().map(|(i, combatant)| {
+            let mut name = Line::raw(combatant.name.as_str());
+            if combatant.delayed {
+                name.push_span(Span::raw(" (delayed)").italic());
+            }
+            let conditions = combatant.conditions
+                .iter()
+                .map(|&kind| Span::styled(format!("{} ", kind), condition_color(kind)))
+                .collect::<Line>();
+
+            let mut style = Style::default().fg(THEME.foreground.into());
+            if combatant.delayed {
+                style = style.fg(THEME.foreground.mix(THEME.background).into());
+            }
+            let bg_color = if i == self.view.turn { THEME.primary } else { THEME.background };
+            Row::new([
+                name.into(),
+                health_line(combatant.health).into(),
+                Text::from(conditions),
+            ])
+                .style(style.bg(bg_color.into()))
+        });
+        let table = Table::new(rows, [
+            Constraint::Fill(2), // name
+            Constraint::Fill(1), // health
+            Constraint::Fill(2), // conditions
+        ])
+            .header(
+                Row::new([
+                    Text::from("Name").centered(),
+                    Text::from("Health").centered(),
+                    Text::from("Conditions").centered(),
+                ])
+                    .style(THEME.foreground)
+                    .bold()
+            );
+        Widget::render(table, combatants, buf);
+    }
+}
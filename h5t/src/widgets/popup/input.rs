@@ -102,7 +102,7 @@ impl Widget for Input<'_> {
         );
 
         let (color, theme) = if self.active {
-            (self.color, THEME)
+            (self.color, *THEME)
         } else {
             (self.color.mix(THEME.background), THEME.dim())
         };
@@ -56,7 +56,7 @@ impl<T: Selectable> Widget for Multiselect<'_, T> {
         );
 
         let theme = if self.active {
-            THEME
+            *THEME
         } else {
             THEME.dim()
         };
@@ -53,7 +53,7 @@ impl<T: Selectable> Widget for Select<'_, T> {
         );
 
         let theme = if self.active {
-            THEME
+            *THEME
         } else {
             THEME.dim()
         };
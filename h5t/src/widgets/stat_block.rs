@@ -1,35 +1,10 @@
 use crate::theme::THEME;
-use h5t_core::{monster::{Size, Speed, Type, Usage}, Monster};
+use h5t_core::{monster::Usage, ChallengeRating, Monster};
 use ratatui::{prelude::*, widgets::*};
 use super::AbilityScores;
 
 /// Creates a [`Paragraph`] widget for displaying the monster's name and type.
-fn name_and_type_paragraph(monster: &Monster) -> Paragraph {
-    let size = match monster.size {
-        Size::Tiny => "Tiny",
-        Size::Small => "Small",
-        Size::Medium => "Medium",
-        Size::Large => "Large",
-        Size::Huge => "Huge",
-        Size::Gargantuan => "Gargantuan",
-    };
-    let r#type = match monster.r#type {
-        Type::Aberration => "Aberration",
-        Type::Beast => "Beast",
-        Type::Celestial => "Celestial",
-        Type::Construct => "Construct",
-        Type::Dragon => "Dragon",
-        Type::Elemental => "Elemental",
-        Type::Fey => "Fey",
-        Type::Fiend => "Fiend",
-        Type::Giant => "Giant",
-        Type::Humanoid => "Humanoid",
-        Type::Monstrosity => "Monstrosity",
-        Type::Ooze => "Ooze",
-        Type::Plant => "Plant",
-        Type::Undead => "Undead",
-        Type::Other => "Other",
-    };
+fn name_and_type_paragraph(monster: &Monster) -> Paragraph<'_> {
     let subtype = if let Some(subtype) = &monster.subtype {
         format!(" ({})", subtype)
     } else {
@@ -39,9 +14,9 @@ fn name_and_type_paragraph(monster: &Monster) -> Paragraph {
     Paragraph::new(vec![
         Span::styled(&monster.name, Modifier::BOLD).into(),
         Line::from(vec![
-            Span::raw(size),
+            Span::raw(monster.size.to_string()),
             Span::raw(" "),
-            Span::raw(r#type),
+            Span::raw(monster.r#type.to_string()),
             Span::raw(", "),
             Span::raw(&monster.alignment),
             Span::raw(subtype),
@@ -50,53 +25,29 @@ fn name_and_type_paragraph(monster: &Monster) -> Paragraph {
         .fg(THEME.foreground)
 }
 
-/// Creates a [`Table`] widget for displaying a monster's basic statistics.
-fn basic_stats_table(monster: &Monster) -> Table {
-    /// Format's a speed value.
-    fn fmt_speed(speed: &Speed) -> String {
-        let mut parts = String::new();
-        if let Some(speed) = &speed.walk {
-            parts.push_str(speed);
-            parts.push_str(", ");
-        }
-        if let Some(speed) = &speed.burrow {
-            parts.push_str("burrow ");
-            parts.push_str(speed);
-            parts.push_str(", ");
-        }
-        if let Some(speed) = &speed.climb {
-            parts.push_str("climb ");
-            parts.push_str(speed);
-            parts.push_str(", ");
-        }
-        if let Some(speed) = &speed.fly {
-            parts.push_str("fly ");
-            parts.push_str(speed);
-            parts.push_str(", ");
-        }
-        if let Some(speed) = &speed.swim {
-            parts.push_str("swim ");
-            parts.push_str(speed);
-            parts.push_str(", ");
-        }
-        parts.pop(); // remove trailing comma
-        parts.pop(); // remove trailing space
-        parts
-    }
-
-    /// Formats a challenge rating.
-    fn fmt_cr(cr: f32, xp: i32) -> String {
-        let cr_value = if cr == 0.0 {
-            "0".to_string()
-        } else if cr < 1.0 {
-            format!("1/{}", 1.0 / cr)
-        } else {
-            cr.to_string()
-        };
+/// Formats a challenge rating.
+pub(crate) fn fmt_cr(cr: ChallengeRating, xp: i32) -> String {
+    format!("{} ({} XP)", cr, xp)
+}
 
-        format!("{} ({} XP)", cr_value, xp)
+/// Formats the usage constraint that follows a trait's name, including the period that ends the
+/// name.
+pub(crate) fn fmt_usage(usage: &Usage) -> String {
+    match usage {
+        Usage::PerDay(count) => format!(" ({}/Day). ", count),
+        Usage::RechargeAfterRest => " (Recharges after a Short or Long Rest). ".to_string(),
+        Usage::RechargeAfterLongRest => " (Recharges after a Long Rest). ".to_string(),
+        Usage::AtWill => ". ".to_string(),
     }
+}
+
+/// Formats a monster's languages, using a dash if it knows none, as printed stat blocks do.
+pub(crate) fn fmt_languages(languages: &str) -> &str {
+    if languages.is_empty() { "—" } else { languages }
+}
 
+/// Creates a [`Table`] widget for displaying a monster's basic statistics.
+fn basic_stats_table(monster: &Monster) -> Table<'_> {
     Table::new(
         vec![
             Row::new(vec![
@@ -109,7 +60,15 @@ fn basic_stats_table(monster: &Monster) -> Table {
             ]),
             Row::new(vec![
                 Text::styled("Speed", Modifier::BOLD),
-                Text::raw(fmt_speed(&monster.speed)),
+                Text::raw(monster.speed.to_string()),
+            ]),
+            Row::new(vec![
+                Text::styled("Senses", Modifier::BOLD),
+                Text::raw(monster.senses.to_string()),
+            ]),
+            Row::new(vec![
+                Text::styled("Languages", Modifier::BOLD),
+                Text::raw(fmt_languages(&monster.languages)),
             ]),
             Row::new(vec![
                 Text::styled("Challenge", Modifier::BOLD),
@@ -128,27 +87,30 @@ fn basic_stats_table(monster: &Monster) -> Table {
         .fg(THEME.foreground)
 }
 
-/// Creates a [`Paragraph`] widget for displaying a monster's traits.
-fn traits_paragraph(monster: &Monster) -> Paragraph {
+/// Creates a [`Paragraph`] widget for displaying a monster's traits and actions.
+fn traits_paragraph(monster: &Monster) -> Paragraph<'_> {
     use itertools::Itertools;
 
-    let text = monster
+    let traits = monster
         .traits
         .iter()
         .map(|ability| {
-            let constraint = match ability.usage {
-                Usage::PerDay(count) => format!(" ({}/Day). ", count),
-                Usage::RechargeAfterRest => " (Recharges after a Short or Long Rest). ".to_string(),
-                Usage::RechargeAfterLongRest => " (Recharges after a Long Rest). ".to_string(),
-                Usage::AtWill => ". ".to_string(),
-            };
             Line::from(vec![
                 Span::styled(&ability.name, Modifier::BOLD | Modifier::ITALIC),
-                Span::styled(constraint, Modifier::BOLD | Modifier::ITALIC),
+                Span::styled(fmt_usage(&ability.usage), Modifier::BOLD | Modifier::ITALIC),
                 Span::raw(&ability.desc),
             ])
-        })
-        .intersperse(Line::raw(""))
+        });
+    let actions = (!monster.actions.is_empty())
+        .then(|| Line::styled("Actions", Modifier::BOLD))
+        .into_iter()
+        .chain(monster.actions.iter().map(|action| {
+            Line::from(vec![
+                Span::styled(format!("{}. ", action.name), Modifier::BOLD | Modifier::ITALIC),
+                Span::raw(&action.desc),
+            ])
+        }));
+    let text = Itertools::intersperse(traits.chain(actions), Line::raw(""))
         .collect::<Vec<_>>();
     Paragraph::new(text)
         .fg(THEME.foreground)
@@ -185,7 +147,7 @@ impl Widget for StatBlock<'_> {
             traits
         ] = Layout::vertical([
             Constraint::Length(2), // name and type
-            Constraint::Length(5), // basic stats
+            Constraint::Length(7), // basic stats
             Constraint::Length(7), // ability scores
             Constraint::Min(1),    // traits
         ])
@@ -1,5 +1,12 @@
 use crate::{theme::THEME, ui::LabelModeState, widgets::{CompactConditions, HitPoints}};
-use h5t_core::{Resources, Combatant, Tracker as CoreTracker};
+use h5t_core::{
+    Difficulty,
+    EncounterDifficulty,
+    InitiativeMode,
+    Resources,
+    Combatant,
+    Tracker as CoreTracker,
+};
 use ratatui::{prelude::*, widgets::*};
 
 /// Creates a [`Line`] widget for displaying the character's action count.
@@ -30,16 +37,66 @@ fn action_line(actions: Resources) -> Line<'static> {
     Line::from(spans)
 }
 
+/// Creates a [`Line`] widget for displaying the encounter's difficulty rating.
+fn difficulty_line(difficulty: EncounterDifficulty) -> Line<'static> {
+    let color = match difficulty.difficulty {
+        Difficulty::Trivial | Difficulty::Easy => THEME.foreground,
+        Difficulty::Medium => THEME.action,
+        Difficulty::Hard => THEME.warning,
+        Difficulty::Deadly => THEME.error,
+    };
+
+    Line::from(vec![
+        Span::styled("Difficulty: ", Modifier::BOLD),
+        Span::styled(difficulty.difficulty.to_string(), color).bold(),
+        Span::raw(format!(" ({} XP)", difficulty.adjusted_xp)),
+    ])
+}
+
+/// Creates a [`Line`] widget listing the surprised combatants, or [`None`] if no one is surprised.
+fn surprised_line(tracker: &CoreTracker) -> Option<Line<'_>> {
+    use itertools::Itertools;
+
+    // surprise only matters in the first round
+    if tracker.round > 0 {
+        return None;
+    }
+
+    let names = tracker.combatants
+        .iter()
+        .filter(|combatant| combatant.surprised)
+        .map(Combatant::name);
+    let names = Itertools::intersperse(names, ", ").collect::<String>();
+    if names.is_empty() {
+        return None;
+    }
+
+    Some(Line::from(vec![
+        Span::styled("Surprised: ", Modifier::BOLD),
+        Span::styled(names, THEME.warning),
+    ]))
+}
+
 /// Creates a [`Table`] widget for displaying the combatants in the tracker.
 fn combatant_table<'a>(widget: &'a Tracker) -> Table<'a> {
     /// Builds a table [`Row`] for a combatant.
-    fn combatant_row(label: Option<char>, combatant: &Combatant) -> Row {
+    fn combatant_row(label: Option<char>, combatant: &Combatant) -> Row<'_> {
         let label_text = label
             .map(|l| Text::from(format!("{}", l)).bold())
             .unwrap_or_default();
+        let mut name = if combatant.delayed {
+            Line::from(vec![Span::raw(combatant.name()), Span::raw(" (delayed)").italic()])
+        } else if combatant.readied.is_some() {
+            Line::from(vec![Span::raw(combatant.name()), Span::raw(" (readied)").italic()])
+        } else {
+            Line::raw(combatant.name())
+        };
+        if let Some(mob) = &combatant.mob {
+            name.push_span(Span::raw(format!(" ×{} alive", mob.alive())));
+        }
         Row::new([
             label_text,
-            Text::from(combatant.name()),
+            name.into(),
             action_line(combatant.resources).into(),
             HitPoints::new(combatant).line().into(),
             CompactConditions::new(combatant).line().into(),
@@ -59,6 +116,11 @@ fn combatant_table<'a>(widget: &'a Tracker) -> Table<'a> {
                 if is_label_selected {
                     style = style.bold();
                 }
+                // in popcorn initiative, dim those who can't be picked to go next
+                let popcorn = widget.tracker.initiative_mode == InitiativeMode::Popcorn;
+                if combatant.delayed || popcorn && combatant.acted && !is_current_turn {
+                    style = style.fg(THEME.foreground.mix(THEME.background).into());
+                }
 
                 let mut bg_color = None;
                 if combatant.hit_points <= 0 {
@@ -125,10 +187,34 @@ impl<'a> Tracker<'a> {
     }
 }
 
+/// Returns the lines shown above the combatants: the round, the turn, the encounter's difficulty,
+/// and who is surprised.
+fn header_lines(tracker: &CoreTracker) -> Vec<Line<'_>> {
+    let mut round = Line::styled(format!("Round: {}", tracker.round + 1), Modifier::BOLD);
+    if tracker.initiative_mode != InitiativeMode::Individual {
+        round.push_span(Span::raw(format!(" ({} initiative)", tracker.initiative_mode)));
+    }
+    let mut lines = vec![
+        round,
+        Line::styled(
+            format!("Turn: {}/{}", tracker.turn + 1, tracker.combatants.len()),
+            Modifier::BOLD
+        ),
+    ];
+    if let Some(difficulty) = tracker.difficulty() {
+        lines.push(difficulty_line(difficulty));
+    }
+    if let Some(surprised) = surprised_line(tracker) {
+        lines.push(surprised);
+    }
+    lines
+}
+
 /// Returns the maximum number of combatants that can be displayed in the tracker widget, given the
 /// size of the widget.
-pub(crate) fn max_combatants(size: Size) -> usize {
-    size.height as usize - 6 // 2 for upper and lower borders, 4 for header, spacing, etc.
+pub(crate) fn max_combatants(tracker: &CoreTracker, size: Size) -> usize {
+    // 2 for the upper and lower borders, 1 for spacing, and 1 for the table header
+    (size.height as usize).saturating_sub(header_lines(tracker).len() + 4)
 }
 
 impl Widget for Tracker<'_> {
@@ -140,8 +226,10 @@ impl Widget for Tracker<'_> {
             .title("Initiative Tracker")
             .render(area, buf);
 
+        let text = header_lines(self.tracker);
         let [round_and_turn, combatants] = Layout::vertical([
-            Constraint::Length(2), // round and turn
+            // round, turn, difficulty, and surprise
+            Constraint::Length(text.len() as u16),
             Constraint::Fill(1),
         ])
             .horizontal_margin(2)
@@ -149,13 +237,6 @@ impl Widget for Tracker<'_> {
             .spacing(1)
             .areas(area);
 
-        let text = vec![
-            Line::styled(format!("Round: {}", self.tracker.round + 1), Modifier::BOLD),
-            Line::styled(
-                format!("Turn: {}/{}", self.tracker.turn + 1, self.tracker.combatants.len()),
-                Modifier::BOLD
-            ),
-        ];
         Paragraph::new(text)
             .style(THEME.foreground)
             .wrap(Wrap { trim: true })